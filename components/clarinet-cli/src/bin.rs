@@ -9,11 +9,13 @@ extern crate serde_json;
 #[macro_use]
 extern crate hiro_system_kit;
 
+mod contracts;
 mod deployments;
 mod devnet;
 mod frontend;
 mod generate;
 mod lsp;
+mod testing;
 
 use frontend::cli;
 
@@ -0,0 +1,406 @@
+use clarity_repl::clarity::analysis::contract_interface_builder::{
+    ContractInterface, ContractInterfaceAtomType, ContractInterfaceFunction,
+    ContractInterfaceFunctionAccess, ContractInterfaceVariableAccess,
+};
+use clarity_repl::clarity::vm::analysis::types::ContractAnalysis;
+use clarity_repl::clarity::vm::types::FunctionSignature;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    /// Callers, indexers or proxies relying on the previous interface have to be updated
+    Breaking,
+    Additive,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceChange {
+    pub kind: ChangeKind,
+    pub description: String,
+}
+
+impl InterfaceChange {
+    fn breaking(description: String) -> InterfaceChange {
+        InterfaceChange {
+            kind: ChangeKind::Breaking,
+            description,
+        }
+    }
+
+    fn additive(description: String) -> InterfaceChange {
+        InterfaceChange {
+            kind: ChangeKind::Additive,
+            description,
+        }
+    }
+}
+
+/// Clarity notation of a type of the interface, e.g. `(response bool uint)`
+pub fn atom_type_to_string(atom: &ContractInterfaceAtomType) -> String {
+    use ContractInterfaceAtomType::*;
+    match atom {
+        none => "none".to_string(),
+        int128 => "int".to_string(),
+        uint128 => "uint".to_string(),
+        bool => "bool".to_string(),
+        principal => "principal".to_string(),
+        buffer { length } => format!("(buff {})", length),
+        string_ascii { length } => format!("(string-ascii {})", length),
+        string_utf8 { length } => format!("(string-utf8 {})", length),
+        tuple(entries) => {
+            let entries = entries
+                .iter()
+                .map(|entry| format!("{}: {}", entry.name, atom_type_to_string(&entry.type_f)))
+                .collect::<Vec<_>>();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        optional(inner) => format!("(optional {})", atom_type_to_string(inner)),
+        response { ok, error } => format!(
+            "(response {} {})",
+            atom_type_to_string(ok),
+            atom_type_to_string(error)
+        ),
+        list { type_f, length } => format!("(list {} {})", length, atom_type_to_string(type_f)),
+        trait_reference => "<trait>".to_string(),
+    }
+}
+
+fn access_to_string(access: &ContractInterfaceFunctionAccess) -> &'static str {
+    match access {
+        ContractInterfaceFunctionAccess::public => "public",
+        ContractInterfaceFunctionAccess::read_only => "read-only",
+        ContractInterfaceFunctionAccess::private => "private",
+    }
+}
+
+fn function_signature(function: &ContractInterfaceFunction) -> String {
+    let args = function
+        .args
+        .iter()
+        .map(|arg| atom_type_to_string(&arg.type_f))
+        .collect::<Vec<_>>();
+    format!(
+        "({}) -> {}",
+        args.join(" "),
+        atom_type_to_string(&function.outputs.type_f)
+    )
+}
+
+fn trait_signature(signature: &FunctionSignature) -> String {
+    let args = signature
+        .args
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>();
+    format!("({}) -> {}", args.join(" "), signature.returns)
+}
+
+fn diff_functions(
+    previous: &ContractInterface,
+    current: &ContractInterface,
+    changes: &mut Vec<InterfaceChange>,
+) {
+    // Private functions can't be called from other contracts
+    let is_callable = |f: &&ContractInterfaceFunction| {
+        !matches!(f.access, ContractInterfaceFunctionAccess::private)
+    };
+    let previous_functions = previous
+        .functions
+        .iter()
+        .filter(is_callable)
+        .map(|f| (f.name.as_str(), f))
+        .collect::<BTreeMap<_, _>>();
+    let current_functions = current
+        .functions
+        .iter()
+        .filter(is_callable)
+        .map(|f| (f.name.as_str(), f))
+        .collect::<BTreeMap<_, _>>();
+
+    for (name, function) in previous_functions.iter() {
+        let access = access_to_string(&function.access);
+        let current_function = match current_functions.get(name) {
+            Some(current_function) => current_function,
+            None => {
+                changes.push(InterfaceChange::breaking(format!(
+                    "{} function `{}` removed",
+                    access, name
+                )));
+                continue;
+            }
+        };
+        if current_function.access != function.access {
+            changes.push(InterfaceChange::breaking(format!(
+                "function `{}` changed from {} to {}",
+                name,
+                access,
+                access_to_string(&current_function.access)
+            )));
+        }
+        let (signature, current_signature) = (
+            function_signature(function),
+            function_signature(current_function),
+        );
+        if signature != current_signature {
+            changes.push(InterfaceChange::breaking(format!(
+                "signature of {} function `{}` changed from {} to {}",
+                access, name, signature, current_signature
+            )));
+        }
+    }
+    for (name, function) in current_functions.iter() {
+        if !previous_functions.contains_key(name) {
+            changes.push(InterfaceChange::additive(format!(
+                "{} function `{}` added: {}",
+                access_to_string(&function.access),
+                name,
+                function_signature(function)
+            )));
+        }
+    }
+}
+
+type Definitions<'a> = BTreeMap<&'a str, (&'static str, String)>;
+
+fn variables(interface: &ContractInterface) -> Definitions {
+    interface
+        .variables
+        .iter()
+        .map(|variable| {
+            let kind = match variable.access {
+                ContractInterfaceVariableAccess::constant => "constant",
+                ContractInterfaceVariableAccess::variable => "data var",
+            };
+            (
+                variable.name.as_str(),
+                (kind, atom_type_to_string(&variable.type_f)),
+            )
+        })
+        .collect()
+}
+
+fn maps(interface: &ContractInterface) -> Definitions {
+    interface
+        .maps
+        .iter()
+        .map(|map| {
+            let types = format!(
+                "{} => {}",
+                atom_type_to_string(&map.key),
+                atom_type_to_string(&map.value)
+            );
+            (map.name.as_str(), ("map", types))
+        })
+        .collect()
+}
+
+fn tokens(interface: &ContractInterface) -> Definitions {
+    let mut tokens = interface
+        .fungible_tokens
+        .iter()
+        .map(|ft| (ft.name.as_str(), ("fungible token", String::new())))
+        .collect::<Definitions>();
+    for nft in interface.non_fungible_tokens.iter() {
+        tokens.insert(
+            nft.name.as_str(),
+            ("non-fungible token", atom_type_to_string(&nft.type_f)),
+        );
+    }
+    tokens
+}
+
+/// Compare definitions given as `name => (kind, type)`. Removing a definition or
+/// changing its kind or type is breaking, adding one is additive.
+fn diff_definitions(
+    previous: &Definitions,
+    current: &Definitions,
+    changes: &mut Vec<InterfaceChange>,
+) {
+    for (name, (kind, type_f)) in previous.iter() {
+        match current.get(name) {
+            None => changes.push(InterfaceChange::breaking(format!(
+                "{} `{}` removed",
+                kind, name
+            ))),
+            Some((current_kind, _)) if current_kind != kind => {
+                changes.push(InterfaceChange::breaking(format!(
+                    "`{}` changed from {} to {}",
+                    name, kind, current_kind
+                )))
+            }
+            Some((_, current_type)) if current_type != type_f => {
+                changes.push(InterfaceChange::breaking(format!(
+                    "type of {} `{}` changed from {} to {}",
+                    kind, name, type_f, current_type
+                )))
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, (kind, type_f)) in current.iter() {
+        if !previous.contains_key(name) {
+            let description = match type_f.is_empty() {
+                true => format!("{} `{}` added", kind, name),
+                false => format!("{} `{}` added: {}", kind, name, type_f),
+            };
+            changes.push(InterfaceChange::additive(description));
+        }
+    }
+}
+
+fn diff_traits(
+    previous: &ContractAnalysis,
+    current: &ContractAnalysis,
+    changes: &mut Vec<InterfaceChange>,
+) {
+    for trait_id in previous.implemented_traits.iter() {
+        if !current.implemented_traits.contains(trait_id) {
+            changes.push(InterfaceChange::breaking(format!(
+                "trait {}.{} no longer implemented",
+                trait_id.contract_identifier, trait_id.name
+            )));
+        }
+    }
+    for trait_id in current.implemented_traits.iter() {
+        if !previous.implemented_traits.contains(trait_id) {
+            changes.push(InterfaceChange::additive(format!(
+                "trait {}.{} implemented",
+                trait_id.contract_identifier, trait_id.name
+            )));
+        }
+    }
+
+    for (name, functions) in previous.defined_traits.iter() {
+        let current_functions = match current.defined_traits.get(name) {
+            Some(current_functions) => current_functions,
+            None => {
+                changes.push(InterfaceChange::breaking(format!(
+                    "trait `{}` removed",
+                    name
+                )));
+                continue;
+            }
+        };
+        // Contracts implementing the previous definition don't conform to the new one
+        if functions != current_functions {
+            let describe = |functions: &BTreeMap<_, FunctionSignature>| {
+                functions
+                    .iter()
+                    .map(|(name, signature)| format!("{} {}", name, trait_signature(signature)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            changes.push(InterfaceChange::breaking(format!(
+                "definition of trait `{}` changed from [{}] to [{}]",
+                name,
+                describe(functions),
+                describe(current_functions)
+            )));
+        }
+    }
+    for name in current.defined_traits.keys() {
+        if !previous.defined_traits.contains_key(name) {
+            changes.push(InterfaceChange::additive(format!(
+                "trait `{}` defined",
+                name
+            )));
+        }
+    }
+}
+
+/// List the changes of the interface of a contract: public and read-only
+/// functions, data vars, constants, maps, tokens, and traits.
+pub fn diff_contracts(
+    previous: &ContractAnalysis,
+    current: &ContractAnalysis,
+) -> Result<Vec<InterfaceChange>, String> {
+    let previous_interface = previous.contract_interface.as_ref().ok_or(format!(
+        "unable to get the interface of {}",
+        previous.contract_identifier
+    ))?;
+    let current_interface = current.contract_interface.as_ref().ok_or(format!(
+        "unable to get the interface of {}",
+        current.contract_identifier
+    ))?;
+
+    let mut changes = vec![];
+    diff_functions(previous_interface, current_interface, &mut changes);
+    for definitions in [variables, maps, tokens] {
+        diff_definitions(
+            &definitions(previous_interface),
+            &definitions(current_interface),
+            &mut changes,
+        );
+    }
+    diff_traits(previous, current, &mut changes);
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity_repl::clarity::vm::EvaluationResult;
+    use clarity_repl::repl::{Session, SessionSettings};
+
+    fn analyze(session: &mut Session, snippet: &str) -> ContractAnalysis {
+        match session.eval(snippet.to_string(), None, false) {
+            Ok(execution) => match execution.result {
+                EvaluationResult::Contract(result) => result.contract.analysis,
+                _ => panic!("expected a contract"),
+            },
+            Err(diagnostics) => panic!("unable to deploy contract: {:?}", diagnostics),
+        }
+    }
+
+    #[test]
+    fn test_breaking_and_additive_changes() {
+        let mut session = Session::new(SessionSettings::default());
+        let previous = analyze(
+            &mut session,
+            r#"
+(define-map balances principal uint)
+(define-data-var owner principal tx-sender)
+(define-public (transfer (amount uint) (recipient principal))
+  (if (> amount u0) (ok true) (err u1)))
+(define-read-only (get-owner)
+  (var-get owner))
+(define-private (helper) true)
+"#,
+        );
+        let current = analyze(
+            &mut session,
+            r#"
+(define-map balances principal int)
+(define-data-var owner principal tx-sender)
+(define-fungible-token token)
+(define-public (transfer (amount uint) (recipient principal) (memo (optional (buff 34))))
+  (if (> amount u0) (ok true) (err u1)))
+(define-read-only (get-owner)
+  (var-get owner))
+(define-read-only (get-balance (who principal))
+  (map-get? balances who))
+"#,
+        );
+
+        let changes = diff_contracts(&previous, &current).unwrap();
+        let breaking = changes
+            .iter()
+            .filter(|change| change.kind == ChangeKind::Breaking)
+            .map(|change| change.description.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            breaking,
+            vec![
+                "signature of public function `transfer` changed from (uint principal) -> (response bool uint) to (uint principal (optional (buff 34))) -> (response bool uint)",
+                "type of map `balances` changed from principal => uint to principal => int",
+            ]
+        );
+        let additive = changes
+            .iter()
+            .filter(|change| change.kind == ChangeKind::Additive)
+            .count();
+        assert_eq!(additive, 2);
+
+        assert!(diff_contracts(&current, &current).unwrap().is_empty());
+    }
+}
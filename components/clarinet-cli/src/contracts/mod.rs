@@ -0,0 +1,134 @@
+pub mod diff;
+
+use std::process::Command;
+
+use clarinet_deployments::requirements::retrieve_contract;
+use clarinet_files::ProjectManifest;
+use clarity_repl::clarity::vm::analysis::types::ContractAnalysis;
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::vm::{ContractName, EvaluationResult};
+use clarity_repl::repl::{ClarityCodeSource, ClarityContract, ContractDeployer, Session};
+
+/// Name under which a previous version of a contract is deployed, next to the
+/// current one, to be analyzed
+const PREVIOUS_CONTRACT_NAME: &str = "contract-diff-previous";
+
+/// What a contract is compared against: a deployed contract, or its source at a git reference
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffTarget {
+    Deployed(QualifiedContractIdentifier),
+    GitRef(String),
+}
+
+impl DiffTarget {
+    pub fn parse(target: &str) -> DiffTarget {
+        match QualifiedContractIdentifier::parse(target) {
+            Ok(contract_id) => DiffTarget::Deployed(contract_id),
+            Err(_) => DiffTarget::GitRef(target.to_string()),
+        }
+    }
+}
+
+/// Deploy a contract in a copy of the session, in which its dependencies are deployed
+fn analyze_contract(
+    session: &Session,
+    contract: &ClarityContract,
+) -> Result<ContractAnalysis, String> {
+    let mut session = session.clone();
+    match session.deploy_contract(contract, None, false, None, &mut None) {
+        Ok(execution) => match execution.result {
+            EvaluationResult::Contract(result) => Ok(result.contract.analysis),
+            _ => Err(format!("unable to analyze {}", contract.name)),
+        },
+        Err(diagnostics) => {
+            let messages = diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>();
+            Err(format!(
+                "unable to analyze {}:\n{}",
+                contract.name,
+                messages.join("\n")
+            ))
+        }
+    }
+}
+
+fn get_source_at_git_ref(
+    manifest: &ProjectManifest,
+    contract: &ClarityContract,
+    git_ref: &str,
+) -> Result<String, String> {
+    let path = match &contract.code_source {
+        ClarityCodeSource::ContractOnDisk(path) => path,
+        _ => return Err(format!("unable to locate the source of {}", contract.name)),
+    };
+    let project_root = manifest.location.get_project_root_location()?;
+    // `./` makes the path relative to the project root rather than to the repository root
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(project_root.to_string())
+        .arg("show")
+        .arg(format!("{}:./{}", git_ref, path.display()))
+        .output()
+        .map_err(|e| format!("unable to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "unable to get {} at {}: {}",
+            path.display(),
+            git_ref,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| e.to_string())
+}
+
+/// Analysis of the version of a contract of the project to compare against
+pub fn analyze_diff_target(
+    manifest: &ProjectManifest,
+    session: &Session,
+    contract_id: &QualifiedContractIdentifier,
+    target: &DiffTarget,
+) -> Result<ContractAnalysis, String> {
+    match target {
+        DiffTarget::Deployed(target_id) => {
+            // Requirements are already deployed in the simnet session
+            if let Some(contract) = session.contracts.get(target_id) {
+                return Ok(contract.analysis.clone());
+            }
+            let future = retrieve_contract(target_id, &manifest.project.cache_location, &None);
+            let (source, epoch, clarity_version, _) = hiro_system_kit::nestable_block_on(future)?;
+            let contract = ClarityContract {
+                code_source: ClarityCodeSource::ContractInMemory(source),
+                name: target_id.name.to_string(),
+                deployer: ContractDeployer::ContractIdentifier(target_id.clone()),
+                clarity_version,
+                epoch,
+            };
+            analyze_contract(session, &contract)
+        }
+        DiffTarget::GitRef(git_ref) => {
+            let contract = manifest
+                .contracts
+                .get(contract_id.name.as_str())
+                .ok_or(format!(
+                    "contract {} not found in the manifest",
+                    contract_id
+                ))?;
+            let source = get_source_at_git_ref(manifest, contract, git_ref)?;
+            // The current version is deployed under the same identifier
+            let previous_id = QualifiedContractIdentifier::new(
+                contract_id.issuer.clone(),
+                ContractName::from(PREVIOUS_CONTRACT_NAME),
+            );
+            let contract = ClarityContract {
+                code_source: ClarityCodeSource::ContractInMemory(source),
+                name: PREVIOUS_CONTRACT_NAME.to_string(),
+                deployer: ContractDeployer::ContractIdentifier(previous_id),
+                clarity_version: contract.clarity_version,
+                epoch: contract.epoch,
+            };
+            analyze_contract(session, &contract)
+        }
+    }
+}
@@ -1,6 +1,7 @@
 pub mod types;
 mod ui;
 
+use std::collections::BTreeMap;
 use std::fs::{self};
 use std::path::PathBuf;
 pub use ui::start_ui;
@@ -8,6 +9,7 @@ pub use ui::start_ui;
 use clarinet_deployments::types::{DeploymentGenerationArtifacts, DeploymentSpecification};
 use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::{FileLocation, ProjectManifest};
+use clarity_events::abi::{check_events_abi, collect_contract_events, events_abi};
 
 #[derive(Deserialize, Debug)]
 pub struct Balance {
@@ -104,3 +106,60 @@ pub fn write_deployment(
     target_location.write_content(&deployment.to_file_content()?)?;
     Ok(())
 }
+
+pub fn get_events_abi_location(
+    manifest: &ProjectManifest,
+    deployment_plan_location: &Option<String>,
+) -> Result<FileLocation, String> {
+    let mut events_abi_location = match deployment_plan_location {
+        Some(location) => FileLocation::from_path_string(location)?.get_parent_location()?,
+        None => {
+            let mut location = manifest.location.get_project_root_location()?;
+            location.append_path("deployments")?;
+            location
+        }
+    };
+    events_abi_location.append_path("events.abi.json")?;
+    Ok(events_abi_location)
+}
+
+/// Write the events ABI of the contracts of a deployment, after checking that the
+/// contracts whose events changed shape had their version bumped in the manifest
+pub fn write_events_abi(
+    manifest: &ProjectManifest,
+    deployment: &DeploymentSpecification,
+    artifacts: &DeploymentGenerationArtifacts,
+    target_location: &FileLocation,
+) -> Result<usize, String> {
+    let mut contracts = BTreeMap::new();
+    for contract_id in deployment.contracts.keys() {
+        let contract_analysis = match artifacts.analysis.get(contract_id) {
+            Some(contract_analysis) => contract_analysis,
+            None => continue,
+        };
+        let version = manifest
+            .project
+            .events_abi
+            .versions
+            .get(contract_id.name.as_str())
+            .copied()
+            .unwrap_or(1);
+        contracts.insert(
+            contract_id.clone(),
+            collect_contract_events(contract_analysis, version),
+        );
+    }
+    let abi = events_abi(&contracts);
+
+    if target_location.exists() {
+        let content = target_location.read_content()?;
+        let previous_abi: serde_json::Value = serde_json::from_slice(&content)
+            .map_err(|e| format!("unable to parse {}: {}", target_location, e))?;
+        check_events_abi(&previous_abi, &abi).map_err(|errors| errors.join("\n"))?;
+    }
+
+    let content = serde_json::to_string_pretty(&abi)
+        .map_err(|e| format!("unable to serialize events ABI: {}", e))?;
+    target_location.write_content(content.as_bytes())?;
+    Ok(contracts.len())
+}
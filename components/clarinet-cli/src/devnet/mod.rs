@@ -1,2 +1,3 @@
 pub mod package;
+pub mod replay;
 pub mod start;
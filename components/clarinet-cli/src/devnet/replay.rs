@@ -0,0 +1,25 @@
+use stacks_network::events_recording::{load_recorded_events, replay_recorded_events};
+
+pub fn replay(recording_path: &str, observer_url: &str, speed: f64) -> Result<(), String> {
+    let events = load_recorded_events(recording_path)?;
+    println!(
+        "Replaying {} events from {} to {}",
+        events.len(),
+        recording_path,
+        observer_url
+    );
+    hiro_system_kit::nestable_block_on(replay_recorded_events(
+        &events,
+        observer_url,
+        speed,
+        |i, event| {
+            println!(
+                "{} {} ({}/{})",
+                green!("replayed"),
+                event.path,
+                i + 1,
+                events.len()
+            )
+        },
+    ))
+}
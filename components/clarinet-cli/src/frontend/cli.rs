@@ -1,15 +1,21 @@
+use crate::contracts::diff::{diff_contracts, ChangeKind};
+use crate::contracts::{analyze_diff_target, DiffTarget};
 use crate::deployments::types::DeploymentSynthesis;
 use crate::deployments::{
     self, check_deployments, generate_default_deployment, get_absolute_deployment_path,
-    write_deployment,
+    get_events_abi_location, write_deployment, write_events_abi,
 };
 use crate::devnet::package::{self as Package, ConfigurationPackage};
+use crate::devnet::replay::replay;
 use crate::devnet::start::start;
 use crate::generate::{
     self,
     changes::{Changes, TOMLEdition},
 };
 use crate::lsp::run_lsp;
+use crate::testing::fuzz::{build_script, parse_script, Fuzzer, Rng, INVARIANT_FUNCTION_PREFIX};
+use crate::testing::mutation::{find_mutants, run_mutation_testing, MutantOutcome};
+use crate::testing::runner::{discover_test_files, run_tests, TEST_FILE_SUFFIX};
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{Generator, Shell};
@@ -24,10 +30,11 @@ use clarinet_deployments::{
 };
 use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::{
-    get_manifest_location, FileLocation, NetworkManifest, ProjectManifest, ProjectManifestFile,
-    RequirementConfig,
+    get_manifest_location, DevnetConfigFile, FileLocation, NetworkManifest, ProjectManifest,
+    ProjectManifestFile, RequirementConfig,
 };
 use clarity_repl::analysis::call_checker::ContractAnalysis;
+use clarity_repl::analysis::coverage_formats::CoverageSummary;
 use clarity_repl::clarity::vm::analysis::AnalysisDatabase;
 use clarity_repl::clarity::vm::costs::LimitedCostTracker;
 use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
@@ -85,6 +92,15 @@ enum Command {
     /// Check contracts syntax
     #[clap(name = "check", bin_name = "check")]
     Check(Check),
+    /// Run the Clarity tests (tests/*_test.clar) in Simnet
+    #[clap(name = "test", bin_name = "test")]
+    Test(Test),
+    /// Call the public functions of a contract with random arguments and check its invariants
+    #[clap(name = "fuzz", bin_name = "fuzz")]
+    Fuzz(Fuzz),
+    /// Run the Clarity tests against mutated versions of the contracts, and report the mutations left undetected
+    #[clap(name = "mutate", bin_name = "mutate")]
+    Mutate(Mutate),
     /// Start a local Devnet network for interacting with your contracts from your browser
     #[clap(name = "integrate", bin_name = "integrate")]
     Integrate(DevnetStart),
@@ -108,6 +124,10 @@ enum Devnet {
     /// Start a local Devnet network for interacting with your contracts from your browser
     #[clap(name = "start", bin_name = "start")]
     DevnetStart(DevnetStart),
+
+    /// Replay chain events recorded with 'clarinet devnet start --record' to an event observer
+    #[clap(name = "replay", bin_name = "replay")]
+    DevnetReplay(DevnetReplay),
 }
 
 #[derive(Subcommand, PartialEq, Clone, Debug)]
@@ -118,6 +138,9 @@ enum Contracts {
     /// Remove files and settings for a contract
     #[clap(name = "rm", bin_name = "rm")]
     RemoveContract(RemoveContract),
+    /// Compare the interface of a contract with a deployed contract or a previous version
+    #[clap(name = "diff", bin_name = "diff")]
+    DiffContract(DiffContract),
 }
 
 #[derive(Subcommand, PartialEq, Clone, Debug)]
@@ -163,6 +186,9 @@ struct GenerateProject {
 struct NewContract {
     /// Contract's name
     pub name: String,
+    /// Generate a contract implementing a standard trait (sip-009, sip-010, sip-013)
+    #[clap(long = "trait")]
+    pub r#trait: Option<String>,
     /// Path to Clarinet.toml
     #[clap(long = "manifest-path", short = 'm')]
     pub manifest_path: Option<String>,
@@ -177,6 +203,18 @@ struct RemoveContract {
     pub manifest_path: Option<String>,
 }
 
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct DiffContract {
+    /// Contract's name
+    pub name: String,
+    /// Deployed contract id (ex. "SP2PABAF9FTAJYNFZH93XENAJ8FVY99RRM50D2JG9.nft-trait") or git reference to compare against
+    #[clap(long = "against")]
+    pub against: String,
+    /// Path to Clarinet.toml
+    #[clap(long = "manifest-path", short = 'm')]
+    pub manifest_path: Option<String>,
+}
+
 #[derive(Parser, PartialEq, Clone, Debug)]
 struct AddRequirement {
     /// Contract id (ex. "SP2PABAF9FTAJYNFZH93XENAJ8FVY99RRM50D2JG9.nft-trait")
@@ -388,6 +426,24 @@ struct DevnetStart {
         conflicts_with = "manifest_path"
     )]
     pub package: Option<String>,
+    /// Record the events posted by the stacks-node in this file (ndjson)
+    #[clap(long = "record")]
+    pub record: Option<String>,
+    /// Relay the events through the events proxy, for delay_events faults requested at runtime
+    #[clap(long = "enable-events-delay")]
+    pub enable_events_delay: bool,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct DevnetReplay {
+    /// Path to the recording (ndjson) produced by 'clarinet devnet start --record'
+    pub recording_path: String,
+    /// Event observer url: events are posted to the paths the stacks-node posted them to (<url>/new_block, <url>/new_burn_block, ...)
+    #[clap(long = "observer-url")]
+    pub observer_url: String,
+    /// Replay speed factor applied to the original timing (0 replays without waiting)
+    #[clap(long = "speed", default_value = "1")]
+    pub speed: f64,
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
@@ -417,6 +473,114 @@ struct Check {
     /// Allow the Clarity Wasm preview to run in parallel with the Clarity interpreter (beta)
     #[clap(long = "enable-clarity-wasm")]
     pub enable_clarity_wasm: bool,
+    /// Write the events ABI of the contracts next to the deployment plan (events.abi.json)
+    #[clap(long = "emit-events-abi")]
+    pub emit_events_abi: bool,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct Test {
+    /// Path to Clarinet.toml
+    #[clap(long = "manifest-path", short = 'm')]
+    pub manifest_path: Option<String>,
+    /// If specified, use this deployment file
+    #[clap(long = "deployment-plan-path", short = 'p')]
+    pub deployment_plan_path: Option<String>,
+    /// Use on disk deployment plan (prevent updates computing)
+    #[clap(
+        long = "use-on-disk-deployment-plan",
+        short = 'd',
+        conflicts_with = "use_computed_deployment_plan"
+    )]
+    pub use_on_disk_deployment_plan: bool,
+    /// Use computed deployment plan (will overwrite on disk version if any update)
+    #[clap(
+        long = "use-computed-deployment-plan",
+        short = 'c',
+        conflicts_with = "use_on_disk_deployment_plan"
+    )]
+    pub use_computed_deployment_plan: bool,
+    /// Write the coverage report to this file
+    #[clap(long = "coverage")]
+    pub coverage_path: Option<String>,
+    /// Format of the coverage report: lcov, html or cobertura
+    #[clap(
+        long = "coverage-format",
+        default_value = "lcov",
+        requires = "coverage_path"
+    )]
+    pub coverage_format: String,
+    /// Write the costs reports (JSON) to this file
+    #[clap(long = "costs")]
+    pub costs_path: Option<String>,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct Fuzz {
+    /// Name of the contract to fuzz
+    pub contract: String,
+    /// Path to Clarinet.toml
+    #[clap(long = "manifest-path", short = 'm')]
+    pub manifest_path: Option<String>,
+    /// If specified, use this deployment file
+    #[clap(long = "deployment-plan-path", short = 'p')]
+    pub deployment_plan_path: Option<String>,
+    /// Use on disk deployment plan (prevent updates computing)
+    #[clap(
+        long = "use-on-disk-deployment-plan",
+        short = 'd',
+        conflicts_with = "use_computed_deployment_plan"
+    )]
+    pub use_on_disk_deployment_plan: bool,
+    /// Use computed deployment plan (will overwrite on disk version if any update)
+    #[clap(
+        long = "use-computed-deployment-plan",
+        short = 'c',
+        conflicts_with = "use_on_disk_deployment_plan"
+    )]
+    pub use_computed_deployment_plan: bool,
+    /// Number of sequences of calls to run
+    #[clap(long = "runs", default_value = "100")]
+    pub runs: usize,
+    /// Number of steps (calls or blocks advances) per sequence
+    #[clap(long = "depth", default_value = "20")]
+    pub depth: usize,
+    /// Seed of the random generator (defaults to a time based seed)
+    #[clap(long = "seed")]
+    pub seed: Option<u64>,
+    /// Path of the script reproducing a failure (defaults to fuzz/<contract>-<seed>.repl)
+    #[clap(long = "output", short = 'o', conflicts_with = "replay")]
+    pub output: Option<String>,
+    /// Replay a script saved by a previous run instead of fuzzing
+    #[clap(long = "replay")]
+    pub replay: Option<String>,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct Mutate {
+    /// Path to Clarinet.toml
+    #[clap(long = "manifest-path", short = 'm')]
+    pub manifest_path: Option<String>,
+    /// Only mutate this contract (defaults to all the project contracts)
+    #[clap(long = "contract")]
+    pub contract: Option<String>,
+    /// If specified, use this deployment file
+    #[clap(long = "deployment-plan-path", short = 'p')]
+    pub deployment_plan_path: Option<String>,
+    /// Use on disk deployment plan (prevent updates computing)
+    #[clap(
+        long = "use-on-disk-deployment-plan",
+        short = 'd',
+        conflicts_with = "use_computed_deployment_plan"
+    )]
+    pub use_on_disk_deployment_plan: bool,
+    /// Use computed deployment plan (will overwrite on disk version if any update)
+    #[clap(
+        long = "use-computed-deployment-plan",
+        short = 'c',
+        conflicts_with = "use_on_disk_deployment_plan"
+    )]
+    pub use_computed_deployment_plan: bool,
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
@@ -836,6 +1000,7 @@ pub fn main() {
                     &manifest.location,
                     cmd.name,
                     None,
+                    cmd.r#trait,
                     true,
                 ) {
                     Ok(changes) => changes,
@@ -883,6 +1048,89 @@ pub fn main() {
                     display_post_check_hint();
                 }
             }
+            Contracts::DiffContract(cmd) => {
+                let manifest = load_manifest_or_exit(cmd.manifest_path);
+                let (deployment, _, artifacts) =
+                    load_deployment_and_artifacts_or_exit(&manifest, &None, false, false);
+                if !artifacts.success {
+                    let diags_digest = DiagnosticsDigest::new(&artifacts.diags, &deployment);
+                    if diags_digest.has_feedbacks() {
+                        println!("{}", diags_digest.message);
+                    }
+                    std::process::exit(1);
+                }
+
+                let current = match deployment
+                    .contracts
+                    .keys()
+                    .find(|contract_id| contract_id.name.as_str() == cmd.name)
+                    .and_then(|contract_id| artifacts.session.contracts.get(contract_id))
+                {
+                    Some(contract) => &contract.analysis,
+                    None => {
+                        println!(
+                            "{}",
+                            format_err!(format!("contract {} not found", cmd.name))
+                        );
+                        std::process::exit(1);
+                    }
+                };
+                let target = DiffTarget::parse(&cmd.against);
+                let changes = analyze_diff_target(
+                    &manifest,
+                    &artifacts.session,
+                    &current.contract_identifier,
+                    &target,
+                )
+                .and_then(|previous| diff_contracts(&previous, current));
+                let changes = match changes {
+                    Ok(changes) => changes,
+                    Err(message) => {
+                        println!("{}", format_err!(message));
+                        std::process::exit(1);
+                    }
+                };
+
+                if changes.is_empty() {
+                    println!(
+                        "{} no interface change between {} and {}",
+                        green!("✔"),
+                        cmd.name,
+                        cmd.against
+                    );
+                    std::process::exit(0);
+                }
+                let breaking = changes
+                    .iter()
+                    .filter(|change| change.kind == ChangeKind::Breaking)
+                    .collect::<Vec<_>>();
+                let additive = changes
+                    .iter()
+                    .filter(|change| change.kind == ChangeKind::Additive)
+                    .collect::<Vec<_>>();
+                if !breaking.is_empty() {
+                    println!("{}", red!("Breaking changes"));
+                    for change in breaking.iter() {
+                        println!("{} {}", red!("x"), change.description);
+                    }
+                }
+                if !additive.is_empty() {
+                    println!("{}", green!("Additive changes"));
+                    for change in additive.iter() {
+                        println!("{} {}", green!("+"), change.description);
+                    }
+                }
+                println!(
+                    "\n{} breaking and {} additive changes between {} and {}",
+                    breaking.len(),
+                    additive.len(),
+                    cmd.name,
+                    cmd.against
+                );
+                if !breaking.is_empty() {
+                    std::process::exit(1);
+                }
+            }
         },
         Command::Requirements(subcommand) => match subcommand {
             Requirements::AddRequirement(cmd) => {
@@ -1073,13 +1321,18 @@ pub fn main() {
             }
         }
         Command::Check(cmd) => {
-            let manifest = load_manifest_or_exit(cmd.manifest_path);
-            let (deployment, _, artifacts) = load_deployment_and_artifacts_or_exit(
-                &manifest,
-                &cmd.deployment_plan_path,
-                cmd.use_on_disk_deployment_plan,
-                cmd.use_computed_deployment_plan,
-            );
+            let mut manifest = load_manifest_or_exit(cmd.manifest_path);
+            manifest
+                .repl_settings
+                .analysis
+                .set_passes(vec![analysis::Pass::TraitChecker]);
+            let (deployment, deployment_plan_location, artifacts) =
+                load_deployment_and_artifacts_or_exit(
+                    &manifest,
+                    &cmd.deployment_plan_path,
+                    cmd.use_on_disk_deployment_plan,
+                    cmd.use_computed_deployment_plan,
+                );
 
             if cmd.enable_clarity_wasm {
                 let mut manifest_wasm = manifest.clone();
@@ -1118,11 +1371,31 @@ pub fn main() {
                     pluralize!(diags_digest.contracts_checked, "contract"),
                 );
             }
-            let exit_code = match artifacts.success {
+            let mut exit_code = match artifacts.success {
                 true => 0,
                 false => 1,
             };
 
+            if artifacts.success && (cmd.emit_events_abi || manifest.project.events_abi.emit) {
+                let result = get_events_abi_location(&manifest, &deployment_plan_location)
+                    .and_then(|location| {
+                        write_events_abi(&manifest, &deployment, &artifacts, &location)
+                            .map(|count| (location, count))
+                    });
+                match result {
+                    Ok((location, count)) => println!(
+                        "{} events ABI of {} written to {}",
+                        green!("✔"),
+                        pluralize!(count, "contract"),
+                        location
+                    ),
+                    Err(message) => {
+                        println!("{}", format_err!(message));
+                        exit_code = 1;
+                    }
+                }
+            }
+
             if global_settings.enable_hints.unwrap_or(true) {
                 display_post_check_hint();
             }
@@ -1134,6 +1407,392 @@ pub fn main() {
             }
             std::process::exit(exit_code);
         }
+        Command::Test(cmd) => {
+            let manifest = load_manifest_or_exit(cmd.manifest_path);
+            let (deployment, _, artifacts) = load_deployment_and_artifacts_or_exit(
+                &manifest,
+                &cmd.deployment_plan_path,
+                cmd.use_on_disk_deployment_plan,
+                cmd.use_computed_deployment_plan,
+            );
+            if !artifacts.success {
+                let diags_digest = DiagnosticsDigest::new(&artifacts.diags, &deployment);
+                if diags_digest.has_feedbacks() {
+                    println!("{}", diags_digest.message);
+                }
+                std::process::exit(1);
+            }
+
+            let test_files = match discover_test_files(&manifest) {
+                Ok(test_files) => test_files,
+                Err(e) => {
+                    println!("{}", format_err!(e));
+                    std::process::exit(1);
+                }
+            };
+            if test_files.is_empty() {
+                println!(
+                    "{} no test found (tests/*{})",
+                    yellow!("note:"),
+                    TEST_FILE_SUFFIX
+                );
+                std::process::exit(0);
+            }
+
+            let start = std::time::Instant::now();
+            let report = match run_tests(&artifacts.session, &deployment, &test_files, &|result| {
+                let duration = format!("({}ms)", result.duration.as_millis());
+                if result.passed {
+                    println!(
+                        "{} {}::{} {}",
+                        green!("✔"),
+                        result.test_file,
+                        result.test_name,
+                        black!(duration)
+                    );
+                } else {
+                    println!(
+                        "{} {}::{} {}\n    {}",
+                        red!("x"),
+                        result.test_file,
+                        result.test_name,
+                        black!(duration),
+                        result.output
+                    );
+                }
+            }) {
+                Ok(report) => report,
+                Err(e) => {
+                    println!("{}", format_err!(e));
+                    std::process::exit(1);
+                }
+            };
+
+            println!(
+                "\n{} passed, {} failed ({}ms)",
+                report.passed(),
+                report.failed(),
+                start.elapsed().as_millis()
+            );
+
+            if let Some(ref coverage_path) = cmd.coverage_path {
+                let lcov = report.coverage.build_lcov_content();
+                let content = match cmd.coverage_format.as_str() {
+                    "lcov" => Ok(lcov),
+                    "cobertura" => {
+                        let timestamp = std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .map(|d| d.as_secs())
+                            .unwrap_or_default();
+                        Ok(CoverageSummary::from_lcov(&lcov).build_cobertura_content(timestamp))
+                    }
+                    "html" => {
+                        let sources = report
+                            .coverage
+                            .contract_paths
+                            .values()
+                            .filter_map(|path| {
+                                fs::read_to_string(path)
+                                    .ok()
+                                    .map(|code| (path.clone(), code))
+                            })
+                            .collect();
+                        Ok(CoverageSummary::from_lcov(&lcov).build_html_content(&sources))
+                    }
+                    format => Err(format!(
+                        "unknown coverage format {} (expected lcov, html or cobertura)",
+                        format
+                    )),
+                };
+                let written = content.and_then(|content| {
+                    std::path::Path::new(coverage_path)
+                        .parent()
+                        .map_or(Ok(()), fs::create_dir_all)
+                        .and_then(|_| fs::write(coverage_path, content))
+                        .map_err(|e| format!("unable to write {} ({})", coverage_path, e))
+                });
+                match written {
+                    Ok(_) => println!(
+                        "{} coverage report written to {}",
+                        green!("✔"),
+                        coverage_path
+                    ),
+                    Err(e) => println!("{}", format_err!(e)),
+                }
+            }
+            if let Some(ref costs_path) = cmd.costs_path {
+                let costs = serde_json::to_string_pretty(&report.costs_to_json())
+                    .expect("unable to serialize costs");
+                match fs::write(costs_path, costs) {
+                    Ok(_) => println!("{} costs report written to {}", green!("✔"), costs_path),
+                    Err(e) => println!(
+                        "{}",
+                        format_err!(format!("unable to write {} ({})", costs_path, e))
+                    ),
+                }
+            }
+
+            std::process::exit(if report.failed() > 0 { 1 } else { 0 });
+        }
+        Command::Fuzz(cmd) => {
+            let manifest = load_manifest_or_exit(cmd.manifest_path);
+            let (deployment, _, artifacts) = load_deployment_and_artifacts_or_exit(
+                &manifest,
+                &cmd.deployment_plan_path,
+                cmd.use_on_disk_deployment_plan,
+                cmd.use_computed_deployment_plan,
+            );
+            if !artifacts.success {
+                let diags_digest = DiagnosticsDigest::new(&artifacts.diags, &deployment);
+                if diags_digest.has_feedbacks() {
+                    println!("{}", diags_digest.message);
+                }
+                std::process::exit(1);
+            }
+
+            let contract_id = match deployment
+                .contracts
+                .keys()
+                .find(|contract_id| contract_id.name.as_str() == cmd.contract)
+            {
+                Some(contract_id) => contract_id.clone(),
+                None => {
+                    println!(
+                        "{}",
+                        format_err!(format!("contract {} not found", cmd.contract))
+                    );
+                    std::process::exit(1);
+                }
+            };
+            let senders = deployment
+                .genesis
+                .as_ref()
+                .map(|genesis| {
+                    genesis
+                        .wallets
+                        .iter()
+                        .map(|wallet| wallet.address.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let fuzzer = match Fuzzer::new(artifacts.session, contract_id, senders) {
+                Ok(fuzzer) => fuzzer,
+                Err(e) => {
+                    println!("{}", format_err!(e));
+                    std::process::exit(1);
+                }
+            };
+            if fuzzer.invariants().is_empty() {
+                println!(
+                    "{} no invariant found ({}* read-only functions), only calls failing with a runtime error will be reported",
+                    yellow!("note:"),
+                    INVARIANT_FUNCTION_PREFIX
+                );
+            }
+
+            if let Some(ref replay_path) = cmd.replay {
+                let script = match fs::read_to_string(replay_path) {
+                    Ok(script) => script,
+                    Err(e) => {
+                        println!(
+                            "{}",
+                            format_err!(format!("unable to read {} ({})", replay_path, e))
+                        );
+                        std::process::exit(1);
+                    }
+                };
+                match fuzzer.replay(&parse_script(&script)) {
+                    Some(failure) => {
+                        println!("{} {}: {}", red!("x"), failure.summary(), failure.output);
+                        std::process::exit(1);
+                    }
+                    None => {
+                        println!("{} all calls succeeded, all invariants hold", green!("✔"));
+                        std::process::exit(0);
+                    }
+                }
+            }
+
+            let (mut rng, seed) = match cmd.seed {
+                Some(seed) => (Rng::new(seed), seed),
+                None => Rng::from_time(),
+            };
+            println!(
+                "Fuzzing {} ({} runs of {} steps, seed {})",
+                fuzzer.contract_id(),
+                cmd.runs,
+                cmd.depth,
+                seed
+            );
+
+            for run in 0..cmd.runs {
+                let steps = fuzzer.generate_steps(&mut rng, cmd.depth);
+                let Some(failure) = fuzzer.run(&steps) else {
+                    continue;
+                };
+                println!(
+                    "{} {} during run {}, shrinking...",
+                    red!("x"),
+                    failure.summary(),
+                    run + 1
+                );
+                let (steps, failure) = fuzzer.shrink(&steps, &failure);
+                let header = format!(
+                    "clarinet fuzz {} --seed {}\n{}: {}",
+                    cmd.contract,
+                    seed,
+                    failure.summary(),
+                    failure.output
+                );
+                let script = build_script(fuzzer.contract_id(), &steps, &header);
+                println!("{}", script);
+
+                let output = cmd.output.clone().unwrap_or_else(|| {
+                    let mut location = manifest
+                        .location
+                        .get_project_root_location()
+                        .expect("unable to get project root");
+                    location
+                        .append_path(&format!("fuzz/{}-{}.repl", cmd.contract, seed))
+                        .expect("unable to build script path");
+                    location.to_string()
+                });
+                let written = std::path::Path::new(&output)
+                    .parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|_| fs::write(&output, script));
+                match written {
+                    Ok(_) => println!(
+                        "{} minimal reproduction ({}) written to {}",
+                        yellow!("!"),
+                        pluralize!(steps.len(), "step"),
+                        output
+                    ),
+                    Err(e) => println!(
+                        "{}",
+                        format_err!(format!("unable to write {} ({})", output, e))
+                    ),
+                }
+                std::process::exit(1);
+            }
+            println!(
+                "{} {} runs completed, all calls succeeded, all invariants hold",
+                green!("✔"),
+                cmd.runs
+            );
+        }
+        Command::Mutate(cmd) => {
+            let manifest = load_manifest_or_exit(cmd.manifest_path);
+            let (deployment, _, artifacts) = load_deployment_and_artifacts_or_exit(
+                &manifest,
+                &cmd.deployment_plan_path,
+                cmd.use_on_disk_deployment_plan,
+                cmd.use_computed_deployment_plan,
+            );
+            if !artifacts.success {
+                let diags_digest = DiagnosticsDigest::new(&artifacts.diags, &deployment);
+                if diags_digest.has_feedbacks() {
+                    println!("{}", diags_digest.message);
+                }
+                std::process::exit(1);
+            }
+
+            let test_files = match discover_test_files(&manifest) {
+                Ok(test_files) if !test_files.is_empty() => test_files,
+                Ok(_) => {
+                    println!(
+                        "{}",
+                        format_err!(format!("no test found (tests/*{})", TEST_FILE_SUFFIX))
+                    );
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    println!("{}", format_err!(e));
+                    std::process::exit(1);
+                }
+            };
+
+            let mut mutants = vec![];
+            for (contract_id, ast) in artifacts.asts.iter() {
+                let name = contract_id.name.to_string();
+                let selected = match cmd.contract {
+                    Some(ref contract) => contract == &name,
+                    None => manifest.contracts.contains_key(&name),
+                };
+                if selected {
+                    mutants.append(&mut find_mutants(contract_id, ast));
+                }
+            }
+            if mutants.is_empty() {
+                println!("{} no mutation to apply", yellow!("note:"));
+                std::process::exit(0);
+            }
+            println!("Testing {}", pluralize!(mutants.len(), "mutant"));
+
+            let contract_path = |contract_id: &QualifiedContractIdentifier| {
+                deployment
+                    .contracts
+                    .get(contract_id)
+                    .map(|(_, location)| {
+                        location
+                            .get_relative_location()
+                            .unwrap_or(location.to_string())
+                    })
+                    .unwrap_or(contract_id.to_string())
+            };
+            let outcomes = match run_mutation_testing(
+                &manifest,
+                &deployment,
+                &artifacts.asts,
+                &mutants,
+                &test_files,
+                &|mutant, outcome| {
+                    let location = format!(
+                        "{}:{}:{}",
+                        contract_path(&mutant.contract_id),
+                        mutant.span.start_line,
+                        mutant.span.start_column
+                    );
+                    match outcome {
+                        MutantOutcome::Killed(test) => println!(
+                            "{} {} {} {}",
+                            green!("killed"),
+                            location,
+                            mutant.description,
+                            black!(format!("(by {})", test))
+                        ),
+                        MutantOutcome::Survived => {
+                            println!("{} {} {}", red!("survived"), location, mutant.description)
+                        }
+                        MutantOutcome::Invalid(_) => {
+                            println!("{} {} {}", black!("invalid"), location, mutant.description)
+                        }
+                    }
+                },
+            ) {
+                Ok(outcomes) => outcomes,
+                Err(e) => {
+                    println!("{}", format_err!(e));
+                    std::process::exit(1);
+                }
+            };
+
+            let killed = outcomes
+                .iter()
+                .filter(|o| matches!(o, MutantOutcome::Killed(_)))
+                .count();
+            let survived = outcomes
+                .iter()
+                .filter(|o| matches!(o, MutantOutcome::Survived))
+                .count();
+            println!(
+                "\n{} killed, {} survived, {} invalid",
+                killed,
+                survived,
+                outcomes.len() - killed - survived
+            );
+            std::process::exit(if survived > 0 { 1 } else { 0 });
+        }
         Command::Integrate(cmd) => {
             println!(
                 "{}",
@@ -1158,6 +1817,12 @@ pub fn main() {
                 }
             }
             Devnet::DevnetStart(cmd) => devnet_start(cmd, global_settings),
+            Devnet::DevnetReplay(cmd) => {
+                if let Err(e) = replay(&cmd.recording_path, &cmd.observer_url, cmd.speed) {
+                    println!("{}", format_err!(e));
+                    process::exit(1);
+                }
+            }
         },
     };
 }
@@ -1807,7 +2472,15 @@ fn devnet_start(cmd: DevnetStart, global_settings: GlobalSettings) {
         }
     };
 
-    let orchestrator = match DevnetOrchestrator::new(manifest, None, None, true) {
+    let devnet_override = match (cmd.record, cmd.enable_events_delay) {
+        (None, false) => None,
+        (record_events_path, enable_events_delay) => Some(DevnetConfigFile {
+            record_events_path,
+            enable_events_delay: enable_events_delay.then_some(true),
+            ..Default::default()
+        }),
+    };
+    let orchestrator = match DevnetOrchestrator::new(manifest, None, devnet_override, true) {
         Ok(orchestrator) => orchestrator,
         Err(e) => {
             println!("{}", format_err!(e));
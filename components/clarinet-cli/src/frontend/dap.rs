@@ -1,8 +1,16 @@
-use crate::deployments::generate_default_deployment;
-use clarinet_deployments::setup_session_with_deployment;
+use crate::deployments::{generate_default_deployment, get_absolute_deployment_path};
+use clarinet_deployments::types::DeploymentSpecification;
+use clarinet_deployments::{
+    initiate_session_from_deployment, load_deployment, setup_session_with_deployment,
+    update_session_with_contracts_executions, update_session_with_genesis_accounts,
+};
 use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::{FileLocation, ProjectManifest};
-use clarity_repl::repl::debug::dap::DAPDebugger;
+use clarity_repl::clarity::vm::diagnostic::Diagnostic;
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::vm::EvalHook;
+use clarity_repl::repl::debug::dap::{DAPDebugger, LaunchTarget};
+use clarity_repl::repl::Session;
 use std::path::PathBuf;
 
 #[cfg(feature = "telemetry")]
@@ -10,42 +18,373 @@ use super::telemetry::{telemetry_report_event, DeveloperUsageDigest, DeveloperUs
 
 pub fn run_dap() -> Result<(), String> {
     let mut dap = DAPDebugger::new();
-    match dap.init() {
-        Ok((manifest_location_str, expression)) => {
-            let manifest_location = FileLocation::from_path_string(&manifest_location_str)?;
-            let project_manifest = ProjectManifest::from_location(&manifest_location)?;
-            let (deployment, artifacts) =
-                generate_default_deployment(&project_manifest, &StacksNetwork::Simnet, false)?;
-            let mut session = setup_session_with_deployment(
-                &project_manifest,
+    let configuration = dap.init().map_err(|e| format!("dap_init: {}", e))?;
+    let manifest_location = FileLocation::from_path_string(&configuration.manifest)?;
+    let project_manifest = ProjectManifest::from_location(&manifest_location)?;
+
+    if project_manifest.project.telemetry {
+        #[cfg(feature = "telemetry")]
+        telemetry_report_event(DeveloperUsageEvent::DAPDebugStarted(
+            DeveloperUsageDigest::new(
+                &project_manifest.project.name,
+                &project_manifest.project.authors,
+            ),
+        ));
+    }
+
+    launch(&mut dap, &project_manifest, configuration.target)
+}
+
+/// What the launch targets need from the debugger, besides evaluating in debug mode
+trait LaunchDebugger: EvalHook {
+    fn register_contract_path(&mut self, contract_id: &QualifiedContractIdentifier, path: PathBuf);
+    fn stdout(&mut self, message: String);
+    fn stderr(&mut self, message: String);
+}
+
+impl LaunchDebugger for DAPDebugger {
+    fn register_contract_path(&mut self, contract_id: &QualifiedContractIdentifier, path: PathBuf) {
+        self.path_to_contract_id
+            .insert(path.clone(), contract_id.clone());
+        self.contract_id_to_path.insert(contract_id.clone(), path);
+    }
+
+    fn stdout(&mut self, message: String) {
+        DAPDebugger::stdout(self, message);
+    }
+
+    fn stderr(&mut self, message: String) {
+        DAPDebugger::stderr(self, message);
+    }
+}
+
+fn launch(
+    debugger: &mut impl LaunchDebugger,
+    project_manifest: &ProjectManifest,
+    target: LaunchTarget,
+) -> Result<(), String> {
+    match target {
+        // The deployment plan itself is debugged step by step
+        LaunchTarget::DeploymentPlan(path) => {
+            let (deployment, asts) = match path.as_str() {
+                "default" => {
+                    let (deployment, artifacts) = generate_default_deployment(
+                        project_manifest,
+                        &StacksNetwork::Simnet,
+                        false,
+                    )?;
+                    (deployment, Some(artifacts.asts))
+                }
+                path => {
+                    let location = get_absolute_deployment_path(project_manifest, path)?;
+                    (load_deployment(project_manifest, &location)?, None)
+                }
+            };
+            register_contracts_paths(debugger, &deployment);
+
+            let mut session = initiate_session_from_deployment(project_manifest);
+            update_session_with_genesis_accounts(&mut session, &deployment);
+            let executions = update_session_with_contracts_executions(
+                &mut session,
                 &deployment,
-                Some(&artifacts.asts),
-            )
-            .session;
-
-            if project_manifest.project.telemetry {
-                #[cfg(feature = "telemetry")]
-                telemetry_report_event(DeveloperUsageEvent::DAPDebugStarted(
-                    DeveloperUsageDigest::new(
-                        &project_manifest.project.name,
-                        &project_manifest.project.authors,
-                    ),
-                ));
+                asts.as_ref(),
+                false,
+                None,
+                Some(debugger),
+            );
+            for (contract_id, result) in executions.contracts.iter() {
+                if let Err(diagnostics) = result {
+                    return Err(format!(
+                        "unable to deploy {}{}",
+                        contract_id,
+                        diagnostics_reason(diagnostics)
+                    ));
+                }
             }
-
-            for (contract_id, (_, location)) in deployment.contracts.iter() {
-                dap.path_to_contract_id
-                    .insert(PathBuf::from(location.to_string()), contract_id.clone());
-                dap.contract_id_to_path
-                    .insert(contract_id.clone(), PathBuf::from(location.to_string()));
+            for (call, result) in executions.contract_calls.iter() {
+                if let Err(diagnostics) = result {
+                    return Err(format!(
+                        "unable to call {}{}",
+                        call,
+                        diagnostics_reason(diagnostics)
+                    ));
+                }
             }
-
+            Ok(())
+        }
+        LaunchTarget::Expression(expression) => {
+            let mut session = setup_default_session(debugger, project_manifest)?;
             // Begin execution of the expression in debug mode
-            match session.eval(expression.clone(), Some(vec![&mut dap]), false) {
+            match session.eval(expression, Some(vec![debugger]), false) {
                 Ok(_result) => Ok(()),
                 Err(_diagnostics) => Err("unable to interpret expression".to_string()),
             }
         }
-        Err(e) => Err(format!("dap_init: {}", e)),
+        LaunchTarget::ContractCall(call) => {
+            let mut session = setup_default_session(debugger, project_manifest)?;
+            let sender = call.sender.unwrap_or(session.get_tx_sender());
+            match session.invoke_contract_call(
+                &call.contract,
+                &call.method,
+                &call.args,
+                &sender,
+                "debug".to_string(),
+                Some(vec![debugger]),
+            ) {
+                Ok(_result) => Ok(()),
+                Err(_diagnostics) => {
+                    Err(format!("unable to call {}::{}", call.contract, call.method))
+                }
+            }
+        }
+        LaunchTarget::Script(path) => {
+            let mut session = setup_default_session(debugger, project_manifest)?;
+            let script = std::fs::read_to_string(&path)
+                .map_err(|e| format!("unable to read {}: {}", path, e))?;
+            replay_script(debugger, &mut session, &script);
+            Ok(())
+        }
+    }
+}
+
+fn diagnostics_reason(diagnostics: &[Diagnostic]) -> String {
+    match diagnostics.last() {
+        Some(diagnostic) => format!(": {}", diagnostic.message),
+        None => String::new(),
+    }
+}
+
+/// Session with the default deployment applied, to debug a target after it
+fn setup_default_session(
+    debugger: &mut impl LaunchDebugger,
+    project_manifest: &ProjectManifest,
+) -> Result<Session, String> {
+    let (deployment, artifacts) =
+        generate_default_deployment(project_manifest, &StacksNetwork::Simnet, false)?;
+    let session =
+        setup_session_with_deployment(project_manifest, &deployment, Some(&artifacts.asts)).session;
+    register_contracts_paths(debugger, &deployment);
+    Ok(session)
+}
+
+fn register_contracts_paths(
+    debugger: &mut impl LaunchDebugger,
+    deployment: &DeploymentSpecification,
+) {
+    for (contract_id, (_, location)) in deployment.contracts.iter() {
+        debugger.register_contract_path(contract_id, PathBuf::from(location.to_string()));
+    }
+}
+
+/// Replay a console script: each line is a console command or an expression,
+/// expressions are evaluated in debug mode
+fn replay_script(debugger: &mut impl LaunchDebugger, session: &mut Session, script: &str) {
+    for line in script.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(";;") {
+            continue;
+        }
+        if line.starts_with("::") {
+            let (_, output, _) = session.handle_command(line);
+            for message in output {
+                debugger.stdout(format!("{}\n", message));
+            }
+            continue;
+        }
+        if session
+            .eval(line.to_string(), Some(vec![&mut *debugger]), false)
+            .is_err()
+        {
+            debugger.stderr(format!("unable to interpret expression: {}\n", line));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity_repl::clarity::vm::contexts::{Environment, LocalContext};
+    use clarity_repl::clarity::vm::errors::Error;
+    use clarity_repl::clarity::vm::{EvaluationResult, ExecutionResult, SymbolicExpression, Value};
+    use clarity_repl::repl::debug::dap::ContractCallLaunch;
+    use std::fs;
+    use std::path::Path;
+
+    const COUNTER: &str = r#"(define-data-var count uint u0)
+(define-public (increment)
+  (begin
+    (var-set count (+ (var-get count) u1))
+    (ok (var-get count))))"#;
+
+    /// Record what a launch target evaluates and outputs
+    #[derive(Default)]
+    struct LaunchRecorder {
+        contracts_paths: Vec<(QualifiedContractIdentifier, PathBuf)>,
+        /// Number of expressions evaluated in debug mode
+        expressions: usize,
+        results: Vec<Value>,
+        stdout: Vec<String>,
+        stderr: Vec<String>,
+    }
+
+    impl EvalHook for LaunchRecorder {
+        fn will_begin_eval(
+            &mut self,
+            _env: &mut Environment,
+            _context: &LocalContext,
+            _expr: &SymbolicExpression,
+        ) {
+            self.expressions += 1;
+        }
+
+        fn did_finish_eval(
+            &mut self,
+            _env: &mut Environment,
+            _context: &LocalContext,
+            _expr: &SymbolicExpression,
+            _res: &Result<Value, Error>,
+        ) {
+        }
+
+        fn did_complete(&mut self, result: Result<&mut ExecutionResult, String>) {
+            if let Ok(ExecutionResult {
+                result: EvaluationResult::Snippet(snippet),
+                ..
+            }) = result
+            {
+                self.results.push(snippet.result.clone());
+            }
+        }
+    }
+
+    impl LaunchDebugger for LaunchRecorder {
+        fn register_contract_path(
+            &mut self,
+            contract_id: &QualifiedContractIdentifier,
+            path: PathBuf,
+        ) {
+            self.contracts_paths.push((contract_id.clone(), path));
+        }
+
+        fn stdout(&mut self, message: String) {
+            self.stdout.push(message);
+        }
+
+        fn stderr(&mut self, message: String) {
+            self.stderr.push(message);
+        }
+    }
+
+    fn write_project(project_dir: &Path) -> ProjectManifest {
+        fs::create_dir_all(project_dir.join("contracts")).unwrap();
+        fs::create_dir_all(project_dir.join("settings")).unwrap();
+        fs::write(
+            project_dir.join("Clarinet.toml"),
+            "[project]\nname = \"counter\"\n\n[contracts.counter]\npath = \"contracts/counter.clar\"\n",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("settings").join("Devnet.toml"),
+            r#"[network]
+name = "devnet"
+
+[accounts.deployer]
+mnemonic = "twice kind fence tip hidden tilt action fragile skin nothing glory cousin green tomorrow spring wrist shed math olympic multiply hip blue scout claw"
+balance = 100_000_000_000_000
+"#,
+        )
+        .unwrap();
+        fs::write(project_dir.join("contracts").join("counter.clar"), COUNTER).unwrap();
+
+        ProjectManifest::from_location(&FileLocation::from_path(project_dir.join("Clarinet.toml")))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_launch_failing_deployment_plan() {
+        let project_dir = std::env::temp_dir().join(format!("dap-plan-{}", std::process::id()));
+        let manifest = write_project(&project_dir);
+
+        let mut recorder = LaunchRecorder::default();
+        let target = LaunchTarget::DeploymentPlan("default".to_string());
+        assert!(launch(&mut recorder, &manifest, target).is_ok());
+
+        // the contract type checks but fails when it is deployed
+        fs::write(
+            project_dir.join("contracts").join("counter.clar"),
+            "(define-constant first (unwrap-panic (element-at (list u1) u2)))",
+        )
+        .unwrap();
+        let target = LaunchTarget::DeploymentPlan("default".to_string());
+        let result = launch(&mut recorder, &manifest, target);
+        let _ = fs::remove_dir_all(&project_dir);
+
+        let message = result.unwrap_err();
+        assert!(message.starts_with("unable to deploy "));
+        assert!(message.contains(".counter"));
+    }
+
+    #[test]
+    fn test_launch_contract_call() {
+        let project_dir = std::env::temp_dir().join(format!("dap-call-{}", std::process::id()));
+        let manifest = write_project(&project_dir);
+
+        let mut recorder = LaunchRecorder::default();
+        let target = LaunchTarget::ContractCall(ContractCallLaunch {
+            contract: "counter".to_string(),
+            method: "increment".to_string(),
+            args: vec![],
+            sender: None,
+        });
+        let result = launch(&mut recorder, &manifest, target);
+        let _ = fs::remove_dir_all(&project_dir);
+        result.unwrap();
+
+        // the breakpoints set in the contract files can be resolved
+        assert_eq!(recorder.contracts_paths.len(), 1);
+        let (contract_id, path) = &recorder.contracts_paths[0];
+        assert_eq!(contract_id.name.as_str(), "counter");
+        assert!(path.ends_with("contracts/counter.clar"));
+
+        assert!(recorder.expressions > 0);
+        assert_eq!(recorder.results, vec![Value::okay(Value::UInt(1)).unwrap()]);
+    }
+
+    #[test]
+    fn test_launch_script() {
+        let project_dir = std::env::temp_dir().join(format!("dap-script-{}", std::process::id()));
+        let manifest = write_project(&project_dir);
+        let script_path = project_dir.join("script.clar");
+        fs::write(
+            &script_path,
+            r#";; increment the counter twice
+(contract-call? .counter increment)
+::get_block_height
+
+(contract-call? .counter increment)
+(unknown-function)
+"#,
+        )
+        .unwrap();
+
+        let mut recorder = LaunchRecorder::default();
+        let target = LaunchTarget::Script(script_path.to_string_lossy().to_string());
+        let result = launch(&mut recorder, &manifest, target);
+        let _ = fs::remove_dir_all(&project_dir);
+        result.unwrap();
+
+        // the expressions are evaluated in debug mode, in the same session
+        assert_eq!(
+            recorder.results,
+            vec![
+                Value::okay(Value::UInt(1)).unwrap(),
+                Value::okay(Value::UInt(2)).unwrap()
+            ]
+        );
+        assert_eq!(recorder.stdout.len(), 1);
+        assert!(recorder.stdout[0].contains("Current height"));
+        assert_eq!(
+            recorder.stderr,
+            vec!["unable to interpret expression: (unknown-function)\n"]
+        );
     }
 }
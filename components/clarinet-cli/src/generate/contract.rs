@@ -1,5 +1,7 @@
 use super::changes::{Changes, FileCreation, FileDeletion, TOMLEdition};
-use clarinet_files::FileLocation;
+use super::traits::get_trait_template;
+use clarinet_files::{FileLocation, RequirementConfig};
+use clarity_repl::analysis::trait_registry::{get_standard_trait, STANDARD_TRAITS};
 use clarity_repl::repl::{
     ClarityCodeSource, ClarityContract, ContractDeployer, DEFAULT_CLARITY_VERSION, DEFAULT_EPOCH,
 };
@@ -85,6 +87,7 @@ pub struct GetChangesForNewContract {
     manifest_location: FileLocation,
     contract_name: String,
     source: Option<String>,
+    requirements: Vec<RequirementConfig>,
     changes: Vec<Changes>,
 }
 
@@ -98,10 +101,32 @@ impl GetChangesForNewContract {
             manifest_location,
             contract_name: contract_name.replace('.', "_"),
             source,
+            requirements: vec![],
             changes: vec![],
         }
     }
 
+    /// Generate the contract from the template of a standard trait (e.g. `sip-010`),
+    /// and add the contract defining the trait to the requirements
+    pub fn implement_standard_trait(&mut self, id: &str) -> Result<(), String> {
+        let standard_trait = get_standard_trait(id).ok_or_else(|| {
+            let ids = STANDARD_TRAITS
+                .iter()
+                .map(|standard_trait| standard_trait.id)
+                .collect::<Vec<_>>();
+            format!(
+                "unknown trait {} (available traits: {})",
+                id,
+                ids.join(", ")
+            )
+        })?;
+        self.source = Some(get_trait_template(standard_trait, &self.contract_name));
+        self.requirements.push(RequirementConfig {
+            contract_id: standard_trait.contract_id.to_string(),
+        });
+        Ok(())
+    }
+
     pub fn run(&mut self, include_test: bool) -> Result<Vec<Changes>, String> {
         self.create_template_contract()?;
         if include_test {
@@ -235,7 +260,7 @@ describe("example tests", () => {
             manifest_location,
             contracts_to_rm: vec![],
             contracts_to_add,
-            requirements_to_add: vec![],
+            requirements_to_add: self.requirements.clone(),
         };
         self.changes.push(Changes::EditTOML(change));
     }
@@ -1,6 +1,7 @@
 pub mod changes;
 mod contract;
 mod project;
+mod traits;
 
 pub use changes::Changes;
 use clarinet_files::FileLocation;
@@ -22,10 +23,14 @@ pub fn get_changes_for_new_contract(
     manifest_location: &FileLocation,
     contract_name: String,
     source: Option<String>,
+    standard_trait: Option<String>,
     include_test: bool,
 ) -> Result<Vec<Changes>, String> {
     let mut command =
         GetChangesForNewContract::new(manifest_location.clone(), contract_name, source);
+    if let Some(standard_trait) = standard_trait {
+        command.implement_standard_trait(&standard_trait)?;
+    }
     command.run(include_test)
 }
 
@@ -383,6 +383,9 @@ disable_stacks_api = false
 # disable_bitcoin_explorer = true
 # working_dir = "tmp/devnet"
 # stacks_node_events_observers = ["host.docker.internal:8002"]
+# stacks_node_events_proxy_port = 20447
+# relay the events through the proxy, for delay_events faults requested from the control API
+# enable_events_delay = true
 # miner_mnemonic = "{default_stacks_miner_mnemonic}"
 # miner_derivation_path = "{default_derivation_path}"
 # faucet_mnemonic = "{default_stacks_faucet_mnemonic}"
@@ -393,8 +396,12 @@ disable_stacks_api = false
 # bitcoin_node_username = "devnet"
 # bitcoin_node_password = "devnet"
 # bitcoin_controller_block_time = 30_000
+# mine blocks as soon as the stacks-node is ready, bitcoin_controller_block_time being the timeout
+# bitcoin_controller_accelerated_mining = false
 # stacks_node_rpc_port = 20443
 # stacks_node_p2p_port = 20444
+# stacks_miners = 1
+# stacks_followers = 0
 # stacks_api_port = 3999
 # stacks_api_events_port = 3700
 # bitcoin_explorer_port = 8001
@@ -431,6 +438,50 @@ disable_stacks_api = false
 # epoch_2_4 = {DEFAULT_EPOCH_2_4}
 # epoch_2_5 = {DEFAULT_EPOCH_2_5}
 
+# Signer set (defaults to wallet_1 and wallet_2)
+# Stacking orders can pick a signer with `signer = "wallet_1"`
+# The signing threshold is not configurable: blocks need signatures from 70% of the stacked STX
+# [devnet.signers]
+# accounts = [{{ wallet = "wallet_1" }}, {{ wallet = "wallet_2", start = true }}]
+
+# Inject faults once the bitcoin chain reaches a given height
+# kind can be "bitcoin_reorg", "pause_service", "partition" or "delay_events"
+# [[devnet.faults]]
+# at_block = 150
+# kind = "bitcoin_reorg"
+# depth = 3
+#
+# [[devnet.faults]]
+# at_block = 160
+# kind = "pause_service"
+# service = "stacks-node"
+# duration_ms = 30000
+#
+# The listed services are split from the rest of the devnet (they still reach each other)
+# [[devnet.faults]]
+# at_block = 165
+# kind = "partition"
+# services = ["stacks-node"]
+# duration_ms = 30000
+#
+# Events delivered to stacks_node_events_observers are held for delay_ms
+# (when scheduled or with enable_events_delay = true, the nodes post their events to the proxy
+# listening on stacks_node_events_proxy_port)
+# [[devnet.faults]]
+# at_block = 170
+# kind = "delay_events"
+# delay_ms = 5000
+# duration_ms = 60000
+
+# Run scripts once the deployment plan is applied (or at a given bitcoin block with at_block)
+# Devnet urls are exposed in their environment, and the wallets mnemonics with allow_wallets
+# allow_write is advisory (exposed as CLARINET_ALLOW_WRITE): scripts are not sandboxed
+# [[devnet.execute_script]]
+# script = "npm run seed"
+# allow_wallets = true
+# allow_write = false
+# critical = false
+
 
 # Send some stacking orders
 [[devnet.pox_stacking_orders]]
@@ -595,6 +646,8 @@ import { vitestSetupFilePath, getClarinetVitestsArgv } from "@hirosystems/clarin
   The `getClarinetVitestsArgv()` will parse options passed to the command `vitest run --`
     - vitest run -- --manifest ./Clarinet.toml  # pass a custom path
     - vitest run -- --coverage --costs          # collect coverage and cost reports
+    - vitest run -- --costs --profile-costs     # also write speedscope cost profiles
+    - vitest run -- --coverage --coverage-format html  # also write an html coverage report
 */
 
 export default defineConfig({
@@ -0,0 +1,247 @@
+use clarity_repl::analysis::trait_registry::StandardTrait;
+
+const SIP_009_TEMPLATE: &str = r#"
+;; title: {{name}}
+;; version:
+;; summary: SIP-009 non-fungible token
+;; description:
+
+;; traits
+(impl-trait '{{trait_id}})
+
+;; token definitions
+(define-non-fungible-token {{name}} uint)
+
+;; constants
+(define-constant contract-owner tx-sender)
+(define-constant err-owner-only (err u100))
+(define-constant err-not-token-owner (err u101))
+
+;; data vars
+(define-data-var last-token-id uint u0)
+
+;; public functions
+(define-public (transfer (token-id uint) (sender principal) (recipient principal))
+  (begin
+    (asserts! (is-eq tx-sender sender) err-not-token-owner)
+    (nft-transfer? {{name}} token-id sender recipient)
+  )
+)
+
+(define-public (mint (recipient principal))
+  (let ((token-id (+ (var-get last-token-id) u1)))
+    (asserts! (is-eq tx-sender contract-owner) err-owner-only)
+    (try! (nft-mint? {{name}} token-id recipient))
+    (var-set last-token-id token-id)
+    (ok token-id)
+  )
+)
+
+;; read only functions
+(define-read-only (get-last-token-id)
+  (ok (var-get last-token-id))
+)
+
+(define-read-only (get-token-uri (token-id uint))
+  (ok none)
+)
+
+(define-read-only (get-owner (token-id uint))
+  (ok (nft-get-owner? {{name}} token-id))
+)
+"#;
+
+const SIP_010_TEMPLATE: &str = r#"
+;; title: {{name}}
+;; version:
+;; summary: SIP-010 fungible token
+;; description:
+
+;; traits
+(impl-trait '{{trait_id}})
+
+;; token definitions
+(define-fungible-token {{name}})
+
+;; constants
+(define-constant contract-owner tx-sender)
+(define-constant err-owner-only (err u100))
+(define-constant err-not-token-owner (err u101))
+
+;; public functions
+(define-public (transfer (amount uint) (sender principal) (recipient principal) (memo (optional (buff 34))))
+  (begin
+    (asserts! (is-eq tx-sender sender) err-not-token-owner)
+    (try! (ft-transfer? {{name}} amount sender recipient))
+    (match memo to-print (print to-print) 0x)
+    (ok true)
+  )
+)
+
+(define-public (mint (amount uint) (recipient principal))
+  (begin
+    (asserts! (is-eq tx-sender contract-owner) err-owner-only)
+    (ft-mint? {{name}} amount recipient)
+  )
+)
+
+;; read only functions
+(define-read-only (get-name)
+  (ok "{{name}}")
+)
+
+(define-read-only (get-symbol)
+  (ok "{{name}}")
+)
+
+(define-read-only (get-decimals)
+  (ok u6)
+)
+
+(define-read-only (get-balance (who principal))
+  (ok (ft-get-balance {{name}} who))
+)
+
+(define-read-only (get-total-supply)
+  (ok (ft-get-supply {{name}}))
+)
+
+(define-read-only (get-token-uri)
+  (ok none)
+)
+"#;
+
+const SIP_013_TEMPLATE: &str = r#"
+;; title: {{name}}
+;; version:
+;; summary: SIP-013 semi-fungible token
+;; description:
+
+;; traits
+(impl-trait '{{trait_id}})
+
+;; token definitions
+(define-fungible-token {{name}})
+
+;; constants
+(define-constant contract-owner tx-sender)
+(define-constant err-owner-only (err u100))
+(define-constant err-insufficient-balance (err u1))
+(define-constant err-invalid-sender (err u4))
+
+;; data maps
+(define-map balances { token-id: uint, owner: principal } uint)
+(define-map supplies uint uint)
+
+;; public functions
+(define-public (transfer (token-id uint) (amount uint) (sender principal) (recipient principal))
+  (let ((sender-balance (unwrap-panic (get-balance token-id sender))))
+    (asserts! (or (is-eq sender tx-sender) (is-eq sender contract-caller)) err-invalid-sender)
+    (asserts! (<= amount sender-balance) err-insufficient-balance)
+    (try! (ft-transfer? {{name}} amount sender recipient))
+    (map-set balances { token-id: token-id, owner: sender } (- sender-balance amount))
+    (map-set balances { token-id: token-id, owner: recipient } (+ (unwrap-panic (get-balance token-id recipient)) amount))
+    (print { type: "sft_transfer", token-id: token-id, amount: amount, sender: sender, recipient: recipient })
+    (ok true)
+  )
+)
+
+(define-public (transfer-memo (token-id uint) (amount uint) (sender principal) (recipient principal) (memo (buff 34)))
+  (begin
+    (try! (transfer token-id amount sender recipient))
+    (print memo)
+    (ok true)
+  )
+)
+
+(define-public (mint (token-id uint) (amount uint) (recipient principal))
+  (begin
+    (asserts! (is-eq tx-sender contract-owner) err-owner-only)
+    (try! (ft-mint? {{name}} amount recipient))
+    (map-set balances { token-id: token-id, owner: recipient } (+ (unwrap-panic (get-balance token-id recipient)) amount))
+    (map-set supplies token-id (+ (unwrap-panic (get-total-supply token-id)) amount))
+    (print { type: "sft_mint", token-id: token-id, amount: amount, recipient: recipient })
+    (ok true)
+  )
+)
+
+;; read only functions
+(define-read-only (get-balance (token-id uint) (who principal))
+  (ok (default-to u0 (map-get? balances { token-id: token-id, owner: who })))
+)
+
+(define-read-only (get-overall-balance (who principal))
+  (ok (ft-get-balance {{name}} who))
+)
+
+(define-read-only (get-total-supply (token-id uint))
+  (ok (default-to u0 (map-get? supplies token-id)))
+)
+
+(define-read-only (get-overall-supply)
+  (ok (ft-get-supply {{name}}))
+)
+
+(define-read-only (get-decimals (token-id uint))
+  (ok u0)
+)
+
+(define-read-only (get-token-uri (token-id uint))
+  (ok none)
+)
+"#;
+
+/// Source of a new contract implementing a standard trait
+pub fn get_trait_template(standard_trait: &StandardTrait, contract_name: &str) -> String {
+    let template = match standard_trait.id {
+        "sip-009" => SIP_009_TEMPLATE,
+        "sip-010" => SIP_010_TEMPLATE,
+        "sip-013" => SIP_013_TEMPLATE,
+        id => unreachable!("no template for standard trait {}", id),
+    };
+    template
+        .replace("{{trait_id}}", &standard_trait.trait_id())
+        .replace("{{name}}", contract_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity_repl::analysis::trait_registry::STANDARD_TRAITS;
+    use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+    use clarity_repl::clarity::ClarityVersion;
+    use clarity_repl::repl::{
+        ClarityCodeSource, ClarityContract, ContractDeployer, Session, SessionSettings,
+        DEFAULT_EPOCH,
+    };
+
+    fn contract(source: String, contract_id: &str) -> ClarityContract {
+        let contract_id = QualifiedContractIdentifier::parse(contract_id).unwrap();
+        ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(source),
+            name: contract_id.name.to_string(),
+            deployer: ContractDeployer::ContractIdentifier(contract_id),
+            clarity_version: ClarityVersion::default_for_epoch(DEFAULT_EPOCH),
+            epoch: DEFAULT_EPOCH,
+        }
+    }
+
+    #[test]
+    fn test_templates_implement_standard_traits() {
+        for standard_trait in STANDARD_TRAITS.iter() {
+            let mut session = Session::new(SessionSettings::default());
+            let requirement = contract(
+                standard_trait.source.to_string(),
+                standard_trait.contract_id,
+            );
+            assert!(session
+                .deploy_contract(&requirement, None, false, None, &mut None)
+                .is_ok());
+
+            let source = get_trait_template(standard_trait, "token");
+            let token = contract(source, "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.token");
+            let result = session.deploy_contract(&token, None, false, None, &mut None);
+            assert!(result.is_ok(), "{}: {:?}", standard_trait.id, result.err());
+        }
+    }
+}
@@ -13,8 +13,10 @@ extern crate lazy_static;
 
 pub extern crate clarity_repl;
 
+pub mod contracts;
 pub mod deployments;
 pub mod generate;
+pub mod testing;
 
 pub mod devnet;
 #[cfg(feature = "cli")]
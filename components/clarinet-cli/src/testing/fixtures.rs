@@ -0,0 +1,71 @@
+//! Counter project shared by the tests of the test runner and of mutation testing
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use clarinet_deployments::types::{
+    DeploymentSpecification, EmulatedContractPublishSpecification, EpochSpec,
+    TransactionPlanSpecification, TransactionSpecification, TransactionsBatchSpecification,
+};
+use clarinet_files::chainhook_types::StacksNetwork;
+use clarinet_files::{FileLocation, ProjectManifest};
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::{ClarityVersion, ContractName};
+
+pub const DEPLOYER: &str = "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM";
+
+pub const COUNTER: &str = r#"(define-constant MAX u1)
+(define-data-var count uint u0)
+(define-read-only (is-full) (>= (var-get count) MAX))
+(define-public (increment)
+  (begin
+    (asserts! (< (var-get count) MAX) (err u1))
+    (var-set count (+ (var-get count) u1))
+    (ok (var-get count))))"#;
+
+/// Write a project with the counter contract, and `files` (name, source) in its tests directory
+pub fn write_project(project_dir: &Path, files: &[(&str, &str)]) -> ProjectManifest {
+    fs::create_dir_all(project_dir.join("tests")).unwrap();
+    fs::write(
+        project_dir.join("Clarinet.toml"),
+        "[project]\nname = \"counter\"\n",
+    )
+    .unwrap();
+    fs::write(project_dir.join("counter.clar"), COUNTER).unwrap();
+    for (name, source) in files {
+        fs::write(project_dir.join("tests").join(name), source).unwrap();
+    }
+
+    ProjectManifest::from_location(&FileLocation::from_path(project_dir.join("Clarinet.toml")))
+        .unwrap()
+}
+
+/// Simnet deployment publishing the counter contract as DEPLOYER
+pub fn counter_deployment(project_dir: &Path) -> DeploymentSpecification {
+    let contract_id = QualifiedContractIdentifier::parse(&format!("{}.counter", DEPLOYER)).unwrap();
+    DeploymentSpecification {
+        id: 0,
+        name: "test".to_string(),
+        network: StacksNetwork::Simnet,
+        stacks_node: None,
+        bitcoin_node: None,
+        genesis: None,
+        contracts: BTreeMap::new(),
+        plan: TransactionPlanSpecification {
+            batches: vec![TransactionsBatchSpecification {
+                id: 0,
+                transactions: vec![TransactionSpecification::EmulatedContractPublish(
+                    EmulatedContractPublishSpecification {
+                        contract_name: ContractName::try_from("counter".to_string()).unwrap(),
+                        emulated_sender: contract_id.issuer.clone(),
+                        location: FileLocation::from_path(project_dir.join("counter.clar")),
+                        source: COUNTER.to_string(),
+                        clarity_version: ClarityVersion::Clarity2,
+                    },
+                )],
+                epoch: Some(EpochSpec::Epoch2_4),
+            }],
+        },
+    }
+}
@@ -0,0 +1,602 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clarity_repl::clarity::analysis::contract_interface_builder::{
+    ContractInterfaceAtomType, ContractInterfaceFunction, ContractInterfaceFunctionAccess,
+};
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::vm::{EvaluationResult, Value};
+use clarity_repl::repl::Session;
+
+pub const INVARIANT_FUNCTION_PREFIX: &str = "invariant-";
+
+const MAX_BLOCKS_ADVANCE: u64 = 10;
+const MAX_GENERATED_LENGTH: u32 = 8;
+
+/// splitmix64: fuzzing campaigns are reproducible from their seed
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn from_time() -> (Rng, u64) {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        (Rng::new(seed), seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in `0..bound` (`bound` must be > 0)
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FuzzStep {
+    Call {
+        sender: String,
+        function: String,
+        args: Vec<String>,
+    },
+    AdvanceChainTip(u64),
+}
+
+impl FuzzStep {
+    /// Console commands performing the step, so that the sequence can be replayed
+    /// with `clarinet fuzz --replay` or pasted in `clarinet console`
+    pub fn to_commands(&self, contract_id: &QualifiedContractIdentifier) -> Vec<String> {
+        match self {
+            FuzzStep::Call {
+                sender,
+                function,
+                args,
+            } => {
+                let mut call = format!("(contract-call? '{} {}", contract_id, function);
+                for arg in args.iter() {
+                    call.push(' ');
+                    call.push_str(arg);
+                }
+                call.push(')');
+                vec![format!("::set_tx_sender {}", sender), call]
+            }
+            FuzzStep::AdvanceChainTip(count) => vec![format!("::advance_chain_tip {}", count)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FailureKind {
+    /// An invariant returned `false` or an `err` response
+    InvariantViolated(String),
+    /// A call to the function was aborted by a runtime error (an `err` response is not a failure)
+    CallFailed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzFailure {
+    pub kind: FailureKind,
+    pub output: String,
+    /// Number of steps executed when the failure occurred
+    pub step: usize,
+}
+
+impl FuzzFailure {
+    pub fn summary(&self) -> String {
+        match &self.kind {
+            FailureKind::InvariantViolated(invariant) => format!("{} violated", invariant),
+            FailureKind::CallFailed(function) => format!("call to {} failed", function),
+        }
+    }
+}
+
+pub struct Fuzzer {
+    session: Session,
+    contract_id: QualifiedContractIdentifier,
+    functions: Vec<ContractInterfaceFunction>,
+    invariants: Vec<String>,
+    senders: Vec<String>,
+    principals: Vec<String>,
+}
+
+impl Fuzzer {
+    /// `session` is the simnet session in which the contract is deployed,
+    /// `senders` the addresses used to issue the calls.
+    pub fn new(
+        session: Session,
+        contract_id: QualifiedContractIdentifier,
+        senders: Vec<String>,
+    ) -> Result<Fuzzer, String> {
+        let contract = session
+            .contracts
+            .get(&contract_id)
+            .ok_or(format!("contract {} not found in the session", contract_id))?;
+        let interface = contract
+            .analysis
+            .contract_interface
+            .clone()
+            .ok_or(format!("unable to get the interface of {}", contract_id))?;
+
+        let functions = interface
+            .functions
+            .iter()
+            .filter(|f| matches!(f.access, ContractInterfaceFunctionAccess::public))
+            .filter(|f| !f.args.iter().any(|a| has_trait_reference(&a.type_f)))
+            .cloned()
+            .collect::<Vec<_>>();
+        let invariants = interface
+            .functions
+            .iter()
+            .filter(|f| matches!(f.access, ContractInterfaceFunctionAccess::read_only))
+            .filter(|f| f.name.starts_with(INVARIANT_FUNCTION_PREFIX) && f.args.is_empty())
+            .map(|f| f.name.clone())
+            .collect::<Vec<_>>();
+
+        if functions.is_empty() {
+            return Err(format!("{} has no public function to fuzz", contract_id));
+        }
+        if senders.is_empty() {
+            return Err("no account available to send transactions".to_string());
+        }
+
+        let mut principals = senders.clone();
+        principals.extend(session.contracts.keys().map(|id| id.to_string()));
+
+        Ok(Fuzzer {
+            session,
+            contract_id,
+            functions,
+            invariants,
+            senders,
+            principals,
+        })
+    }
+
+    pub fn contract_id(&self) -> &QualifiedContractIdentifier {
+        &self.contract_id
+    }
+
+    pub fn invariants(&self) -> &[String] {
+        &self.invariants
+    }
+
+    pub fn generate_steps(&self, rng: &mut Rng, depth: usize) -> Vec<FuzzStep> {
+        (0..depth).map(|_| self.generate_step(rng)).collect()
+    }
+
+    fn generate_step(&self, rng: &mut Rng) -> FuzzStep {
+        if rng.one_in(5) {
+            return FuzzStep::AdvanceChainTip(1 + rng.below(MAX_BLOCKS_ADVANCE));
+        }
+        let function = rng.pick(&self.functions);
+        FuzzStep::Call {
+            sender: rng.pick(&self.senders).clone(),
+            function: function.name.clone(),
+            args: function
+                .args
+                .iter()
+                .map(|arg| self.generate_value(rng, &arg.type_f))
+                .collect(),
+        }
+    }
+
+    /// Random Clarity literal of the given type
+    fn generate_value(&self, rng: &mut Rng, atom: &ContractInterfaceAtomType) -> String {
+        use ContractInterfaceAtomType::*;
+        match atom {
+            none => "none".to_string(),
+            int128 => match rng.below(6) {
+                0 => i128::MAX.to_string(),
+                1 => i128::MIN.to_string(),
+                2 => "0".to_string(),
+                _ => (rng.below(2001) as i128 - 1000).to_string(),
+            },
+            uint128 => match rng.below(6) {
+                0 => format!("u{}", u128::MAX),
+                1 => "u0".to_string(),
+                _ => format!("u{}", rng.below(1001)),
+            },
+            bool => rng.one_in(2).to_string(),
+            principal => format!("'{}", rng.pick(&self.principals)),
+            buffer { length } => {
+                let len = rng.below((*length).min(32) as u64 + 1);
+                let bytes = (0..len).map(|_| rng.below(256) as u8).collect::<Vec<_>>();
+                format!("0x{}", hex::encode(bytes))
+            }
+            string_ascii { length } => format!("\"{}\"", self.generate_text(rng, *length)),
+            string_utf8 { length } => format!("u\"{}\"", self.generate_text(rng, *length)),
+            tuple(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|entry| {
+                        format!(
+                            "{}: {}",
+                            entry.name,
+                            self.generate_value(rng, &entry.type_f)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!("{{ {} }}", entries.join(", "))
+            }
+            optional(inner) => match rng.one_in(3) {
+                true => "none".to_string(),
+                false => format!("(some {})", self.generate_value(rng, inner)),
+            },
+            response { ok, error } => match rng.one_in(2) {
+                true => format!("(ok {})", self.generate_value(rng, ok)),
+                false => format!("(err {})", self.generate_value(rng, error)),
+            },
+            list { type_f, length } => {
+                let len = rng.below((*length).min(MAX_GENERATED_LENGTH) as u64 + 1);
+                let mut list = "(list".to_string();
+                for _ in 0..len {
+                    list.push(' ');
+                    list.push_str(&self.generate_value(rng, type_f));
+                }
+                list.push(')');
+                list
+            }
+            trait_reference => unreachable!("functions taking traits are not fuzzed"),
+        }
+    }
+
+    fn generate_text(&self, rng: &mut Rng, length: u32) -> String {
+        const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789-_ ";
+        let len = rng.below(length.min(MAX_GENERATED_LENGTH) as u64 + 1);
+        (0..len)
+            .map(|_| *rng.pick(CHARSET) as char)
+            .collect::<String>()
+    }
+
+    /// Execute the steps in a fresh copy of the session, checking the invariants
+    /// before the first step and after every call.
+    pub fn run(&self, steps: &[FuzzStep]) -> Option<FuzzFailure> {
+        let mut session = self.session.clone();
+        if let Some(failure) = self.check_invariants(&mut session, 0) {
+            return Some(failure);
+        }
+        for (i, step) in steps.iter().enumerate() {
+            for command in step.to_commands(&self.contract_id) {
+                if let Err(output) = run_command(&mut session, &command) {
+                    if let FuzzStep::Call { function, .. } = step {
+                        return Some(FuzzFailure {
+                            kind: FailureKind::CallFailed(function.clone()),
+                            output,
+                            step: i + 1,
+                        });
+                    }
+                }
+            }
+            if let FuzzStep::Call { .. } = step {
+                if let Some(failure) = self.check_invariants(&mut session, i + 1) {
+                    return Some(failure);
+                }
+            }
+        }
+        None
+    }
+
+    /// Replay console commands (as written by `save_script`), checking the
+    /// invariants after each contract call.
+    pub fn replay(&self, commands: &[String]) -> Option<FuzzFailure> {
+        let mut session = self.session.clone();
+        if let Some(failure) = self.check_invariants(&mut session, 0) {
+            return Some(failure);
+        }
+        for (i, command) in commands.iter().enumerate() {
+            let result = run_command(&mut session, command);
+            if command.starts_with("::") {
+                continue;
+            }
+            if let Err(output) = result {
+                // (contract-call? 'contract function args...)
+                let function = command
+                    .split_whitespace()
+                    .nth(2)
+                    .unwrap_or(command.as_str());
+                return Some(FuzzFailure {
+                    kind: FailureKind::CallFailed(function.trim_end_matches(')').to_string()),
+                    output,
+                    step: i + 1,
+                });
+            }
+            if let Some(failure) = self.check_invariants(&mut session, i + 1) {
+                return Some(failure);
+            }
+        }
+        None
+    }
+
+    /// Remove steps from a failing sequence for as long as it keeps failing
+    /// the same way (same invariant violated, or same function failing).
+    pub fn shrink(
+        &self,
+        steps: &[FuzzStep],
+        failure: &FuzzFailure,
+    ) -> (Vec<FuzzStep>, FuzzFailure) {
+        let mut steps = steps[..failure.step.min(steps.len())].to_vec();
+        let mut failure = failure.clone();
+
+        let mut shrunk = true;
+        while shrunk {
+            shrunk = false;
+            for i in (0..steps.len()).rev() {
+                let mut candidate = steps.clone();
+                let removed = candidate.remove(i);
+                if let FuzzStep::AdvanceChainTip(count) = removed {
+                    // try a shorter advance before dropping it
+                    if count > 1 {
+                        candidate.insert(i, FuzzStep::AdvanceChainTip(1));
+                    }
+                }
+                match self.run(&candidate) {
+                    Some(new_failure) if new_failure.kind == failure.kind => {
+                        candidate.truncate(new_failure.step);
+                        steps = candidate;
+                        failure = new_failure;
+                        shrunk = true;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (steps, failure)
+    }
+
+    fn check_invariants(&self, session: &mut Session, step: usize) -> Option<FuzzFailure> {
+        for invariant in self.invariants.iter() {
+            let snippet = format!("(contract-call? '{} {})", self.contract_id, invariant);
+            let (holds, output) = match run_command(session, &snippet) {
+                Ok(Some(value)) => (is_truthy(&value), value.to_string()),
+                Ok(None) => (false, "no value returned".to_string()),
+                Err(message) => (false, message),
+            };
+            if !holds {
+                return Some(FuzzFailure {
+                    kind: FailureKind::InvariantViolated(invariant.clone()),
+                    output,
+                    step,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Render a sequence of steps as a replayable console script
+pub fn build_script(
+    contract_id: &QualifiedContractIdentifier,
+    steps: &[FuzzStep],
+    header: &str,
+) -> String {
+    let mut script = String::new();
+    for line in header.lines() {
+        script.push_str(&format!(";; {}\n", line));
+    }
+    for step in steps.iter() {
+        for command in step.to_commands(contract_id) {
+            script.push_str(&command);
+            script.push('\n');
+        }
+    }
+    script
+}
+
+/// Console commands of a script, comments and blank lines excluded
+pub fn parse_script(script: &str) -> Vec<String> {
+    script
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with(";;"))
+        .map(|l| l.to_string())
+        .collect()
+}
+
+fn run_command(session: &mut Session, command: &str) -> Result<Option<Value>, String> {
+    if let Some(sender) = command.strip_prefix("::set_tx_sender ") {
+        session.set_tx_sender(sender.trim().to_string());
+        return Ok(None);
+    }
+    if let Some(count) = command.strip_prefix("::advance_chain_tip ") {
+        let count = count
+            .trim()
+            .parse::<u32>()
+            .map_err(|e| format!("invalid block count ({})", e))?;
+        session.advance_chain_tip(count);
+        return Ok(None);
+    }
+    match session.eval(command.to_string(), None, false) {
+        Ok(execution) => match execution.result {
+            EvaluationResult::Snippet(result) => Ok(Some(result.result)),
+            EvaluationResult::Contract(_) => Ok(None),
+        },
+        Err(diagnostics) => Err(diagnostics
+            .iter()
+            .map(|d| d.message.clone())
+            .collect::<Vec<_>>()
+            .join(", ")),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Response(response) => response.committed && is_truthy(&response.data),
+        _ => false,
+    }
+}
+
+fn has_trait_reference(atom: &ContractInterfaceAtomType) -> bool {
+    use ContractInterfaceAtomType::*;
+    match atom {
+        trait_reference => true,
+        tuple(entries) => entries.iter().any(|e| has_trait_reference(&e.type_f)),
+        optional(inner) => has_trait_reference(inner),
+        response { ok, error } => has_trait_reference(ok) || has_trait_reference(error),
+        list { type_f, .. } => has_trait_reference(type_f),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity_repl::clarity::ClarityVersion;
+    use clarity_repl::repl::{
+        ClarityCodeSource, ClarityContract, ContractDeployer, SessionSettings, DEFAULT_EPOCH,
+    };
+
+    const DEPLOYER: &str = "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM";
+    const SENDER: &str = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+
+    fn fuzzer_for(name: &str, snippet: &str) -> Fuzzer {
+        let mut session = Session::new(SessionSettings::default());
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: name.to_string(),
+            deployer: ContractDeployer::Address(DEPLOYER.to_string()),
+            clarity_version: ClarityVersion::Clarity1,
+            epoch: DEFAULT_EPOCH,
+        };
+        session
+            .deploy_contract(&contract, None, false, None, &mut None)
+            .unwrap();
+        let contract_id =
+            QualifiedContractIdentifier::parse(&format!("{}.{}", DEPLOYER, name)).unwrap();
+        Fuzzer::new(session, contract_id, vec![SENDER.to_string()]).unwrap()
+    }
+
+    /// Run sequences until one fails
+    fn find_failure(fuzzer: &Fuzzer) -> (Vec<FuzzStep>, FuzzFailure) {
+        let mut rng = Rng::new(42);
+        for _ in 0..100 {
+            let steps = fuzzer.generate_steps(&mut rng, 20);
+            if let Some(failure) = fuzzer.run(&steps) {
+                return (steps, failure);
+            }
+        }
+        panic!("no failure found");
+    }
+
+    #[test]
+    fn test_fuzz_and_shrink_invariant_violation() {
+        let fuzzer = fuzzer_for(
+            "vault",
+            r#"(define-data-var deposits uint u0)
+(define-read-only (invariant-few-deposits) (< (var-get deposits) u3))
+(define-public (deposit)
+  (begin
+    (var-set deposits (+ (var-get deposits) u1))
+    (ok (var-get deposits))))
+(define-public (echo (amount uint)) (ok amount))"#,
+        );
+        assert_eq!(fuzzer.invariants(), ["invariant-few-deposits"]);
+
+        let (steps, failure) = find_failure(&fuzzer);
+        let expected_kind = FailureKind::InvariantViolated("invariant-few-deposits".to_string());
+        assert_eq!(failure.kind, expected_kind);
+
+        // the three deposits breaking the invariant are kept, the other steps dropped
+        let (steps, failure) = fuzzer.shrink(&steps, &failure);
+        assert_eq!(failure.kind, expected_kind);
+        assert_eq!(failure.step, 3);
+        let deposit = FuzzStep::Call {
+            sender: SENDER.to_string(),
+            function: "deposit".to_string(),
+            args: vec![],
+        };
+        assert_eq!(steps, vec![deposit.clone(), deposit.clone(), deposit]);
+
+        // the saved script reproduces the failure
+        let script = build_script(fuzzer.contract_id(), &steps, "vault");
+        let replayed = fuzzer.replay(&parse_script(&script)).unwrap();
+        assert_eq!(replayed.kind, expected_kind);
+        assert!(fuzzer.replay(&parse_script(&script)[..4]).is_none());
+    }
+
+    #[test]
+    fn test_fuzz_and_shrink_failing_call() {
+        let fuzzer = fuzzer_for(
+            "divider",
+            r#"(define-public (divide (divisor uint)) (ok (/ u10 divisor)))
+(define-public (reject (code uint)) (err code))"#,
+        );
+        assert!(fuzzer.invariants().is_empty());
+
+        // `reject` returns err responses, only the division by zero is a failure
+        let (steps, failure) = find_failure(&fuzzer);
+        assert_eq!(failure.kind, FailureKind::CallFailed("divide".to_string()));
+        assert!(failure.output.contains("DivisionByZero"));
+
+        let (steps, failure) = fuzzer.shrink(&steps, &failure);
+        assert_eq!(failure.kind, FailureKind::CallFailed("divide".to_string()));
+        assert_eq!(
+            steps,
+            vec![FuzzStep::Call {
+                sender: SENDER.to_string(),
+                function: "divide".to_string(),
+                args: vec!["u0".to_string()],
+            }]
+        );
+
+        let script = build_script(fuzzer.contract_id(), &steps, "divider");
+        let replayed = fuzzer.replay(&parse_script(&script)).unwrap();
+        assert_eq!(replayed.kind, FailureKind::CallFailed("divide".to_string()));
+    }
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert!(Rng::new(7).below(3) < 3);
+    }
+
+    #[test]
+    fn test_script_round_trip() {
+        let contract_id =
+            QualifiedContractIdentifier::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter")
+                .unwrap();
+        let steps = vec![
+            FuzzStep::Call {
+                sender: "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5".to_string(),
+                function: "add".to_string(),
+                args: vec!["u1".to_string(), "(some 0x01)".to_string()],
+            },
+            FuzzStep::AdvanceChainTip(3),
+        ];
+        let script = build_script(&contract_id, &steps, "clarinet fuzz counter\nseed: 42");
+        let commands = parse_script(&script);
+        assert_eq!(
+            commands,
+            vec![
+                "::set_tx_sender ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5",
+                "(contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter add u1 (some 0x01))",
+                "::advance_chain_tip 3",
+            ]
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod fuzz;
+pub mod mutation;
+pub mod runner;
+
+#[cfg(test)]
+mod fixtures;
@@ -0,0 +1,400 @@
+use std::collections::BTreeMap;
+
+use clarinet_deployments::setup_session_with_deployment;
+use clarinet_deployments::types::DeploymentSpecification;
+use clarinet_files::ProjectManifest;
+use clarity_repl::clarity::ast::ContractAST;
+use clarity_repl::clarity::vm::representations::{Span, SymbolicExpressionType};
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::vm::{ClarityName, SymbolicExpression, Value};
+
+use super::runner::{run_tests, TestFile};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MutationOperator {
+    FlipComparison,
+    SwapResponse,
+    RemoveAssertion,
+    ChangeConstant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Replacement {
+    Atom(String),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone)]
+pub struct Mutant {
+    pub contract_id: QualifiedContractIdentifier,
+    pub expr_id: u64,
+    pub span: Span,
+    pub operator: MutationOperator,
+    pub replacement: Replacement,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MutantOutcome {
+    /// At least one test failed: the suite caught the mutation
+    Killed(String),
+    /// Every test passed despite the mutation
+    Survived,
+    /// The mutated contract could not be deployed
+    Invalid(String),
+}
+
+/// List the mutants that can be generated from a contract
+pub fn find_mutants(contract_id: &QualifiedContractIdentifier, ast: &ContractAST) -> Vec<Mutant> {
+    let mut mutants = vec![];
+    for expr in ast.expressions.iter() {
+        collect_mutants(contract_id, expr, &mut mutants);
+    }
+    mutants
+}
+
+fn collect_mutants(
+    contract_id: &QualifiedContractIdentifier,
+    expr: &SymbolicExpression,
+    mutants: &mut Vec<Mutant>,
+) {
+    let Some(list) = expr.match_list() else {
+        return;
+    };
+
+    if let Some((head, args)) = list.split_first() {
+        let mut push = |target: &SymbolicExpression, operator, replacement, description| {
+            mutants.push(Mutant {
+                contract_id: contract_id.clone(),
+                expr_id: target.id,
+                span: target.span.clone(),
+                operator,
+                replacement,
+                description,
+            })
+        };
+
+        match head.match_atom().map(|name| name.as_str()) {
+            Some(name @ (">" | ">=" | "<" | "<=")) => {
+                let flipped = match name {
+                    ">" => ">=",
+                    ">=" => ">",
+                    "<" => "<=",
+                    _ => "<",
+                };
+                push(
+                    head,
+                    MutationOperator::FlipComparison,
+                    Replacement::Atom(flipped.to_string()),
+                    format!("`{}` replaced with `{}`", name, flipped),
+                );
+            }
+            Some(name @ ("ok" | "err")) => {
+                let swapped = if name == "ok" { "err" } else { "ok" };
+                push(
+                    head,
+                    MutationOperator::SwapResponse,
+                    Replacement::Atom(swapped.to_string()),
+                    format!("`{}` replaced with `{}`", name, swapped),
+                );
+            }
+            Some("asserts!") => {
+                push(
+                    expr,
+                    MutationOperator::RemoveAssertion,
+                    Replacement::Literal(Value::Bool(true)),
+                    "`asserts!` removed".to_string(),
+                );
+            }
+            Some("define-constant") => {
+                if let Some(value_expr) = args.get(1) {
+                    if let Some(value) = value_expr.match_literal_value() {
+                        if let Some(changed) = change_constant(value) {
+                            push(
+                                value_expr,
+                                MutationOperator::ChangeConstant,
+                                Replacement::Literal(changed.clone()),
+                                format!("constant `{}` changed to `{}`", value, changed),
+                            );
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for expr in list.iter() {
+        collect_mutants(contract_id, expr, mutants);
+    }
+}
+
+fn change_constant(value: &Value) -> Option<Value> {
+    match value {
+        Value::Int(n) => Some(Value::Int(n.checked_add(1).unwrap_or(n - 1))),
+        Value::UInt(n) => Some(Value::UInt(n.checked_add(1).unwrap_or(n - 1))),
+        _ => None,
+    }
+}
+
+/// Copy of the ast with the mutation applied
+pub fn apply_mutant(ast: &ContractAST, mutant: &Mutant) -> Result<ContractAST, String> {
+    let mut ast = ast.clone();
+    let expr_type = match mutant.replacement {
+        Replacement::Atom(ref name) => SymbolicExpressionType::Atom(
+            ClarityName::try_from(name.clone()).map_err(|e| format!("{:?}", e))?,
+        ),
+        Replacement::Literal(ref value) => SymbolicExpressionType::LiteralValue(value.clone()),
+    };
+    if !replace_expression(&mut ast.expressions, mutant.expr_id, expr_type) {
+        return Err(format!("expression {} not found", mutant.expr_id));
+    }
+    Ok(ast)
+}
+
+fn replace_expression(
+    exprs: &mut [SymbolicExpression],
+    expr_id: u64,
+    expr_type: SymbolicExpressionType,
+) -> bool {
+    for expr in exprs.iter_mut() {
+        if expr.id == expr_id {
+            expr.expr = expr_type;
+            return true;
+        }
+        if let SymbolicExpressionType::List(ref mut list) = expr.expr {
+            if replace_expression(list, expr_id, expr_type.clone()) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Deploy each mutant in a new simnet session, and run the test suite against it
+pub fn run_mutation_testing(
+    manifest: &ProjectManifest,
+    deployment: &DeploymentSpecification,
+    asts: &BTreeMap<QualifiedContractIdentifier, ContractAST>,
+    mutants: &[Mutant],
+    test_files: &[TestFile],
+    on_mutant_tested: &dyn Fn(&Mutant, &MutantOutcome),
+) -> Result<Vec<MutantOutcome>, String> {
+    let baseline = setup_session_with_deployment(manifest, deployment, Some(asts));
+    let report = run_tests(&baseline.session, deployment, test_files, &|_| {})?;
+    if report.failed() > 0 {
+        return Err("the test suite must pass before running mutation testing".to_string());
+    }
+
+    let mut outcomes = vec![];
+    for mutant in mutants.iter() {
+        let outcome = test_mutant(manifest, deployment, asts, mutant, test_files)?;
+        on_mutant_tested(mutant, &outcome);
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+fn test_mutant(
+    manifest: &ProjectManifest,
+    deployment: &DeploymentSpecification,
+    asts: &BTreeMap<QualifiedContractIdentifier, ContractAST>,
+    mutant: &Mutant,
+    test_files: &[TestFile],
+) -> Result<MutantOutcome, String> {
+    let original = asts
+        .get(&mutant.contract_id)
+        .ok_or(format!("ast of {} not found", mutant.contract_id))?;
+    let mut mutated_asts = asts.clone();
+    mutated_asts.insert(mutant.contract_id.clone(), apply_mutant(original, mutant)?);
+
+    let artifacts = setup_session_with_deployment(manifest, deployment, Some(&mutated_asts));
+    if !artifacts.success {
+        let message = artifacts
+            .diags
+            .get(&mutant.contract_id)
+            .and_then(|diags| diags.first())
+            .map(|d| d.message.clone())
+            .unwrap_or_else(|| "deployment failed".to_string());
+        return Ok(MutantOutcome::Invalid(message));
+    }
+
+    let report = run_tests(&artifacts.session, deployment, test_files, &|_| {})?;
+    Ok(match report.results.iter().find(|result| !result.passed) {
+        Some(result) => {
+            MutantOutcome::Killed(format!("{}::{}", result.test_file, result.test_name))
+        }
+        None => MutantOutcome::Survived,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{counter_deployment, write_project, COUNTER, DEPLOYER};
+    use clarinet_files::FileLocation;
+    use clarity_repl::clarity::vm::types::StandardPrincipalData;
+    use clarity_repl::clarity::{ClarityVersion, StacksEpochId};
+    use clarity_repl::repl::{
+        ClarityCodeSource, ClarityContract, ClarityInterpreter, ContractDeployer, Settings,
+    };
+    use std::fs;
+
+    // `is-full` is not tested: mutating it can't be caught
+    const COUNTER_TEST: &str = r#"(define-public (test-increment)
+  (begin
+    (try! (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment))
+    (asserts! (is-err (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment)) (err u100))
+    (ok true)))"#;
+
+    fn build_ast(snippet: &str) -> ContractAST {
+        let interpreter =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: "contract".to_string(),
+            deployer: ContractDeployer::DefaultDeployer,
+            clarity_version: ClarityVersion::Clarity2,
+            epoch: StacksEpochId::Epoch24,
+        };
+        let (ast, _, success) = interpreter.build_ast(&contract);
+        assert!(success);
+        ast
+    }
+
+    fn mutants_of(snippet: &str) -> Vec<Mutant> {
+        find_mutants(
+            &QualifiedContractIdentifier::transient(),
+            &build_ast(snippet),
+        )
+    }
+
+    fn descriptions(mutants: &[Mutant], operator: MutationOperator) -> Vec<String> {
+        mutants
+            .iter()
+            .filter(|mutant| mutant.operator == operator)
+            .map(|mutant| mutant.description.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_find_mutants() {
+        let mutants = mutants_of(COUNTER);
+        assert_eq!(
+            descriptions(&mutants, MutationOperator::FlipComparison),
+            vec!["`>=` replaced with `>`", "`<` replaced with `<=`"]
+        );
+        assert_eq!(
+            descriptions(&mutants, MutationOperator::SwapResponse),
+            vec!["`err` replaced with `ok`", "`ok` replaced with `err`"]
+        );
+        assert_eq!(
+            descriptions(&mutants, MutationOperator::RemoveAssertion),
+            vec!["`asserts!` removed"]
+        );
+        assert_eq!(
+            descriptions(&mutants, MutationOperator::ChangeConstant),
+            vec!["constant `u1` changed to `u2`"]
+        );
+
+        let mutants = mutants_of("(define-private (compare (a int)) (and (> a 0) (<= a 9)))");
+        assert_eq!(
+            descriptions(&mutants, MutationOperator::FlipComparison),
+            vec!["`>` replaced with `>=`", "`<=` replaced with `<`"]
+        );
+    }
+
+    #[test]
+    fn test_change_constant() {
+        let mutants = mutants_of(
+            r#"(define-constant MAX_UINT u340282366920938463463374607431768211455)
+(define-constant MAX_INT 170141183460469231731687303715884105727)
+(define-constant MIN_INT -5)
+(define-constant NAME "counter")"#,
+        );
+        let replacements = mutants
+            .iter()
+            .map(|mutant| mutant.replacement.clone())
+            .collect::<Vec<_>>();
+        // integers at their maximum are decremented instead of overflowing
+        assert_eq!(
+            replacements,
+            vec![
+                Replacement::Literal(Value::UInt(u128::MAX - 1)),
+                Replacement::Literal(Value::Int(i128::MAX - 1)),
+                Replacement::Literal(Value::Int(-4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_mutant() {
+        let ast = build_ast(COUNTER);
+        let mutants = find_mutants(&QualifiedContractIdentifier::transient(), &ast);
+
+        let flip = mutants
+            .iter()
+            .find(|mutant| mutant.description == "`<` replaced with `<=`")
+            .unwrap();
+        let mutated = apply_mutant(&ast, flip).unwrap();
+        assert_ne!(mutated.expressions, ast.expressions);
+        let mutated_mutants = find_mutants(&QualifiedContractIdentifier::transient(), &mutated);
+        assert!(mutated_mutants
+            .iter()
+            .any(|mutant| mutant.description == "`<=` replaced with `<`"));
+
+        let assertion = mutants
+            .iter()
+            .find(|mutant| mutant.operator == MutationOperator::RemoveAssertion)
+            .unwrap();
+        let mutated = apply_mutant(&ast, assertion).unwrap();
+        assert!(
+            find_mutants(&QualifiedContractIdentifier::transient(), &mutated)
+                .iter()
+                .all(|mutant| mutant.operator != MutationOperator::RemoveAssertion)
+        );
+
+        let mut missing = flip.clone();
+        missing.expr_id = u64::MAX;
+        assert!(apply_mutant(&ast, &missing).is_err());
+    }
+
+    #[test]
+    fn test_run_mutation_testing() {
+        let project_dir = std::env::temp_dir().join(format!("mutation-{}", std::process::id()));
+        let manifest = write_project(&project_dir, &[("counter_test.clar", COUNTER_TEST)]);
+        let contract_id =
+            QualifiedContractIdentifier::parse(&format!("{}.counter", DEPLOYER)).unwrap();
+        let deployment = counter_deployment(&project_dir);
+        let test_location = project_dir.join("tests").join("counter_test.clar");
+        let test_files = vec![TestFile {
+            contract_name: "counter_test".to_string(),
+            location: FileLocation::from_path(test_location),
+        }];
+
+        let artifacts = setup_session_with_deployment(&manifest, &deployment, None);
+        assert!(artifacts.success);
+        let mutants = find_mutants(&contract_id, artifacts.asts.get(&contract_id).unwrap())
+            .into_iter()
+            .filter(|mutant| mutant.operator == MutationOperator::FlipComparison)
+            .collect::<Vec<_>>();
+
+        let outcomes = run_mutation_testing(
+            &manifest,
+            &deployment,
+            &artifacts.asts,
+            &mutants,
+            &test_files,
+            &|_, _| {},
+        );
+        let _ = fs::remove_dir_all(&project_dir);
+
+        assert_eq!(
+            outcomes.unwrap(),
+            vec![
+                MutantOutcome::Survived,
+                MutantOutcome::Killed("counter_test::test-increment".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use clarinet_deployments::types::DeploymentSpecification;
+use clarinet_files::{FileLocation, ProjectManifest};
+use clarity_repl::analysis::coverage::CoverageReporter;
+use clarity_repl::clarity::vm::types::{FunctionType, QualifiedContractIdentifier};
+use clarity_repl::clarity::vm::{EvaluationResult, Value};
+use clarity_repl::clarity::ClarityVersion;
+use clarity_repl::repl::session::CostsReport;
+use clarity_repl::repl::{ClarityCodeSource, ClarityContract, ContractDeployer, Session};
+use serde_json::Value as JsonValue;
+
+pub const TEST_FILE_SUFFIX: &str = "_test.clar";
+pub const TEST_FUNCTION_PREFIX: &str = "test-";
+
+#[derive(Debug, Clone)]
+pub struct TestFile {
+    pub contract_name: String,
+    pub location: FileLocation,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    pub test_file: String,
+    pub test_name: String,
+    pub passed: bool,
+    /// Returned value, or error message
+    pub output: String,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct TestsReport {
+    pub results: Vec<TestResult>,
+    pub coverage: CoverageReporter,
+    pub costs: Vec<CostsReport>,
+}
+
+impl TestsReport {
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.passed).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|r| !r.passed).count()
+    }
+
+    pub fn costs_to_json(&self) -> JsonValue {
+        let costs = self
+            .costs
+            .iter()
+            .map(|report| {
+                json!({
+                    "test_name": report.test_name,
+                    "contract_id": report.contract_id,
+                    "method": report.method,
+                    "args": report.args,
+                    "cost_result": {
+                        "total": report.cost_result.total,
+                        "limit": report.cost_result.limit,
+                        "memory": report.cost_result.memory,
+                        "memory_limit": report.cost_result.memory_limit,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        JsonValue::Array(costs)
+    }
+}
+
+/// Collect the `tests/*_test.clar` files of the project, sorted by name
+pub fn discover_test_files(manifest: &ProjectManifest) -> Result<Vec<TestFile>, String> {
+    let mut tests_location = manifest.location.get_project_root_location()?;
+    tests_location.append_path("tests")?;
+    let tests_path = tests_location.to_string();
+
+    let entries = match fs::read_dir(&tests_path) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(vec![]),
+    };
+
+    let mut test_files = vec![];
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if let Some(stem) = file_name.strip_suffix(TEST_FILE_SUFFIX) {
+            test_files.push(TestFile {
+                contract_name: format!("{}_test", stem),
+                location: FileLocation::from_path(entry.path()),
+            });
+        }
+    }
+    test_files.sort_by(|a, b| a.contract_name.cmp(&b.contract_name));
+    Ok(test_files)
+}
+
+/// Run every `test-` prefixed public function of the test files, each of them
+/// in a fresh copy of `session` (the session built from the simnet deployment plan).
+pub fn run_tests(
+    session: &Session,
+    deployment: &DeploymentSpecification,
+    test_files: &[TestFile],
+    on_test_completed: &dyn Fn(&TestResult),
+) -> Result<TestsReport, String> {
+    let mut report = TestsReport::default();
+
+    for test_file in test_files.iter() {
+        let code = test_file.location.read_content_as_utf8()?;
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(code),
+            name: test_file.contract_name.clone(),
+            deployer: ContractDeployer::DefaultDeployer,
+            clarity_version: ClarityVersion::default_for_epoch(session.current_epoch),
+            epoch: session.current_epoch,
+        };
+
+        let test_functions = match list_test_functions(session, &contract) {
+            Ok(test_functions) => test_functions,
+            Err(message) => {
+                let result = TestResult {
+                    test_file: test_file.contract_name.clone(),
+                    test_name: "(deployment)".to_string(),
+                    passed: false,
+                    output: message,
+                    duration: Duration::default(),
+                };
+                on_test_completed(&result);
+                report.results.push(result);
+                continue;
+            }
+        };
+
+        for (test_name, takes_args) in test_functions.into_iter() {
+            let mut test_session = session.clone();
+            let start = Instant::now();
+            let outcome = if takes_args {
+                Err("test functions can not take arguments".to_string())
+            } else {
+                run_test_function(&mut test_session, &contract, &test_name)
+            };
+            let duration = start.elapsed();
+
+            let (passed, output) = match outcome {
+                Ok(value) => match value {
+                    Value::Response(ref response) => (response.committed, value.to_string()),
+                    _ => (false, format!("expected a response, got {}", value)),
+                },
+                Err(message) => (false, message),
+            };
+            let result = TestResult {
+                test_file: test_file.contract_name.clone(),
+                test_name,
+                passed,
+                output,
+                duration,
+            };
+            on_test_completed(&result);
+            report.results.push(result);
+
+            report
+                .coverage
+                .reports
+                .append(&mut test_session.coverage_reports);
+            report.costs.append(&mut test_session.costs_reports);
+        }
+    }
+
+    for (contract_id, contract) in session.contracts.iter() {
+        report
+            .coverage
+            .asts
+            .insert(contract_id.clone(), contract.ast.clone());
+    }
+    let contracts_paths: BTreeMap<String, String> = deployment
+        .contracts
+        .iter()
+        .map(|(contract_id, (_, location))| (contract_id.name.to_string(), location.to_string()))
+        .collect();
+    report.coverage.contract_paths = contracts_paths;
+
+    Ok(report)
+}
+
+/// Deploy the test contract in a scratch session and list its test functions,
+/// along with whether they take arguments
+fn list_test_functions(
+    session: &Session,
+    contract: &ClarityContract,
+) -> Result<Vec<(String, bool)>, String> {
+    let mut session = session.clone();
+    let execution = deploy_test_contract(&mut session, contract)?;
+    let EvaluationResult::Contract(result) = execution else {
+        return Ok(vec![]);
+    };
+    Ok(result
+        .contract
+        .analysis
+        .public_function_types
+        .iter()
+        .filter(|(name, _)| name.starts_with(TEST_FUNCTION_PREFIX))
+        .map(|(name, signature)| {
+            let takes_args = match signature {
+                FunctionType::Fixed(function) => !function.args.is_empty(),
+                _ => true,
+            };
+            (name.to_string(), takes_args)
+        })
+        .collect())
+}
+
+fn deploy_test_contract(
+    session: &mut Session,
+    contract: &ClarityContract,
+) -> Result<EvaluationResult, String> {
+    session
+        .deploy_contract(contract, None, false, None, &mut None)
+        .map(|execution| execution.result)
+        .map_err(|diagnostics| {
+            let messages = diagnostics
+                .iter()
+                .map(|d| d.message.clone())
+                .collect::<Vec<_>>();
+            format!(
+                "unable to deploy {}: {}",
+                contract.name,
+                messages.join(", ")
+            )
+        })
+}
+
+fn run_test_function(
+    session: &mut Session,
+    contract: &ClarityContract,
+    test_name: &str,
+) -> Result<Value, String> {
+    deploy_test_contract(session, contract)?;
+
+    let sender = session.get_tx_sender();
+    let contract_id = QualifiedContractIdentifier::parse(&format!("{}.{}", sender, contract.name))
+        .map_err(|e| format!("invalid test contract identifier ({})", e))?;
+    let execution = session
+        .call_contract_fn(
+            &contract_id.to_string(),
+            test_name,
+            &[],
+            &sender,
+            false,
+            true,
+            true,
+            format!("{}::{}", contract.name, test_name),
+        )
+        .map_err(|diagnostics| {
+            diagnostics
+                .iter()
+                .map(|d| d.message.clone())
+                .collect::<Vec<_>>()
+                .join(", ")
+        })?;
+
+    match execution.result {
+        EvaluationResult::Snippet(result) => Ok(result.result),
+        EvaluationResult::Contract(_) => Err("unexpected contract evaluation".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{counter_deployment, write_project};
+    use clarinet_deployments::setup_session_with_deployment;
+    use std::cell::RefCell;
+    use std::path::Path;
+
+    // every test runs in its own session, the counter is reset between them
+    const COUNTER_TEST: &str = r#"(define-public (test-increment)
+  (begin
+    (asserts! (is-eq (try! (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment)) u1) (err u100))
+    (ok true)))
+(define-public (test-increment-twice)
+  (begin
+    (try! (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment))
+    (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment)))
+(define-public (test-unwrap-increment)
+  (begin
+    (try! (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment))
+    (ok (unwrap-panic (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment)))))
+(define-public (test-increment-by (n uint))
+  (begin
+    (asserts! (> n u0) (err u100))
+    (ok n)))
+(define-public (increment-counter)
+  (contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter increment))"#;
+
+    fn write_counter_project(project_dir: &Path) -> ProjectManifest {
+        write_project(
+            project_dir,
+            &[
+                ("counter_test.clar", COUNTER_TEST),
+                ("a_test.clar", "(define-read-only (get-one) u1)"),
+                ("helpers.clar", "(define-read-only (get-one) u1)"),
+                ("counter.test.ts", ""),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_run_tests() {
+        let project_dir = std::env::temp_dir().join(format!("runner-{}", std::process::id()));
+        let manifest = write_counter_project(&project_dir);
+        let deployment = counter_deployment(&project_dir);
+
+        // only the `_test.clar` files are test files, sorted by name
+        let test_files = discover_test_files(&manifest).unwrap();
+        let names = test_files
+            .iter()
+            .map(|test_file| test_file.contract_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a_test", "counter_test"]);
+
+        let artifacts = setup_session_with_deployment(&manifest, &deployment, None);
+        assert!(artifacts.success);
+        let completed = RefCell::new(vec![]);
+        let report = run_tests(&artifacts.session, &deployment, &test_files, &|result| {
+            completed.borrow_mut().push(result.test_name.clone())
+        });
+        let _ = fs::remove_dir_all(&project_dir);
+        let report = report.unwrap();
+
+        // the test functions are the `test-` prefixed public functions
+        let results = report
+            .results
+            .iter()
+            .map(|result| {
+                (
+                    result.test_file.as_str(),
+                    result.test_name.as_str(),
+                    result.passed,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                ("counter_test", "test-increment", true),
+                ("counter_test", "test-increment-by", false),
+                ("counter_test", "test-increment-twice", false),
+                ("counter_test", "test-unwrap-increment", false),
+            ]
+        );
+        assert_eq!(
+            *completed.borrow(),
+            vec![
+                "test-increment",
+                "test-increment-by",
+                "test-increment-twice",
+                "test-unwrap-increment"
+            ]
+        );
+        assert_eq!(report.passed(), 1);
+        assert_eq!(report.failed(), 3);
+
+        // passing tests report their returned value, failing tests their err
+        // response or their error
+        let outputs = report
+            .results
+            .iter()
+            .map(|result| result.output.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(outputs[0], "(ok true)");
+        assert_eq!(outputs[1], "test functions can not take arguments");
+        assert_eq!(outputs[2], "(err u1)");
+        assert!(outputs[3].contains("UnwrapFailure"));
+    }
+
+    #[test]
+    fn test_run_undeployable_test_file() {
+        let project_dir =
+            std::env::temp_dir().join(format!("runner-undeployable-{}", std::process::id()));
+        let manifest = write_counter_project(&project_dir);
+        fs::write(
+            project_dir.join("tests").join("counter_test.clar"),
+            "(define-public (test-unknown) (ok (unknown-function)))",
+        )
+        .unwrap();
+        let deployment = counter_deployment(&project_dir);
+
+        let test_files = discover_test_files(&manifest).unwrap();
+        let artifacts = setup_session_with_deployment(&manifest, &deployment, None);
+        let report = run_tests(&artifacts.session, &deployment, &test_files, &|_| {});
+        let _ = fs::remove_dir_all(&project_dir);
+        let report = report.unwrap();
+
+        assert_eq!(report.results.len(), 1);
+        let result = &report.results[0];
+        assert_eq!(result.test_name, "(deployment)");
+        assert!(!result.passed);
+        assert!(result.output.starts_with("unable to deploy counter_test"));
+    }
+}
@@ -29,6 +29,7 @@ use clarity_repl::clarity::vm::diagnostic::Diagnostic;
 use clarity_repl::clarity::vm::types::PrincipalData;
 use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
 use clarity_repl::clarity::vm::ContractName;
+use clarity_repl::clarity::vm::EvalHook;
 use clarity_repl::clarity::vm::EvaluationResult;
 use clarity_repl::clarity::vm::ExecutionResult;
 use clarity_repl::repl::session::BOOT_CONTRACTS_DATA;
@@ -46,6 +47,8 @@ pub type ExecutionResultMap =
 pub struct UpdateSessionExecutionResult {
     pub boot_contracts: ExecutionResultMap,
     pub contracts: ExecutionResultMap,
+    /// Emulated contract calls of the plan, as `contract_id::method`, in the order of execution
+    pub contract_calls: Vec<(String, Result<ExecutionResult, Vec<Diagnostic>>)>,
 }
 
 pub fn setup_session_with_deployment(
@@ -61,6 +64,7 @@ pub fn setup_session_with_deployment(
         contracts_asts,
         false,
         None,
+        None,
     );
 
     let deps = BTreeMap::new();
@@ -129,6 +133,7 @@ pub fn update_session_with_contracts_executions(
     contracts_asts: Option<&BTreeMap<QualifiedContractIdentifier, ContractAST>>,
     code_coverage_enabled: bool,
     forced_min_epoch: Option<StacksEpochId>,
+    mut eval_hook: Option<&mut dyn EvalHook>,
 ) -> UpdateSessionExecutionResult {
     let boot_contracts_data = BOOT_CONTRACTS_DATA.clone();
 
@@ -141,6 +146,7 @@ pub fn update_session_with_contracts_executions(
     }
 
     let mut contracts = BTreeMap::new();
+    let mut contract_calls = vec![];
     for batch in deployment.plan.batches.iter() {
         let epoch: StacksEpochId = match (batch.epoch, forced_min_epoch) {
             (Some(epoch), _) => epoch.into(),
@@ -184,7 +190,9 @@ pub fn update_session_with_contracts_executions(
 
                     let result = session.deploy_contract(
                         &contract,
-                        None,
+                        eval_hook
+                            .as_deref_mut()
+                            .map(|hook| vec![hook as &mut dyn EvalHook]),
                         false,
                         match code_coverage_enabled {
                             true => Some("__analysis__".to_string()),
@@ -196,13 +204,20 @@ pub fn update_session_with_contracts_executions(
                     session.set_tx_sender(default_tx_sender);
                 }
                 TransactionSpecification::EmulatedContractCall(tx) => {
-                    let _ = session.invoke_contract_call(
+                    let result = session.invoke_contract_call(
                         &tx.contract_id.to_string(),
                         &tx.method.to_string(),
                         &tx.parameters,
                         &tx.emulated_sender.to_string(),
                         "deployment".to_string(),
+                        eval_hook
+                            .as_deref_mut()
+                            .map(|hook| vec![hook as &mut dyn EvalHook]),
                     );
+                    contract_calls.push((
+                        format!("{}::{}", tx.contract_id, tx.method),
+                        result.map(|(execution, _)| execution),
+                    ));
                 }
             }
         }
@@ -211,6 +226,7 @@ pub fn update_session_with_contracts_executions(
     UpdateSessionExecutionResult {
         boot_contracts,
         contracts,
+        contract_calls,
     }
 }
 
@@ -5,7 +5,7 @@ use bip39::{Language, Mnemonic};
 use chainhook_types::{BitcoinNetwork, StacksNetwork};
 use clarinet_utils::get_bip39_seed_from_mnemonic;
 use clarity_repl::clarity::util::hash::bytes_to_hex;
-use clarity_repl::clarity::util::secp256k1::Secp256k1PublicKey;
+use clarity_repl::clarity::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
 use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
 use clarity_repl::clarity::{address::AddressHashMode, chainstate::StacksAddress};
 use libsecp256k1::{PublicKey, SecretKey};
@@ -28,6 +28,11 @@ pub const DEFAULT_SUBNET_CONTRACT_ID: &str =
     "ST173JK7NZBA4BS05ZRATQH1K89YJMTGEH1Z5J52E.subnet-v3-0-1";
 pub const DEFAULT_STACKS_MINER_MNEMONIC: &str = "fragile loan twenty basic net assault jazz absorb diet talk art shock innocent float punch travel gadget embrace caught blossom hockey surround initial reduce";
 pub const DEFAULT_FAUCET_MNEMONIC: &str = "shadow private easily thought say logic fault paddle word top book during ignore notable orange flight clock image wealth health outside kitten belt reform";
+// Secret keys of the default wallet_1 and wallet_2, used as signers when no signer set is configured
+pub const DEFAULT_SIGNERS_SECRET_KEYS: [&str; 2] = [
+    "7287ba251d44a4d3fd9276c88ce34c5c52a038955511cccaf77e61068649c17801",
+    "530d9f61984c888536871c6573073bdfc0058896dc1adfe9a6a10dfacadc209101",
+];
 pub const DEFAULT_SUBNET_MNEMONIC: &str = "twice kind fence tip hidden tilt action fragile skin nothing glory cousin green tomorrow spring wrist shed math olympic multiply hip blue scout claw";
 #[cfg(unix)]
 pub const DEFAULT_DOCKER_SOCKET: &str = "unix:///var/run/docker.sock";
@@ -82,6 +87,9 @@ pub struct DevnetConfigFile {
     pub stacks_node_p2p_port: Option<u16>,
     pub stacks_node_rpc_port: Option<u16>,
     pub stacks_node_events_observers: Option<Vec<String>>,
+    pub stacks_node_events_proxy_port: Option<u16>,
+    pub stacks_miners: Option<u32>,
+    pub stacks_followers: Option<u32>,
     pub stacks_node_wait_time_for_microblocks: Option<u32>,
     pub stacks_node_first_attempt_time_ms: Option<u32>,
     pub stacks_node_subsequent_attempt_time_ms: Option<u32>,
@@ -103,13 +111,18 @@ pub struct DevnetConfigFile {
     pub faucet_derivation_path: Option<String>,
     pub bitcoin_controller_block_time: Option<u32>,
     pub bitcoin_controller_automining_disabled: Option<bool>,
+    pub bitcoin_controller_accelerated_mining: Option<bool>,
     pub working_dir: Option<String>,
+    pub record_events_path: Option<String>,
+    pub enable_events_delay: Option<bool>,
     pub postgres_port: Option<u16>,
     pub postgres_username: Option<String>,
     pub postgres_password: Option<String>,
     pub stacks_api_postgres_database: Option<String>,
     pub subnet_api_postgres_database: Option<String>,
     pub pox_stacking_orders: Option<Vec<PoxStackingOrder>>,
+    pub signers: Option<DevnetSignersConfigFile>,
+    pub faults: Option<Vec<ScheduledFault>>,
     pub execute_script: Option<Vec<ExecuteScript>>,
     pub bitcoin_node_image_url: Option<String>,
     pub bitcoin_explorer_image_url: Option<String>,
@@ -159,11 +172,29 @@ pub struct PoxStackingOrderFile {
     pub bitcoin_address: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DevnetSignersConfigFile {
+    /// Only parsed to be rejected: the signing threshold is set by the protocol
+    pub threshold: Option<u32>,
+    pub accounts: Vec<DevnetSignerConfigFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DevnetSignerConfigFile {
+    pub wallet: String,
+    pub start: Option<bool>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExecuteScript {
     pub script: String,
     pub allow_wallets: bool,
+    /// Advisory: exposed to the script as CLARINET_ALLOW_WRITE, scripts are not sandboxed
     pub allow_write: bool,
+    /// Run at this bitcoin block height instead of after the deployment plan
+    pub at_block: Option<u64>,
+    /// A failing critical script terminates the devnet
+    pub critical: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -242,7 +273,11 @@ pub struct DevnetConfig {
     pub stacks_node_first_attempt_time_ms: u32,
     pub stacks_node_subsequent_attempt_time_ms: u32,
     pub stacks_node_events_observers: Vec<String>,
+    pub stacks_node_events_proxy_port: u16,
     pub stacks_node_env_vars: Vec<String>,
+    pub stacks_miners: u32,
+    pub stacks_followers: u32,
+    pub extra_stacks_nodes: Vec<DevnetStacksNode>,
     pub stacks_api_port: u16,
     pub stacks_api_events_port: u16,
     pub stacks_api_env_vars: Vec<String>,
@@ -251,6 +286,7 @@ pub struct DevnetConfig {
     pub bitcoin_explorer_port: u16,
     pub bitcoin_controller_block_time: u32,
     pub bitcoin_controller_automining_disabled: bool,
+    pub bitcoin_controller_accelerated_mining: bool,
     pub miner_stx_address: String,
     pub miner_secret_key_hex: String,
     pub miner_btc_address: String,
@@ -264,12 +300,16 @@ pub struct DevnetConfig {
     pub faucet_mnemonic: String,
     pub faucet_derivation_path: String,
     pub working_dir: String,
+    pub record_events_path: Option<String>,
+    pub enable_events_delay: bool,
     pub postgres_port: u16,
     pub postgres_username: String,
     pub postgres_password: String,
     pub stacks_api_postgres_database: String,
     pub subnet_api_postgres_database: String,
     pub pox_stacking_orders: Vec<PoxStackingOrder>,
+    pub signers: Vec<DevnetSigner>,
+    pub faults: Vec<ScheduledFault>,
     pub execute_script: Vec<ExecuteScript>,
     pub bitcoin_node_image_url: String,
     pub stacks_node_image_url: String,
@@ -315,6 +355,24 @@ pub struct DevnetConfig {
     pub docker_platform: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DevnetStacksNode {
+    pub name: String,
+    pub is_miner: bool,
+    pub p2p_port: u16,
+    pub rpc_port: u16,
+    pub stx_address: String,
+    pub btc_address: String,
+    pub secret_key_hex: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DevnetSigner {
+    pub wallet: String,
+    pub secret_key_hex: String,
+    pub start: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PoxStackingOrder {
     pub start_at_cycle: u32,
@@ -323,6 +381,35 @@ pub struct PoxStackingOrder {
     pub slots: u64,
     pub btc_address: String,
     pub auto_extend: Option<bool>,
+    pub signer: Option<String>,
+}
+
+/// Fault injected in a running devnet, either scheduled from Devnet.toml or
+/// requested at runtime.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DevnetFault {
+    /// Invalidate the last `depth` bitcoin blocks and mine a longer fork
+    BitcoinReorg { depth: u32 },
+    /// Freeze a service container (eg. `stacks-node`) for `duration_ms`
+    PauseService { service: String, duration_ms: u64 },
+    /// Split the devnet in two for `duration_ms`: the listed services are moved to
+    /// a network of their own, they reach each other but not the other services
+    Partition {
+        services: Vec<String>,
+        duration_ms: u64,
+    },
+    /// Hold the events delivered to `stacks_node_events_observers` for `delay_ms`,
+    /// during `duration_ms`
+    DelayEvents { delay_ms: u64, duration_ms: u64 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScheduledFault {
+    /// Bitcoin block height triggering the fault
+    pub at_block: u64,
+    #[serde(flatten)]
+    pub fault: DevnetFault,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -513,6 +600,14 @@ impl NetworkManifest {
                     devnet_config.stacks_node_events_observers = Some(val.clone());
                 }
 
+                if let Some(val) = devnet_override.stacks_miners {
+                    devnet_config.stacks_miners = Some(val);
+                }
+
+                if let Some(val) = devnet_override.stacks_followers {
+                    devnet_config.stacks_followers = Some(val);
+                }
+
                 if let Some(val) = devnet_override.stacks_api_port {
                     devnet_config.stacks_api_port = Some(val);
                 }
@@ -553,6 +648,14 @@ impl NetworkManifest {
                     devnet_config.working_dir = Some(val.clone());
                 }
 
+                if let Some(ref val) = devnet_override.record_events_path {
+                    devnet_config.record_events_path = Some(val.clone());
+                }
+
+                if let Some(val) = devnet_override.enable_events_delay {
+                    devnet_config.enable_events_delay = Some(val);
+                }
+
                 if let Some(val) = devnet_override.postgres_port {
                     devnet_config.postgres_port = Some(val);
                 }
@@ -577,6 +680,14 @@ impl NetworkManifest {
                     devnet_config.pox_stacking_orders = Some(val.clone());
                 }
 
+                if let Some(ref val) = devnet_override.signers {
+                    devnet_config.signers = Some(val.clone());
+                }
+
+                if let Some(ref val) = devnet_override.faults {
+                    devnet_config.faults = Some(val.clone());
+                }
+
                 if let Some(ref val) = devnet_override.execute_script {
                     devnet_config.execute_script = Some(val.clone());
                 }
@@ -621,6 +732,10 @@ impl NetworkManifest {
                     devnet_config.bitcoin_controller_automining_disabled = Some(val);
                 }
 
+                if let Some(val) = devnet_override.bitcoin_controller_accelerated_mining {
+                    devnet_config.bitcoin_controller_accelerated_mining = Some(val);
+                }
+
                 if let Some(val) = devnet_override.enable_subnet_node {
                     devnet_config.enable_subnet_node = Some(val);
                 }
@@ -791,6 +906,50 @@ impl NetworkManifest {
                 ));
             }
 
+            let signers_config = devnet_config.signers.take();
+            if let Some(threshold) = signers_config.as_ref().and_then(|s| s.threshold) {
+                return Err(format!(
+                    "The signers threshold ({}) can not be configured: Nakamoto blocks are accepted once signed by the signers holding 70% of the stacked STX. Remove `threshold` from [devnet.signers].",
+                    threshold
+                ));
+            }
+            let signers = match signers_config {
+                Some(ref signers_config) => {
+                    let mut signers = vec![];
+                    for signer in signers_config.accounts.iter() {
+                        let account = accounts.get(&signer.wallet).ok_or(format!(
+                            "Account data was not provided for the signer wallet ({}).",
+                            signer.wallet
+                        ))?;
+                        let (_, _, secret_key_hex) =
+                            compute_addresses(&account.mnemonic, &account.derivation, networks);
+                        if Secp256k1PrivateKey::from_hex(&secret_key_hex).is_err() {
+                            return Err(format!(
+                                "The key derived for the signer wallet ({}) is not a valid private key.",
+                                signer.wallet
+                            ));
+                        }
+                        signers.push(DevnetSigner {
+                            wallet: signer.wallet.clone(),
+                            secret_key_hex,
+                            start: signer.start.unwrap_or(true),
+                        });
+                    }
+                    signers
+                }
+                None => DEFAULT_SIGNERS_SECRET_KEYS
+                    .iter()
+                    .enumerate()
+                    .map(|(i, secret_key_hex)| DevnetSigner {
+                        wallet: format!("wallet_{}", i + 1),
+                        secret_key_hex: secret_key_hex.to_string(),
+                        start: true,
+                    })
+                    .collect(),
+            };
+            if signers.is_empty() {
+                return Err("Devnet requires at least one signer in [devnet.signers]".into());
+            }
             // for stacking orders, we validate that wallet names match one of the provided accounts
             if let Some(ref val) = devnet_config.pox_stacking_orders {
                 for (i, stacking_order) in val.iter().enumerate() {
@@ -801,10 +960,77 @@ impl NetworkManifest {
                     if !wallet_is_in_accounts {
                         return Err(format!("Account data was not provided for the wallet ({}) listed in stacking order {}.", wallet_name, i + 1));
                     };
+                    if let Some(ref signer) = stacking_order.signer {
+                        if !signers.iter().any(|s| &s.wallet == signer) {
+                            return Err(format!(
+                                "The signer ({}) listed in stacking order {} is not part of the signer set.",
+                                signer,
+                                i + 1
+                            ));
+                        }
+                    }
                 }
 
                 devnet_config.pox_stacking_orders = Some(val.clone());
             }
+
+            let faults = devnet_config.faults.take().unwrap_or_default();
+            for (i, scheduled_fault) in faults.iter().enumerate() {
+                match &scheduled_fault.fault {
+                    DevnetFault::BitcoinReorg { depth } if *depth == 0 => {
+                        return Err(format!(
+                            "The depth of the bitcoin reorg listed in fault {} must be at least 1.",
+                            i + 1
+                        ));
+                    }
+                    DevnetFault::Partition { services, .. } if services.is_empty() => {
+                        return Err(format!(
+                            "The partition listed in fault {} must list at least one service.",
+                            i + 1
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+
+            // extra stacks nodes (miners first, then followers) are numbered after the main node
+            let stacks_miners = devnet_config.stacks_miners.unwrap_or(1);
+            let stacks_followers = devnet_config.stacks_followers.unwrap_or(0);
+            if stacks_miners == 0 {
+                return Err(
+                    "Devnet requires at least one stacks miner (stacks_miners >= 1)".into(),
+                );
+            }
+            let stacks_node_p2p_port = devnet_config.stacks_node_p2p_port.unwrap_or(20444);
+            let stacks_node_rpc_port = devnet_config.stacks_node_rpc_port.unwrap_or(20443);
+            let mut extra_stacks_nodes = vec![];
+            for i in 1..(stacks_miners + stacks_followers) {
+                let node_number = i + 1;
+                let name = format!("stacks-node-{}", node_number);
+                let (stx_address, btc_address, secret_key_hex) =
+                    match accounts.get(&format!("stacks_node_{}", node_number)) {
+                        Some(account) => {
+                            compute_addresses(&account.mnemonic, &account.derivation, networks)
+                        }
+                        None => compute_addresses(
+                            &miner_mnemonic,
+                            &format!("m/44'/5757'/0'/0/{}", i),
+                            networks,
+                        ),
+                    };
+                let port_offset = u16::try_from(i * 10)
+                    .map_err(|_| format!("too many stacks nodes ({})", i + 1))?;
+                extra_stacks_nodes.push(DevnetStacksNode {
+                    name,
+                    is_miner: i < stacks_miners,
+                    p2p_port: stacks_node_p2p_port + port_offset,
+                    rpc_port: stacks_node_rpc_port + port_offset,
+                    stx_address,
+                    btc_address,
+                    secret_key_hex,
+                });
+            }
+
             let config = DevnetConfig {
                 name: devnet_config.name.take().unwrap_or("devnet".into()),
                 network_id: devnet_config.network_id,
@@ -826,9 +1052,18 @@ impl NetworkManifest {
                 bitcoin_controller_automining_disabled: devnet_config
                     .bitcoin_controller_automining_disabled
                     .unwrap_or(false),
-                stacks_node_p2p_port: devnet_config.stacks_node_p2p_port.unwrap_or(20444),
-                stacks_node_rpc_port: devnet_config.stacks_node_rpc_port.unwrap_or(20443),
+                bitcoin_controller_accelerated_mining: devnet_config
+                    .bitcoin_controller_accelerated_mining
+                    .unwrap_or(false),
+                stacks_node_p2p_port,
+                stacks_node_rpc_port,
                 stacks_node_events_observers,
+                stacks_node_events_proxy_port: devnet_config
+                    .stacks_node_events_proxy_port
+                    .unwrap_or(20447),
+                stacks_miners,
+                stacks_followers,
+                extra_stacks_nodes,
                 stacks_node_wait_time_for_microblocks: devnet_config
                     .stacks_node_wait_time_for_microblocks
                     .unwrap_or(50),
@@ -860,6 +1095,8 @@ impl NetworkManifest {
                     .working_dir
                     .take()
                     .unwrap_or(default_working_dir),
+                record_events_path: devnet_config.record_events_path.take(),
+                enable_events_delay: devnet_config.enable_events_delay.unwrap_or(false),
                 postgres_port: devnet_config.postgres_port.unwrap_or(5432),
                 postgres_username: devnet_config
                     .postgres_username
@@ -907,6 +1144,8 @@ impl NetworkManifest {
                     .take()
                     .unwrap_or(DEFAULT_BITCOIN_EXPLORER_IMAGE.to_string()),
                 pox_stacking_orders: devnet_config.pox_stacking_orders.take().unwrap_or(vec![]),
+                signers,
+                faults,
                 disable_bitcoin_explorer: devnet_config.disable_bitcoin_explorer.unwrap_or(false),
                 disable_stacks_api: devnet_config.disable_stacks_api.unwrap_or(false),
                 disable_stacks_explorer: devnet_config.disable_stacks_explorer.unwrap_or(false),
@@ -980,6 +1219,24 @@ impl NetworkManifest {
     }
 }
 
+impl DevnetConfig {
+    /// Events reach `stacks_node_events_observers` through the events proxy only when
+    /// they can be delayed: with `enable_events_delay`, or a `delay_events` fault
+    /// scheduled. The nodes post to them directly otherwise.
+    pub fn relays_events_through_proxy(&self) -> bool {
+        self.enable_events_delay
+            || self
+                .faults
+                .iter()
+                .any(|f| matches!(f.fault, DevnetFault::DelayEvents { .. }))
+    }
+
+    /// The events proxy runs when it relays or records the events of the nodes
+    pub fn uses_events_proxy(&self) -> bool {
+        self.relays_events_through_proxy() || self.record_events_path.is_some()
+    }
+}
+
 pub fn compute_addresses(
     mnemonic: &str,
     derivation_path: &str,
@@ -1061,3 +1318,228 @@ pub fn is_in_reward_phase(
 fn compute_btc_address(_public_key: &PublicKey, _network: &BitcoinNetwork) -> String {
     "__not_implemented__".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNTS: &str = r#"
+[network]
+name = "devnet"
+
+[accounts.deployer]
+mnemonic = "fetch outside black test wash cover just actual execute nice door want airport betray quantum stamp fish act pen trust portion fatigue scissors vague"
+balance = 1_000_000
+
+[accounts.wallet_1]
+mnemonic = "spoil sock coyote include verify comic jacket gain beauty tank flush victory illness edge reveal shallow plug hobby usual juice harsh pact wreck eight"
+balance = 1_000_000
+
+[accounts.wallet_2]
+mnemonic = "arrange scale orient half ugly kid bike twin magnet joke hurt fiber ethics super receive version wreck media fluid much abstract reward street alter"
+balance = 1_000_000
+"#;
+
+    fn parse_devnet_config(devnet: &str) -> Result<DevnetConfig, String> {
+        let content = format!("{}\n{}", ACCOUNTS, devnet);
+        let mut network_manifest_file: NetworkManifestFile =
+            toml::from_slice(content.as_bytes()).unwrap();
+        let network_manifest = NetworkManifest::from_network_manifest_file(
+            &mut network_manifest_file,
+            &(BitcoinNetwork::Regtest, StacksNetwork::Devnet),
+            None,
+            None,
+        )?;
+        Ok(network_manifest.devnet.unwrap())
+    }
+
+    #[test]
+    fn test_single_stacks_node_by_default() {
+        let devnet_config = parse_devnet_config("").unwrap();
+        assert_eq!(devnet_config.stacks_miners, 1);
+        assert_eq!(devnet_config.stacks_followers, 0);
+        assert!(devnet_config.extra_stacks_nodes.is_empty());
+    }
+
+    #[test]
+    fn test_extra_stacks_nodes() {
+        let devnet_config = parse_devnet_config(
+            r#"
+[devnet]
+stacks_miners = 2
+stacks_followers = 2
+stacks_node_p2p_port = 30444
+stacks_node_rpc_port = 30443
+"#,
+        )
+        .unwrap();
+
+        let nodes = &devnet_config.extra_stacks_nodes;
+        let names = nodes.iter().map(|n| n.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["stacks-node-2", "stacks-node-3", "stacks-node-4"]);
+        let miners = nodes.iter().map(|n| n.is_miner).collect::<Vec<_>>();
+        assert_eq!(miners, [true, false, false]);
+
+        // ports are offset by 10 from the main node for each extra node
+        let ports = nodes
+            .iter()
+            .map(|n| (n.p2p_port, n.rpc_port))
+            .collect::<Vec<_>>();
+        assert_eq!(ports, [(30454, 30453), (30464, 30463), (30474, 30473)]);
+
+        // each node gets its own key, derived from the miner mnemonic
+        assert_ne!(nodes[0].secret_key_hex, devnet_config.miner_secret_key_hex);
+        assert_ne!(nodes[0].secret_key_hex, nodes[1].secret_key_hex);
+        assert_ne!(nodes[1].stx_address, nodes[2].stx_address);
+    }
+
+    #[test]
+    fn test_extra_stacks_node_account() {
+        let devnet_config = parse_devnet_config(
+            r#"
+[accounts.stacks_node_2]
+mnemonic = "glide clown kitchen picnic basket hidden asset beyond kid plug carbon talent drama wet pet rhythm hero nest purity baby bicycle ghost sponsor dragon"
+balance = 0
+
+[devnet]
+stacks_followers = 1
+"#,
+        )
+        .unwrap();
+
+        let node = &devnet_config.extra_stacks_nodes[0];
+        assert!(!node.is_miner);
+        assert_eq!(node.p2p_port, 20454);
+        assert_eq!(node.rpc_port, 20453);
+        let (stx_address, _, _) = compute_addresses(
+            "glide clown kitchen picnic basket hidden asset beyond kid plug carbon talent drama wet pet rhythm hero nest purity baby bicycle ghost sponsor dragon",
+            DEFAULT_DERIVATION_PATH,
+            &(BitcoinNetwork::Regtest, StacksNetwork::Devnet),
+        );
+        assert_eq!(node.stx_address, stx_address);
+    }
+
+    #[test]
+    fn test_stacks_miners_must_be_positive() {
+        let result = parse_devnet_config("[devnet]\nstacks_miners = 0\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_default_signers() {
+        let devnet_config = parse_devnet_config("").unwrap();
+        let wallets = devnet_config
+            .signers
+            .iter()
+            .map(|signer| (signer.wallet.as_str(), signer.start))
+            .collect::<Vec<_>>();
+        assert_eq!(wallets, [("wallet_1", true), ("wallet_2", true)]);
+    }
+
+    #[test]
+    fn test_signers() {
+        let devnet_config = parse_devnet_config(
+            r#"
+[devnet.signers]
+accounts = [{ wallet = "wallet_2" }, { wallet = "wallet_1", start = false }]
+"#,
+        )
+        .unwrap();
+
+        let signers = &devnet_config.signers;
+        assert_eq!(signers.len(), 2);
+        assert_eq!(signers[0].wallet, "wallet_2");
+        assert!(signers[0].start);
+        assert_eq!(signers[1].wallet, "wallet_1");
+        assert!(!signers[1].start);
+
+        // the signer keys are derived from the accounts
+        let wallet_2 = "arrange scale orient half ugly kid bike twin magnet joke hurt fiber ethics super receive version wreck media fluid much abstract reward street alter";
+        let (_, _, secret_key_hex) = compute_addresses(
+            wallet_2,
+            DEFAULT_DERIVATION_PATH,
+            &(BitcoinNetwork::Regtest, StacksNetwork::Devnet),
+        );
+        assert_eq!(signers[0].secret_key_hex, secret_key_hex);
+        assert!(Secp256k1PrivateKey::from_hex(&signers[0].secret_key_hex).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_signers() {
+        let unknown_wallet = parse_devnet_config(
+            r#"
+[devnet.signers]
+accounts = [{ wallet = "wallet_9" }]
+"#,
+        );
+        assert!(unknown_wallet.is_err());
+
+        let no_signers = parse_devnet_config(
+            r#"
+[devnet.signers]
+accounts = []
+"#,
+        );
+        assert!(no_signers.is_err());
+
+        // the threshold can't be honoured, it is rejected rather than ignored
+        let threshold = parse_devnet_config(
+            r#"
+[devnet.signers]
+threshold = 2
+accounts = [{ wallet = "wallet_1" }, { wallet = "wallet_2" }]
+"#,
+        );
+        assert!(threshold.unwrap_err().contains("signers threshold (2)"));
+    }
+
+    #[test]
+    fn test_stacking_order_signer() {
+        let stacking_order = |signer: &str| {
+            format!(
+                r#"
+[[devnet.pox_stacking_orders]]
+start_at_cycle = 1
+duration = 12
+wallet = "wallet_1"
+slots = 2
+btc_address = "mr1iPkD9N3RJZZxXRk7xF9d36gffa6exNC"
+signer = "{}"
+"#,
+                signer
+            )
+        };
+        assert!(parse_devnet_config(&stacking_order("wallet_2")).is_ok());
+        assert!(parse_devnet_config(&stacking_order("deployer")).is_err());
+    }
+
+    #[test]
+    fn test_events_proxy_usage() {
+        // by default, the nodes post to the observers directly
+        let devnet_config =
+            parse_devnet_config("[devnet]\nstacks_node_events_observers = [\"localhost:8002\"]\n")
+                .unwrap();
+        assert!(!devnet_config.uses_events_proxy());
+
+        let devnet_config =
+            parse_devnet_config("[devnet]\nrecord_events_path = \"events.ndjson\"\n").unwrap();
+        assert!(devnet_config.uses_events_proxy());
+        assert!(!devnet_config.relays_events_through_proxy());
+
+        let devnet_config = parse_devnet_config(
+            r#"
+[[devnet.faults]]
+at_block = 170
+kind = "delay_events"
+delay_ms = 5000
+duration_ms = 60000
+"#,
+        )
+        .unwrap();
+        assert!(devnet_config.relays_events_through_proxy());
+
+        // delays requested from the control API need the proxy as well
+        let devnet_config = parse_devnet_config("[devnet]\nenable_events_delay = true\n").unwrap();
+        assert!(devnet_config.relays_events_through_proxy());
+    }
+}
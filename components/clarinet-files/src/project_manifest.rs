@@ -38,6 +38,7 @@ pub struct ProjectConfigFile {
     telemetry: Option<bool>,
     requirements: Option<Value>,
     boot_contracts: Option<Vec<String>>,
+    events_abi: Option<EventsAbiConfig>,
 
     // The fields below have been moved into repl above, but are kept here for
     // backwards compatibility.
@@ -198,6 +199,8 @@ pub struct ProjectConfig {
     pub cache_location: FileLocation,
     #[serde(skip_deserializing)]
     pub boot_contracts: Vec<String>,
+    #[serde(default)]
+    pub events_abi: EventsAbiConfig,
 }
 
 fn cache_location_deserializer<'de, D>(des: D) -> Result<FileLocation, D::Error>
@@ -228,6 +231,9 @@ impl Serialize for ProjectConfig {
         if self.requirements.is_some() {
             map.serialize_entry("requirements", &self.requirements)?;
         }
+        if self.events_abi != EventsAbiConfig::default() {
+            map.serialize_entry("events_abi", &self.events_abi)?;
+        }
         map.end()
     }
 }
@@ -237,6 +243,17 @@ pub struct RequirementConfig {
     pub contract_id: String,
 }
 
+/// Settings of the events ABI (`deployments/events.abi.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct EventsAbiConfig {
+    /// Emit the events ABI on every `clarinet check`
+    #[serde(default)]
+    pub emit: bool,
+    /// Version of the events of each contract, to bump when their shape changes
+    #[serde(default)]
+    pub versions: BTreeMap<String, u32>,
+}
+
 impl ProjectManifest {
     pub async fn from_file_accessor(
         location: &FileLocation,
@@ -316,6 +333,7 @@ impl ProjectManifest {
                 "cost-voting".to_string(),
                 "bns".to_string(),
             ],
+            events_abi: project_manifest_file.project.events_abi.unwrap_or_default(),
         };
 
         let mut config = ProjectManifest {
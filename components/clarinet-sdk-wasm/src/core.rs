@@ -10,11 +10,13 @@ use clarinet_deployments::{
 use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::{FileAccessor, FileLocation, ProjectManifest, WASMFileSystemAccessor};
 use clarity_repl::analysis::coverage::CoverageReporter;
+use clarity_repl::analysis::coverage_formats::CoverageSummary;
 use clarity_repl::clarity::analysis::contract_interface_builder::{
     ContractInterface, ContractInterfaceFunction, ContractInterfaceFunctionAccess,
 };
 use clarity_repl::clarity::ast::ContractAST;
 use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::vm::Value;
 use clarity_repl::clarity::{ClarityVersion, EvaluationResult, ExecutionResult, StacksEpochId};
 use clarity_repl::repl::clarity_values::{uint8_to_string, uint8_to_value};
 use clarity_repl::repl::session::BOOT_CONTRACTS_DATA;
@@ -27,7 +29,7 @@ use js_sys::Function as JsFunction;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_wasm_bindgen::to_value as encode_to_js;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::{panic, path::PathBuf};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
@@ -62,7 +64,7 @@ macro_rules! log {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct CallContractArgsJSON {
     contract: String,
     method: String,
@@ -203,6 +205,30 @@ pub struct TxArgs {
     deploy_contract: Option<DeployContractArgs>,
     #[serde(rename(serialize = "transfer_stx", deserialize = "transferSTX"))]
     transfer_stx: Option<TransferSTXArgs>,
+    nonce: Option<u64>,
+    fee: Option<u64>,
+}
+
+impl TxArgs {
+    fn sender(&self) -> Result<&str, String> {
+        if let Some(ref call_public) = self.call_public_fn {
+            Ok(&call_public.sender)
+        } else if let Some(ref call_private) = self.call_private_fn {
+            Ok(&call_private.sender)
+        } else if let Some(ref transfer_stx) = self.transfer_stx {
+            Ok(&transfer_stx.sender)
+        } else if let Some(ref deploy_contract) = self.deploy_contract {
+            Ok(&deploy_contract.sender)
+        } else {
+            Err("Invalid tx arguments".into())
+        }
+    }
+
+    // private functions can't be called in a transaction on chain, such calls are
+    // evaluated in the block without a nonce or a fee
+    fn is_transaction(&self) -> bool {
+        self.call_private_fn.is_none()
+    }
 }
 
 #[wasm_bindgen(getter_with_clone)]
@@ -210,6 +236,8 @@ pub struct TxArgs {
 pub struct TransactionRes {
     pub result: String,
     pub events: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -222,6 +250,7 @@ pub struct TransactionResRaw {
 pub struct SessionReport {
     pub coverage: String,
     pub costs: String,
+    pub profiles: String,
 }
 
 pub fn execution_result_to_transaction_res(execution: &ExecutionResult) -> TransactionRes {
@@ -241,6 +270,16 @@ pub fn execution_result_to_transaction_res(execution: &ExecutionResult) -> Trans
     TransactionRes {
         result,
         events: json!(events_as_strings).to_string(),
+        error: None,
+    }
+}
+
+// as in the receipts of the node, a transaction that failed or was rejected results in (err none)
+fn failed_transaction_res(error: String) -> TransactionRes {
+    TransactionRes {
+        result: clarity_values::to_raw_value(&Value::err_none()),
+        events: json!(Vec::<String>::new()).to_string(),
+        error: Some(error),
     }
 }
 
@@ -258,15 +297,18 @@ pub struct SDKOptions {
     pub track_costs: bool,
     #[wasm_bindgen(js_name = trackCoverage)]
     pub track_coverage: bool,
+    #[wasm_bindgen(js_name = profileCosts)]
+    pub profile_costs: bool,
 }
 
 #[wasm_bindgen]
 impl SDKOptions {
     #[wasm_bindgen(constructor)]
-    pub fn new(track_costs: bool, track_coverage: bool) -> Self {
+    pub fn new(track_costs: bool, track_coverage: bool, profile_costs: Option<bool>) -> Self {
         Self {
             track_costs,
             track_coverage,
+            profile_costs: profile_costs.unwrap_or(false),
         }
     }
 }
@@ -297,6 +339,7 @@ impl SDK {
 
         let track_coverage = options.as_ref().map_or(false, |o| o.track_coverage);
         let track_costs = options.as_ref().map_or(false, |o| o.track_costs);
+        let profile_costs = options.as_ref().map_or(false, |o| o.profile_costs);
 
         Self {
             deployer: String::new(),
@@ -309,6 +352,7 @@ impl SDK {
             options: SDKOptions {
                 track_coverage,
                 track_costs,
+                profile_costs,
             },
             current_test_name: String::new(),
         }
@@ -332,7 +376,7 @@ impl SDK {
             .ok_or("Failed to parse manifest location")?;
 
         let ProjectCache {
-            session,
+            mut session,
             contracts_interfaces,
             contracts_locations,
             accounts,
@@ -341,6 +385,8 @@ impl SDK {
             None => self.setup_session(&manifest_location).await?,
         };
 
+        // cost profiles are only recorded for calls tracking costs
+        session.profile_costs = self.options.profile_costs;
         self.deployer = session.interpreter.get_tx_sender().to_string();
 
         self.contracts_interfaces = contracts_interfaces;
@@ -427,6 +473,7 @@ impl SDK {
             Some(&artifacts.asts),
             false,
             Some(DEFAULT_EPOCH),
+            None,
         );
 
         let mut accounts = HashMap::new();
@@ -547,6 +594,12 @@ impl SDK {
         session.interpreter.get_block_height()
     }
 
+    #[wasm_bindgen(getter, js_name=burnBlockHeight)]
+    pub fn burn_block_height(&mut self) -> u32 {
+        let session = self.get_session_mut();
+        session.interpreter.get_burn_block_height()
+    }
+
     #[wasm_bindgen(getter, js_name=currentEpoch)]
     pub fn current_epoch(&mut self) -> String {
         let session = self.get_session_mut();
@@ -673,6 +726,7 @@ impl SDK {
         let SDKOptions {
             track_costs,
             track_coverage,
+            ..
         } = self.options;
 
         let session = self.get_session_mut();
@@ -760,6 +814,7 @@ impl SDK {
         let execution = match session.stx_transfer(args.amount, &args.recipient) {
             Ok(res) => res,
             Err(diagnostics) => {
+                session.set_tx_sender(initial_tx_sender);
                 let mut message = format!("{}: {}", "STX transfer error", args.sender);
                 if let Some(diag) = diagnostics.last() {
                     message = format!("{} -> {}", message, diag.message);
@@ -826,19 +881,34 @@ impl SDK {
         Ok(execution_result_to_transaction_res(&execution))
     }
 
+    // a transaction sent on its own is mined in its own block, and as in mineBlock,
+    // the nonce of its sender is incremented even when it fails
+    fn mine_single_tx(
+        &mut self,
+        sender: &str,
+        execute: impl FnOnce(&mut Self) -> Result<TransactionRes, String>,
+    ) -> Result<TransactionRes, String> {
+        self.get_session_mut().begin_block();
+        let result = execute(self);
+        let session = self.get_session_mut();
+        session.end_block();
+        session.increment_nonce(sender);
+        result
+    }
+
     #[wasm_bindgen(js_name=deployContract)]
     pub fn deploy_contract(&mut self, args: &DeployContractArgs) -> Result<TransactionRes, String> {
-        self.inner_deploy_contract(args, true)
+        self.mine_single_tx(&args.sender, |sdk| sdk.inner_deploy_contract(args, true))
     }
 
     #[wasm_bindgen(js_name = "transferSTX")]
     pub fn transfer_stx(&mut self, args: &TransferSTXArgs) -> Result<TransactionRes, String> {
-        self.inner_transfer_stx(args, true)
+        self.mine_single_tx(&args.sender, |sdk| sdk.inner_transfer_stx(args, true))
     }
 
     #[wasm_bindgen(js_name = "callPublicFn")]
     pub fn call_public_fn(&mut self, args: &CallFnArgs) -> Result<TransactionRes, String> {
-        self.inner_call_public_fn(args, true)
+        self.mine_single_tx(&args.sender, |sdk| sdk.inner_call_public_fn(args, true))
     }
 
     #[wasm_bindgen(js_name = "callPrivateFn")]
@@ -846,35 +916,127 @@ impl SDK {
         self.inner_call_private_fn(args, true)
     }
 
+    fn execute_tx(&mut self, tx: &TxArgs) -> Result<TransactionRes, String> {
+        if let Some(ref call_public) = tx.call_public_fn {
+            self.inner_call_public_fn(&CallFnArgs::from_json_args(call_public.clone()), false)
+        } else if let Some(ref call_private) = tx.call_private_fn {
+            self.inner_call_private_fn(&CallFnArgs::from_json_args(call_private.clone()), false)
+        } else if let Some(ref transfer_stx) = tx.transfer_stx {
+            self.inner_transfer_stx(transfer_stx, false)
+        } else if let Some(ref deploy_contract) = tx.deploy_contract {
+            self.inner_deploy_contract(deploy_contract, false)
+        } else {
+            Err("Invalid tx arguments".into())
+        }
+    }
+
+    /*
+      Transactions are mined within the cost limits of a block. Those that don't
+      fit in the current block roll over to the next one, along with the following
+      transactions of the same sender, so the results are returned in the order of `js_txs`
+      while the chain tip may advance by more than one block.
+      As on chain, a transaction failing at runtime is included in the block: its fee
+      is paid and the nonce of its sender incremented. A transaction whose fee can't
+      be paid is not included, nor are the following ones of its sender. Both get a
+      result carrying their error. Private function calls are not transactions, they are
+      evaluated in the block without a nonce or a fee.
+    */
     #[wasm_bindgen(js_name=mineBlock)]
     pub fn mine_block_js(&mut self, js_txs: js_sys::Array) -> Result<JsValue, String> {
-        let mut results: Vec<TransactionRes> = vec![];
-
         let txs: Vec<TxArgs> = js_txs
             .into_serde()
             .map_err(|e| format!("Failed to parse js txs: {:}", e))?;
 
-        for tx in txs {
-            let result = if let Some(call_public) = tx.call_public_fn {
-                self.inner_call_public_fn(&CallFnArgs::from_json_args(call_public), false)
-            } else if let Some(call_private) = tx.call_private_fn {
-                self.inner_call_private_fn(&CallFnArgs::from_json_args(call_private), false)
-            } else if let Some(transfer_stx) = tx.transfer_stx {
-                self.inner_transfer_stx(&transfer_stx, false)
-            } else if let Some(deploy_contract) = tx.deploy_contract {
-                self.inner_deploy_contract(&deploy_contract, false)
-            } else {
-                return Err("Invalid tx arguments".into());
-            }?;
-            results.push(result);
+        // nonces are checked upfront, a block with a bad nonce is rejected as a whole
+        // and leaves the simnet untouched
+        let session = self.get_session();
+        let mut next_nonces: HashMap<String, u64> = HashMap::new();
+        for (i, tx) in txs.iter().enumerate() {
+            let sender = tx.sender()?;
+            if !tx.is_transaction() {
+                continue;
+            }
+            let next_nonce = next_nonces
+                .entry(sender.to_string())
+                .or_insert_with(|| session.get_nonce(sender));
+            if let Some(nonce) = tx.nonce {
+                if nonce != *next_nonce {
+                    return Err(format!(
+                        "Transaction {} rejected: bad nonce for {} (expected {}, got {})",
+                        i, sender, next_nonce, nonce
+                    ));
+                }
+            }
+            *next_nonce += 1;
         }
 
-        let session = self.get_session_mut();
-        session.advance_chain_tip(1);
+        let mut results: Vec<Option<TransactionRes>> = txs.iter().map(|_| None).collect();
+        let mut rejected_senders = HashSet::new();
+        let mut pending: Vec<(usize, TxArgs)> = txs.into_iter().enumerate().collect();
+        while !pending.is_empty() {
+            self.get_session_mut().begin_block();
+            let mut rolled_over = vec![];
+            let mut stalled_senders = HashSet::new();
+
+            for (i, tx) in pending.into_iter() {
+                let sender = tx.sender()?.to_string();
+                let is_transaction = tx.is_transaction();
+                if is_transaction && rejected_senders.contains(&sender) {
+                    results[i] = Some(failed_transaction_res(format!(
+                        "Transaction {} rejected: a previous transaction of {} was rejected",
+                        i, sender
+                    )));
+                    continue;
+                }
+                if stalled_senders.contains(&sender) {
+                    rolled_over.push((i, tx));
+                    continue;
+                }
+
+                let fee = if is_transaction {
+                    tx.fee.unwrap_or(0)
+                } else {
+                    0
+                };
+                let session = self.get_session_mut();
+                let block_is_empty = session.is_block_empty();
+                if let Err(message) = session.pay_fee(&sender, fee) {
+                    results[i] = Some(failed_transaction_res(format!(
+                        "Transaction {} rejected: {}",
+                        i, message
+                    )));
+                    rejected_senders.insert(sender);
+                    continue;
+                }
+
+                let result = self.execute_tx(&tx);
+                let session = self.get_session_mut();
+                if session.interpreter.block_limit_reached && result.is_err() && !block_is_empty {
+                    session.refund_fee(&sender, fee)?;
+                    stalled_senders.insert(sender);
+                    rolled_over.push((i, tx));
+                    continue;
+                }
+                if is_transaction {
+                    session.increment_nonce(&sender);
+                }
+                results[i] = Some(result.unwrap_or_else(|message| {
+                    failed_transaction_res(format!("Transaction {} failed: {}", i, message))
+                }));
+            }
+
+            let session = self.get_session_mut();
+            session.end_block();
+            session.advance_chain_tip(1);
+            pending = rolled_over;
+        }
 
+        let results: Vec<TransactionRes> = results.into_iter().flatten().collect();
         encode_to_js(&results).map_err(|e| format!("error: {}", e))
     }
 
+    // starting with epoch 3.0, the blocks are mined in the current tenure: the burn
+    // block height doesn't move, mineEmptyBurnBlock(s) start new tenures
     #[wasm_bindgen(js_name=mineEmptyBlock)]
     pub fn mine_empty_block(&mut self) -> u32 {
         let session = self.get_session_mut();
@@ -887,6 +1049,18 @@ impl SDK {
         session.advance_chain_tip(count.unwrap_or(1))
     }
 
+    #[wasm_bindgen(js_name=mineEmptyBurnBlock)]
+    pub fn mine_empty_burn_block(&mut self) -> u32 {
+        let session = self.get_session_mut();
+        session.advance_burn_chain_tip(1)
+    }
+
+    #[wasm_bindgen(js_name=mineEmptyBurnBlocks)]
+    pub fn mine_empty_burn_blocks(&mut self, count: Option<u32>) -> u32 {
+        let session = self.get_session_mut();
+        session.advance_burn_chain_tip(count.unwrap_or(1))
+    }
+
     #[wasm_bindgen(js_name=runSnippet)]
     pub fn run_snippet(&mut self, snippet: String) -> String {
         let session = self.get_session_mut();
@@ -907,6 +1081,15 @@ impl SDK {
         }
     }
 
+    // cost profiles are only recorded when costs are tracked
+    #[wasm_bindgen(js_name=setProfileCosts)]
+    pub fn set_profile_costs(&mut self, profile_costs: bool) {
+        self.options.profile_costs = profile_costs;
+        if let Some(session) = self.session.as_mut() {
+            session.profile_costs = profile_costs;
+        }
+    }
+
     #[wasm_bindgen(js_name=setCurrentTestName)]
     pub fn set_current_test_name(&mut self, test_name: String) {
         self.current_test_name = test_name;
@@ -960,6 +1143,45 @@ impl SDK {
             .collect();
         let costs = serde_json::to_string(&costs_reports).map_err(|e| e.to_string())?;
 
-        Ok(SessionReport { coverage, costs })
+        // one speedscope document per profiled call
+        let profiles: Vec<serde_json::Value> = session
+            .cost_profiles
+            .drain(..)
+            .map(|profile| profile.to_speedscope())
+            .collect();
+        let profiles = serde_json::to_string(&profiles).map_err(|e| e.to_string())?;
+
+        Ok(SessionReport {
+            coverage,
+            costs,
+            profiles,
+        })
+    }
+
+    // this method converts the lcov coverage (as returned by `collectReport`)
+    // to the html or cobertura format
+    #[wasm_bindgen(js_name=convertCoverageReport)]
+    pub fn convert_coverage_report(&self, lcov: String, format: String) -> Result<String, String> {
+        let summary = CoverageSummary::from_lcov(&lcov);
+        match format.as_str() {
+            "cobertura" => {
+                let timestamp = (js_sys::Date::now() / 1000.0) as u64;
+                Ok(summary.build_cobertura_content(timestamp))
+            }
+            "html" => {
+                let session = self.get_session();
+                let mut sources = BTreeMap::new();
+                for (contract_id, location) in self.contracts_locations.iter() {
+                    if let Some(contract) = session.contracts.get(contract_id) {
+                        sources.insert(location.to_string(), contract.code.clone());
+                    }
+                }
+                Ok(summary.build_html_content(&sources))
+            }
+            _ => Err(format!(
+                "unknown coverage format {} (expected html or cobertura)",
+                format
+            )),
+        }
     }
 }
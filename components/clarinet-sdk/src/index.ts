@@ -26,9 +26,11 @@ export type ClarityEvent = {
   data: { raw_value?: string; value?: ClarityValue; [key: string]: any };
 };
 
+// in mineBlock, a transaction that failed or was rejected results in (err none) with its error
 export type ParsedTransactionResult = {
   result: ClarityValue;
   events: ClarityEvent[];
+  error?: string;
 };
 
 export type CallFn = (
@@ -54,7 +56,11 @@ export type TransferSTX = (
   sender: string,
 ) => ParsedTransactionResult;
 
-export type Tx =
+// the nonce is checked against the sender's next nonce, the fee is debited from its STX balance,
+// including when the transaction fails (its result then carries the error)
+export type TxSettings = { nonce?: number; fee?: number };
+
+type TxPayload =
   | {
       callPublicFn: {
         contract: string;
@@ -95,11 +101,27 @@ export type Tx =
       transferSTX: { amount: number; recipient: string; sender: string };
     };
 
+export type Tx = TxPayload & TxSettings;
+
 export const tx = {
-  callPublicFn: (contract: string, method: string, args: ClarityValue[], sender: string): Tx => ({
+  callPublicFn: (
+    contract: string,
+    method: string,
+    args: ClarityValue[],
+    sender: string,
+    settings?: TxSettings,
+  ): Tx => ({
+    ...settings,
     callPublicFn: { contract, method, args, sender },
   }),
-  callPrivateFn: (contract: string, method: string, args: ClarityValue[], sender: string): Tx => ({
+  callPrivateFn: (
+    contract: string,
+    method: string,
+    args: ClarityValue[],
+    sender: string,
+    settings?: TxSettings,
+  ): Tx => ({
+    ...settings,
     callPrivateFn: { contract, method, args, sender },
   }),
   deployContract: (
@@ -107,10 +129,18 @@ export const tx = {
     content: string,
     options: DeployContractOptions | null,
     sender: string,
+    settings?: TxSettings,
   ): Tx => ({
+    ...settings,
     deployContract: { name, content, options, sender },
   }),
-  transferSTX: (amount: number, recipient: string, sender: string): Tx => ({
+  transferSTX: (
+    amount: number,
+    recipient: string,
+    sender: string,
+    settings?: TxSettings,
+  ): Tx => ({
+    ...settings,
     transferSTX: { amount, recipient, sender },
   }),
 };
@@ -165,10 +195,12 @@ function parseEvents(events: string): ClarityEvent[] {
 }
 
 function parseTxResponse(response: TransactionRes): ParsedTransactionResult {
-  return {
+  const parsed: ParsedTransactionResult = {
     result: Cl.deserialize(response.result),
     events: parseEvents(response.events),
   };
+  if (response.error) parsed.error = response.error;
+  return parsed;
 }
 
 const getSessionProxy = () => ({
@@ -231,6 +263,7 @@ const getSessionProxy = () => ({
         const serializedTxs = txs.map((tx) => {
           if (tx.callPublicFn) {
             return {
+              ...tx,
               callPublicFn: {
                 ...tx.callPublicFn,
                 args_maps: tx.callPublicFn.args.map(Cl.serialize),
@@ -239,6 +272,7 @@ const getSessionProxy = () => ({
           }
           if (tx.callPrivateFn) {
             return {
+              ...tx,
               callPrivateFn: {
                 ...tx.callPrivateFn,
                 args_maps: tx.callPrivateFn.args.map(Cl.serialize),
@@ -283,11 +317,15 @@ function memoizedInit() {
   return async (
     manifestPath = "./Clarinet.toml",
     noCache = false,
-    options?: { trackCosts: boolean; trackCoverage: boolean },
+    options?: { trackCosts: boolean; trackCoverage: boolean; profileCosts?: boolean },
   ) => {
     if (noCache || !simnet) {
       const module = await wasmModule;
-      let sdkOptions = new SDKOptions(!!options?.trackCosts, !!options?.trackCoverage);
+      let sdkOptions = new SDKOptions(
+        !!options?.trackCosts,
+        !!options?.trackCoverage,
+        !!options?.profileCosts,
+      );
       simnet = new Proxy(new module.SDK(vfs, sdkOptions), getSessionProxy()) as unknown as Simnet;
     }
 
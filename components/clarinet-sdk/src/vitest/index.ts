@@ -27,6 +27,17 @@ export function getClarinetVitestsArgv() {
       type: "boolean",
       default: false,
     })
+    .option("profile-costs", {
+      description: "Record speedscope cost profiles of the contract calls (requires --costs)",
+      type: "boolean",
+      default: false,
+    })
+    .option("coverage-format", {
+      description: "Also convert the lcov coverage report to html or cobertura",
+      type: "string",
+      choices: ["lcov", "html", "cobertura"],
+      default: "lcov",
+    })
     .option("coverage-filename", {
       alias: "cov-file",
       type: "string",
@@ -36,6 +47,11 @@ export function getClarinetVitestsArgv() {
       alias: "costs-file",
       type: "string",
       default: "costs-reports.json",
+    })
+    .option("profiles-filename", {
+      alias: "profiles-file",
+      type: "string",
+      default: "costs-profiles.json",
     }).argv;
 }
 
@@ -16,6 +16,8 @@ import { vitestSetupFilePath, getClarinetVitestsArgv } from "@hirosystems/clarin
   The `getClarinetVitestsArgv()` will parse options passed to the command `vitest run --`
     - vitest run -- --manifest ./Clarinet.toml  # pass a custom path
     - vitest run -- --coverage --costs          # collect coverage and cost reports
+    - vitest run -- --costs --profile-costs     # also write speedscope cost profiles
+    - vitest run -- --coverage --coverage-format html  # also write an html coverage report
 */
 
 export default defineConfig({
@@ -57,6 +57,21 @@ describe("simnet can get code coverage", () => {
     expect(reports.coverage.startsWith("TN:")).toBe(true);
     expect(reports.coverage.endsWith("end_of_record\n")).toBe(true);
   });
+
+  it("converts the coverage report to cobertura", async () => {
+    const simnet = await initSimnet("tests/fixtures/Clarinet.toml", true, {
+      trackCoverage: true,
+      trackCosts: false,
+    });
+
+    simnet.callPublicFn("counter", "increment", [], address1);
+    const reports = simnet.collectReport(false, "");
+
+    const cobertura = simnet.convertCoverageReport(reports.coverage, "cobertura");
+    expect(cobertura.startsWith("<?xml")).toBe(true);
+    expect(cobertura.includes("counter")).toBe(true);
+    expect(() => simnet.convertCoverageReport(reports.coverage, "json")).toThrow();
+  });
 });
 
 describe("simnet can get costs reports", () => {
@@ -107,3 +122,32 @@ describe("simnet can report both costs and coverage", () => {
     expect(reports.coverage.length).greaterThan(0);
   });
 });
+
+describe("simnet can get costs profiles", () => {
+  it("does not profile costs by default", async () => {
+    const simnet = await initSimnet("tests/fixtures/Clarinet.toml", true, {
+      trackCoverage: false,
+      trackCosts: true,
+    });
+    simnet.callPublicFn("counter", "increment", [], address1);
+
+    const reports = simnet.collectReport(false, "");
+    expect(JSON.parse(reports.profiles)).toHaveLength(0);
+  });
+
+  it("returns one speedscope profile per call", async () => {
+    const simnet = await initSimnet("tests/fixtures/Clarinet.toml", true, {
+      trackCoverage: false,
+      trackCosts: true,
+    });
+    simnet.setProfileCosts(true);
+    simnet.callPublicFn("counter", "increment", [], address1);
+    simnet.callPublicFn("counter", "increment", [], address1);
+
+    const reports = simnet.collectReport(false, "");
+    const profiles = JSON.parse(reports.profiles);
+    expect(profiles).toHaveLength(2);
+    expect(profiles[0].exporter).toBe("clarinet");
+    expect(profiles[0].name).toContain("counter.increment");
+  });
+});
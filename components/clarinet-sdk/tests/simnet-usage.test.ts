@@ -48,6 +48,19 @@ describe("basic simnet interactions", () => {
     expect(simnet.blockHeight).toBe(blockHeight + 5);
   });
 
+  it("can mine stacks blocks in a tenure in epoch 3.0", () => {
+    simnet.setEpoch("3.0");
+    const blockHeight = simnet.blockHeight;
+    const burnBlockHeight = simnet.burnBlockHeight;
+    simnet.mineEmptyBlocks(3);
+    expect(simnet.blockHeight).toBe(blockHeight + 3);
+    expect(simnet.burnBlockHeight).toBe(burnBlockHeight);
+
+    simnet.mineEmptyBurnBlocks(2);
+    expect(simnet.burnBlockHeight).toBe(burnBlockHeight + 2);
+    expect(simnet.blockHeight).toBe(blockHeight + 5);
+  });
+
   it("exposes devnet stacks accounts", () => {
     const accounts = simnet.getAccounts();
 
@@ -327,6 +340,116 @@ describe("simnet can transfer stx", () => {
   });
 });
 
+describe("simnet can mine blocks of transactions", () => {
+  it("rejects a block with a bad nonce", () => {
+    const initalBH = simnet.blockHeight;
+
+    expect(() => {
+      simnet.mineBlock([tx.callPublicFn("counter", "increment", [], address1, { nonce: 1 })]);
+    }).toThrow(`Transaction 0 rejected: bad nonce for ${address1} (expected 0, got 1)`);
+    expect(simnet.blockHeight).toBe(initalBH);
+
+    const res = simnet.mineBlock([
+      tx.callPublicFn("counter", "increment", [], address1, { nonce: 0 }),
+      tx.callPublicFn("counter", "increment", [], address1, { nonce: 1 }),
+    ]);
+    expect(res).toHaveLength(2);
+    expect(simnet.blockHeight).toBe(initalBH + 1);
+  });
+
+  it("increments the nonce of failed transactions", () => {
+    const source = "(define-public (divide (n uint)) (ok (/ u10 n)))";
+    simnet.deployContract("divider", source, null, deployerAddr);
+
+    expect(() => {
+      simnet.callPublicFn("divider", "divide", [Cl.uint(0)], address1);
+    }).toThrow();
+    const failed = simnet.mineBlock([
+      tx.callPublicFn("divider", "divide", [Cl.uint(0)], address1),
+      tx.callPublicFn("divider", "divide", [Cl.uint(5)], address1),
+    ]);
+    expect(failed).toHaveLength(2);
+    expect(failed[0].result).toStrictEqual(Cl.error(Cl.none()));
+    expect(failed[0].error).toContain("Transaction 0 failed");
+    expect(failed[1].result).toStrictEqual(Cl.ok(Cl.uint(2)));
+    expect(failed[1].error).toBeUndefined();
+
+    const res = simnet.mineBlock([
+      tx.callPublicFn("divider", "divide", [Cl.uint(2)], address1, { nonce: 3 }),
+    ]);
+    expect(res[0].result).toStrictEqual(Cl.ok(Cl.uint(5)));
+  });
+
+  it("does not take a nonce for private function calls", () => {
+    simnet.callPrivateFn("counter", "inner-increment", [], address1);
+    const res = simnet.mineBlock([
+      tx.callPrivateFn("counter", "inner-increment", [], address1, { fee: 100 }),
+      tx.callPublicFn("counter", "increment", [], address1, { nonce: 0 }),
+    ]);
+    expect(res[0].result).toStrictEqual(Cl.bool(true));
+    expect(res[1].result).toStrictEqual(Cl.ok(Cl.bool(true)));
+
+    // the fee of the private call is not debited
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(100000000000000n);
+  });
+
+  it("debits the fees from the senders", () => {
+    simnet.mineBlock([
+      tx.transferSTX(1000, address2, address1, { fee: 200 }),
+      tx.callPublicFn("counter", "increment", [], address1, { fee: 300 }),
+    ]);
+
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(100000000000000n - 1000n - 200n - 1000000n - 300n);
+    expect(STX.get(address2)).toBe(100000000001000n);
+  });
+
+  it("rejects transactions whose fee can not be paid", () => {
+    const res = simnet.mineBlock([
+      tx.callPublicFn("counter", "increment", [], address1, { fee: 200000000000000 }),
+      tx.callPublicFn("counter", "increment", [], address1),
+      tx.callPublicFn("counter", "increment", [], address2),
+    ]);
+
+    expect(res).toHaveLength(3);
+    expect(res[0].result).toStrictEqual(Cl.error(Cl.none()));
+    expect(res[0].error).toContain("Transaction 0 rejected");
+    expect(res[1].result).toStrictEqual(Cl.error(Cl.none()));
+    expect(res[1].error).toBe(
+      `Transaction 1 rejected: a previous transaction of ${address1} was rejected`,
+    );
+    expect(res[2].result).toStrictEqual(Cl.ok(Cl.bool(true)));
+
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(100000000000000n);
+  });
+
+  it("rolls the transactions exceeding the block budget over to a new block", () => {
+    // each call reads the data var a thousand times, the block read count limit
+    // is reached after 15 calls
+    const items = Array.from({ length: 1000 }, (_, i) => i).join(" ");
+    const source = `(define-data-var value uint u1)
+(define-private (read-value (i int) (acc uint)) (+ acc (var-get value)))
+(define-public (read-many) (ok (fold read-value (list ${items}) u0)))`;
+    simnet.deployContract("reader", source, null, deployerAddr);
+    const initalBH = simnet.blockHeight;
+
+    const txs = Array.from({ length: 20 }, () =>
+      tx.callPublicFn("reader", "read-many", [], address1, { fee: 10 }),
+    );
+    const res = simnet.mineBlock(txs);
+
+    expect(res).toHaveLength(20);
+    res.forEach(({ result }) => expect(result).toStrictEqual(Cl.ok(Cl.uint(1000))));
+    expect(simnet.blockHeight).toBe(initalBH + 2);
+
+    // the fees of the rolled over transactions are refunded and paid in the new block
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(100000000000000n - 20n * 10n);
+  });
+});
+
 describe("the sdk handles multiple manifests project", () => {
   it("handle invalid project", () => {
     const manifestPath = path.join(process.cwd(), "tests/fixtures/contracts/invalid.clar");
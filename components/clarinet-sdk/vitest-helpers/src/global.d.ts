@@ -5,14 +5,18 @@ declare global {
   var testEnvironment: string;
   var coverageReports: string[];
   var costsReports: string[];
+  var coverageReportsByFile: Record<string, string[]>;
   var options: {
     clarinet: {
       manifestPath: string;
       initBeforeEach: boolean;
       coverage: boolean;
       coverageFilename: string;
+      coverageFormat: "lcov" | "html" | "cobertura";
       costs: boolean;
       costsFilename: string;
+      profileCosts: boolean;
+      profilesFilename: string;
       includeBootContracts: boolean;
       bootContractsPath: string;
     };
@@ -1,3 +1,4 @@
+import fs from "node:fs";
 import { Task, afterAll, beforeAll, beforeEach, afterEach } from "vitest";
 
 import "./clarityValuesMatchers";
@@ -17,10 +18,65 @@ function getFullTestName(task: Task, names: string[]) {
   will run for all test file. In that case reports are collected in the after all.
 */
 
+let testFileName = "";
+let profiles: unknown[] = [];
+let fileCoverageReports: string[] = [];
+
+function collectReport() {
+  const { coverage, costs, profileCosts, includeBootContracts, bootContractsPath } =
+    global.options.clarinet;
+
+  const report = simnet.collectReport(includeBootContracts, bootContractsPath || "");
+  if (coverage) {
+    coverageReports.push(report.coverage);
+    fileCoverageReports.push(report.coverage);
+  }
+  if (costs) costsReports.push(report.costs);
+  if (profileCosts) profiles.push(...JSON.parse(report.profiles));
+}
+
+/*
+  The profiles of a test file are written when it's done, keyed by the test file name,
+  so that the files of a test run are merged in the same report.
+*/
+function writeProfiles() {
+  const profilesFilename = global.options.clarinet.profilesFilename || "costs-profiles.json";
+
+  let profilesReport: Record<string, unknown[]> = {};
+  if (fs.existsSync(profilesFilename)) {
+    try {
+      profilesReport = JSON.parse(fs.readFileSync(profilesFilename, "utf-8"));
+    } catch (e) {
+      console.warn(`unable to read ${profilesFilename}, it will be overwritten`);
+    }
+  }
+  profilesReport[testFileName] = profiles;
+  fs.writeFileSync(profilesFilename, JSON.stringify(profilesReport));
+}
+
+/*
+  With the html or cobertura `coverageFormat`, the lcov reports are also converted to this format.
+  The test files run in a single fork: the lcov reports of the files that already ran are kept
+  in `coverageReportsByFile`, and the converted report is written again after each file.
+*/
+function writeConvertedCoverage() {
+  const { coverageFilename, coverageFormat } = global.options.clarinet;
+
+  globalThis.coverageReportsByFile ??= {};
+  coverageReportsByFile[testFileName] = fileCoverageReports;
+  const lcov = Object.values(coverageReportsByFile).flat().join("\n");
+  const content = simnet.convertCoverageReport(lcov, coverageFormat);
+
+  const basename = (coverageFilename || "lcov.info").replace(/\.[^.]*$/, "");
+  fs.writeFileSync(`${basename}.${coverageFormat === "html" ? "html" : "xml"}`, content);
+}
+
 beforeEach(async (ctx) => {
-  const { coverage, initBeforeEach, manifestPath } = global.options.clarinet;
+  const { coverage, profileCosts, initBeforeEach, manifestPath } = global.options.clarinet;
 
+  testFileName = ctx.task.file?.name || "";
   if (initBeforeEach) {
+    simnet.setProfileCosts(!!profileCosts);
     await simnet.initSession(process.cwd(), manifestPath);
   }
 
@@ -32,31 +88,35 @@ beforeEach(async (ctx) => {
 });
 
 afterEach(async () => {
-  const { coverage, costs, initBeforeEach, includeBootContracts, bootContractsPath } =
-    global.options.clarinet;
+  const { coverage, costs, profileCosts, initBeforeEach } = global.options.clarinet;
 
-  if (initBeforeEach && (coverage || costs)) {
-    const report = simnet.collectReport(includeBootContracts, bootContractsPath || "");
-    if (coverage) coverageReports.push(report.coverage);
-    if (costs) costsReports.push(report.costs);
+  if (initBeforeEach && (coverage || costs || profileCosts)) {
+    collectReport();
   }
 });
 
 beforeAll(async () => {
-  const { initBeforeEach, manifestPath } = global.options.clarinet;
+  const { profileCosts, initBeforeEach, manifestPath } = global.options.clarinet;
 
+  profiles = [];
+  fileCoverageReports = [];
   if (!initBeforeEach) {
+    simnet.setProfileCosts(!!profileCosts);
     await simnet.initSession(process.cwd(), manifestPath);
   }
 });
 
 afterAll(() => {
-  const { coverage, costs, initBeforeEach, includeBootContracts, bootContractsPath } =
+  const { coverage, coverageFormat, costs, profileCosts, initBeforeEach } =
     global.options.clarinet;
 
-  if (!initBeforeEach && (coverage || costs)) {
-    const report = simnet.collectReport(includeBootContracts, bootContractsPath || "");
-    if (coverage) coverageReports.push(report.coverage);
-    if (costs) costsReports.push(report.costs);
+  if (!initBeforeEach && (coverage || costs || profileCosts)) {
+    collectReport();
+  }
+  if (coverage && coverageFormat && coverageFormat !== "lcov" && testFileName) {
+    writeConvertedCoverage();
+  }
+  if (profileCosts && testFileName) {
+    writeProfiles();
   }
 });
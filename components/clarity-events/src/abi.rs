@@ -0,0 +1,249 @@
+//! Events ABI of a project: for each contract, the JSON Schema of the events it
+//! can emit, and the events emitted by each of its functions, including the
+//! ones printed by the contracts it calls.
+
+use crate::analysis::{EventCollector, Settings};
+use crate::schema::{describe_events, events_json_schema, EventMap};
+use clarity_repl::clarity::vm::analysis::types::ContractAnalysis;
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::vm::ClarityName;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Functions called by each method, defined in the same contract or in other ones
+pub type ContractCalls =
+    BTreeMap<Option<ClarityName>, Vec<(QualifiedContractIdentifier, ClarityName)>>;
+
+/// Events of a contract, as collected by `EventCollector`
+pub struct ContractEvents {
+    pub event_map: EventMap,
+    pub contract_calls: ContractCalls,
+    /// Version of the events, bumped when their shape changes
+    pub version: u32,
+}
+
+/// Collect the events of a contract from its analysis, which must have been run
+/// with a type map (as the REPL does)
+pub fn collect_contract_events(
+    contract_analysis: &ContractAnalysis,
+    version: u32,
+) -> ContractEvents {
+    let mut event_collector = EventCollector::new(Settings::default());
+    let event_map = event_collector.run(contract_analysis);
+    ContractEvents {
+        event_map,
+        contract_calls: event_collector.contract_calls,
+        version,
+    }
+}
+
+/// Events emitted by a function, followed by the ones of the functions it calls
+fn function_events(
+    contracts: &BTreeMap<QualifiedContractIdentifier, ContractEvents>,
+    contract_id: &QualifiedContractIdentifier,
+    method: &ClarityName,
+    visited: &mut BTreeSet<(QualifiedContractIdentifier, ClarityName)>,
+) -> Vec<JsonValue> {
+    if !visited.insert((contract_id.clone(), method.clone())) {
+        return vec![];
+    }
+    let contract = match contracts.get(contract_id) {
+        Some(contract) => contract,
+        None => return vec![],
+    };
+    let mut events = describe_events(&contract.event_map)
+        .into_iter()
+        .filter(|event| event.method.as_deref() == Some(method.as_str()))
+        .map(|event| json!({ "contract_id": contract_id.to_string(), "event": event.name }))
+        .collect::<Vec<_>>();
+    if let Some(calls) = contract.contract_calls.get(&Some(method.clone())) {
+        for (callee_id, callee_method) in calls.iter() {
+            events.append(&mut function_events(
+                contracts,
+                callee_id,
+                callee_method,
+                visited,
+            ));
+        }
+    }
+    events
+}
+
+/// Events ABI of the given contracts. Calls to contracts missing from `contracts`
+/// (e.g. requirements) are not followed.
+pub fn events_abi(contracts: &BTreeMap<QualifiedContractIdentifier, ContractEvents>) -> JsonValue {
+    let mut abi = serde_json::Map::new();
+    for (contract_id, contract) in contracts.iter() {
+        let mut functions = serde_json::Map::new();
+        for method in contract.event_map.keys().flatten() {
+            let events = function_events(contracts, contract_id, method, &mut BTreeSet::new());
+            functions.insert(method.to_string(), json!(events));
+        }
+        abi.insert(
+            contract_id.to_string(),
+            json!({
+                "version": contract.version,
+                "events": events_json_schema(contract_id, &contract.event_map),
+                "functions": functions,
+            }),
+        );
+    }
+    json!({ "contracts": abi })
+}
+
+/// Compare an events ABI with a previous one, and list the contracts whose events
+/// changed shape without a version bump
+pub fn check_events_abi(previous: &JsonValue, current: &JsonValue) -> Result<(), Vec<String>> {
+    let mut errors = vec![];
+    let previous_contracts = previous["contracts"].as_object();
+    let current_contracts = current["contracts"].as_object();
+    if let (Some(previous_contracts), Some(current_contracts)) =
+        (previous_contracts, current_contracts)
+    {
+        for (contract_id, contract) in current_contracts.iter() {
+            let previous_contract = match previous_contracts.get(contract_id) {
+                Some(previous_contract) => previous_contract,
+                None => continue,
+            };
+            if previous_contract["events"] == contract["events"] {
+                continue;
+            }
+            let previous_version = previous_contract["version"].as_u64().unwrap_or(0);
+            let version = contract["version"].as_u64().unwrap_or(0);
+            if version <= previous_version {
+                errors.push(format!(
+                    "events of {} changed without a version bump (version {})",
+                    contract_id, previous_version
+                ));
+            }
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{NFTEventType, SmartContractEventData, StacksTransactionEvent};
+    use clarity_repl::clarity::vm::types::TypeSignature;
+    use clarity_repl::clarity::vm::EvaluationResult;
+    use clarity_repl::clarity::StacksEpochId;
+    use clarity_repl::repl::{Session, SessionSettings};
+
+    fn print_event(value_type: TypeSignature) -> StacksTransactionEvent {
+        StacksTransactionEvent::SmartContractEvent(SmartContractEventData {
+            data_type: json!({}),
+            value_type: Some(value_type),
+        })
+    }
+
+    fn contract_events(
+        method: &str,
+        value_type: TypeSignature,
+        calls: Vec<(QualifiedContractIdentifier, ClarityName)>,
+        version: u32,
+    ) -> ContractEvents {
+        let mut event_map = EventMap::new();
+        event_map.insert(Some(method.into()), vec![print_event(value_type)]);
+        let mut contract_calls = ContractCalls::new();
+        contract_calls.insert(Some(method.into()), calls);
+        ContractEvents {
+            event_map,
+            contract_calls,
+            version,
+        }
+    }
+
+    #[test]
+    fn test_cross_contract_events_and_version_bump() {
+        let token_id = QualifiedContractIdentifier::local("token").unwrap();
+        let market_id = QualifiedContractIdentifier::local("market").unwrap();
+
+        let mut contracts = BTreeMap::new();
+        contracts.insert(
+            token_id.clone(),
+            contract_events("transfer", TypeSignature::UIntType, vec![], 1),
+        );
+        contracts.insert(
+            market_id.clone(),
+            contract_events(
+                "buy",
+                TypeSignature::BoolType,
+                vec![(token_id.clone(), "transfer".into())],
+                1,
+            ),
+        );
+        let abi = events_abi(&contracts);
+        let buy_events = &abi["contracts"][market_id.to_string()]["functions"]["buy"];
+        assert_eq!(
+            buy_events,
+            &json!([
+                { "contract_id": market_id.to_string(), "event": "BuyPrintEvent" },
+                { "contract_id": token_id.to_string(), "event": "TransferPrintEvent" },
+            ])
+        );
+        assert_eq!(check_events_abi(&abi, &abi), Ok(()));
+
+        contracts.insert(
+            token_id.clone(),
+            contract_events("transfer", TypeSignature::IntType, vec![], 1),
+        );
+        let changed_abi = events_abi(&contracts);
+        assert_eq!(check_events_abi(&abi, &changed_abi).unwrap_err().len(), 1);
+
+        contracts.insert(
+            token_id.clone(),
+            contract_events("transfer", TypeSignature::IntType, vec![], 2),
+        );
+        let bumped_abi = events_abi(&contracts);
+        assert_eq!(check_events_abi(&abi, &bumped_abi), Ok(()));
+    }
+
+    #[test]
+    fn test_clarity_2_contract_events() {
+        let mut session = Session::new(SessionSettings::default());
+        session.update_epoch(StacksEpochId::Epoch24);
+        let snippet = r#"
+(define-non-fungible-token badge uint)
+(define-public (claim (name (string-ascii 16)))
+    (begin
+        (try! (nft-mint? badge u1 tx-sender))
+        (print { name: (unwrap-panic (slice? name u0 u4)), block: block-height })
+        (ok (to-consensus-buff? u1))))
+"#;
+        let contract_analysis = match session.eval(snippet.to_string(), None, false) {
+            Ok(execution) => match execution.result {
+                EvaluationResult::Contract(result) => result.contract.analysis,
+                _ => panic!("expected a contract"),
+            },
+            Err(diagnostics) => panic!("unable to deploy contract: {:?}", diagnostics),
+        };
+        let contract = collect_contract_events(&contract_analysis, 1);
+        let events = contract.event_map.get(&Some("claim".into())).unwrap();
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(data)) => {
+                assert_eq!(data.value_type, Some(TypeSignature::UIntType));
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        match &events[1] {
+            StacksTransactionEvent::SmartContractEvent(data) => {
+                assert!(matches!(data.value_type, Some(TypeSignature::TupleType(_))));
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        let abi = events_abi(&BTreeMap::from([(
+            contract_analysis.contract_identifier.clone(),
+            contract,
+        )]));
+        let schema = &abi["contracts"][contract_analysis.contract_identifier.to_string()]["events"]
+            ["$defs"]["ClaimPrintEventValue"];
+        assert_eq!(schema["type"], json!("object"));
+        assert!(schema["properties"]["name"].is_object());
+    }
+}
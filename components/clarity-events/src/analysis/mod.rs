@@ -1,7 +1,6 @@
 use clarity_repl::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
-use clarity_repl::clarity::analysis::type_checker::v2_05::TypeChecker;
 use clarity_repl::clarity::util::hash;
-use clarity_repl::clarity::vm::analysis::types::ContractAnalysis;
+use clarity_repl::clarity::vm::analysis::types::{ContractAnalysis, TypeMap};
 use clarity_repl::clarity::vm::types::{
     AssetIdentifier, BuffData, CharType, PrincipalData, QualifiedContractIdentifier, SequenceData,
     SequenceSubtype, StringSubtype, TypeSignature, Value,
@@ -215,6 +214,8 @@ pub struct NFTTransferEventData {
     pub sender: Option<PrincipalData>,
     pub recipient: Option<PrincipalData>,
     pub value: Option<JsonValue>,
+    /// Type of the token identifier
+    pub value_type: Option<TypeSignature>,
 }
 
 impl NFTTransferEventData {
@@ -224,6 +225,7 @@ impl NFTTransferEventData {
             sender: None,
             recipient: None,
             value: None,
+            value_type: None,
         }
     }
 }
@@ -239,7 +241,7 @@ impl Serialize for NFTTransferEventData {
             map.serialize_entry("sender", &sender.to_string())?;
         }
         if let Some(ref recipient) = self.recipient {
-            map.serialize_entry("sender", &recipient.to_string())?;
+            map.serialize_entry("recipient", &recipient.to_string())?;
         }
         if let Some(ref value) = self.value {
             map.serialize_entry("value", value)?;
@@ -253,6 +255,8 @@ pub struct NFTMintEventData {
     pub asset_identifier: AssetIdentifier,
     pub recipient: Option<PrincipalData>,
     pub value: Option<JsonValue>,
+    /// Type of the token identifier
+    pub value_type: Option<TypeSignature>,
 }
 
 impl NFTMintEventData {
@@ -261,6 +265,7 @@ impl NFTMintEventData {
             asset_identifier,
             recipient: None,
             value: None,
+            value_type: None,
         }
     }
 }
@@ -273,7 +278,7 @@ impl Serialize for NFTMintEventData {
         let mut map = serializer.serialize_map(Some(1))?;
         map.serialize_entry("asset_identifier", &self.asset_identifier.to_string())?;
         if let Some(ref recipient) = self.recipient {
-            map.serialize_entry("sender", &recipient.to_string())?;
+            map.serialize_entry("recipient", &recipient.to_string())?;
         }
         if let Some(ref value) = self.value {
             map.serialize_entry("value", &value)?;
@@ -287,6 +292,8 @@ pub struct NFTBurnEventData {
     pub asset_identifier: AssetIdentifier,
     pub sender: Option<PrincipalData>,
     pub value: Option<JsonValue>,
+    /// Type of the token identifier
+    pub value_type: Option<TypeSignature>,
 }
 
 impl NFTBurnEventData {
@@ -295,6 +302,7 @@ impl NFTBurnEventData {
             asset_identifier,
             sender: None,
             value: None,
+            value_type: None,
         }
     }
 }
@@ -346,7 +354,7 @@ impl Serialize for FTTransferEventData {
             map.serialize_entry("sender", &sender.to_string())?;
         }
         if let Some(ref recipient) = self.recipient {
-            map.serialize_entry("sender", &recipient.to_string())?;
+            map.serialize_entry("recipient", &recipient.to_string())?;
         }
         if let Some(ref amount) = self.amount {
             map.serialize_entry("amount", amount)?;
@@ -380,7 +388,7 @@ impl Serialize for FTMintEventData {
         let mut map = serializer.serialize_map(Some(1))?;
         map.serialize_entry("asset_identifier", &self.asset_identifier.to_string())?;
         if let Some(ref recipient) = self.recipient {
-            map.serialize_entry("sender", &recipient.to_string())?;
+            map.serialize_entry("recipient", &recipient.to_string())?;
         }
         if let Some(ref amount) = self.amount {
             map.serialize_entry("amount", amount)?;
@@ -426,6 +434,8 @@ impl Serialize for FTBurnEventData {
 #[derive(Debug, Clone, PartialEq)]
 pub struct SmartContractEventData {
     pub data_type: JsonValue,
+    /// Type of the printed value
+    pub value_type: Option<TypeSignature>,
 }
 
 impl Serialize for SmartContractEventData {
@@ -439,32 +449,40 @@ impl Serialize for SmartContractEventData {
     }
 }
 
-pub struct EventCollector<'a, 'b> {
+pub struct EventCollector {
     pub event_map: BTreeMap<Option<ClarityName>, Vec<StacksTransactionEvent>>,
+    /// Functions called by each method, defined in this contract or in other ones
+    pub contract_calls:
+        BTreeMap<Option<ClarityName>, Vec<(QualifiedContractIdentifier, ClarityName)>>,
     pub settings: Settings,
-    pub type_checker: TypeChecker<'a, 'b>,
+    /// Types inferred when the contract was analyzed
+    pub type_map: Option<TypeMap>,
     pub last_entries: Vec<StacksTransactionEvent>,
+    pub last_contract_calls: Vec<(QualifiedContractIdentifier, ClarityName)>,
     pub contract_identifier: Option<QualifiedContractIdentifier>,
 }
 
-impl<'a, 'b> EventCollector<'a, 'b> {
-    pub fn new(settings: Settings, type_checker: TypeChecker<'a, 'b>) -> EventCollector<'a, 'b> {
+impl EventCollector {
+    pub fn new(settings: Settings) -> EventCollector {
         let mut event_map = BTreeMap::new();
         event_map.insert(None, vec![]);
         Self {
             event_map,
+            contract_calls: BTreeMap::new(),
             settings,
-            type_checker,
+            type_map: None,
             last_entries: vec![],
+            last_contract_calls: vec![],
             contract_identifier: None,
         }
     }
 
-    pub fn run<'c>(
-        &'c mut self,
-        contract_analysis: &'c mut ContractAnalysis,
+    /// Collect the events of a contract, using the type map built by its analysis
+    pub fn run(
+        &mut self,
+        contract_analysis: &ContractAnalysis,
     ) -> BTreeMap<Option<ClarityName>, Vec<StacksTransactionEvent>> {
-        let _ = self.type_checker.run(contract_analysis);
+        self.type_map = contract_analysis.type_map.clone();
         self.contract_identifier = Some(contract_analysis.contract_identifier.clone());
         traverse(self, &contract_analysis.expressions);
         self.event_map.clone()
@@ -474,6 +492,13 @@ impl<'a, 'b> EventCollector<'a, 'b> {
         let mut events = vec![];
         events.append(&mut self.last_entries);
         self.event_map.insert(Some(method.clone()), events);
+        let mut contract_calls = vec![];
+        contract_calls.append(&mut self.last_contract_calls);
+        self.contract_calls.insert(Some(method), contract_calls);
+    }
+
+    pub fn get_type(&self, expr: &SymbolicExpression) -> Option<TypeSignature> {
+        self.type_map.as_ref()?.get_type_expected(expr).cloned()
     }
 
     pub fn add_event(&mut self, event: StacksTransactionEvent) {
@@ -598,7 +623,7 @@ pub fn serialize_type_signature(
     }
 }
 
-impl ASTVisitor<'_> for EventCollector<'_, '_> {
+impl ASTVisitor<'_> for EventCollector {
     fn visit_define_public(
         &mut self,
         _expr: &SymbolicExpression,
@@ -633,14 +658,14 @@ impl ASTVisitor<'_> for EventCollector<'_, '_> {
     }
 
     fn visit_print(&mut self, expr: &SymbolicExpression, value: &SymbolicExpression) -> bool {
-        let value_type_shape = self
-            .type_checker
-            .type_map
-            .get_type_expected(expr)
-            .expect("unable to infer value's type shape");
+        let value_type_shape = self.get_type(expr);
 
         let data = SmartContractEventData {
-            data_type: serialize_type_signature(value_type_shape, value),
+            data_type: value_type_shape
+                .as_ref()
+                .map(|value_type_shape| serialize_type_signature(value_type_shape, value))
+                .unwrap_or(json!("")),
+            value_type: value_type_shape,
         };
         self.add_event(StacksTransactionEvent::SmartContractEvent(data));
         true
@@ -843,16 +868,11 @@ impl ASTVisitor<'_> for EventCollector<'_, '_> {
         identifier: &SymbolicExpression,
         sender: &SymbolicExpression,
     ) -> bool {
-        let type_signature = self
-            .type_checker
-            .type_map
-            .get_type_expected(identifier)
-            .expect("unable to infer value's type shape");
-
         let mut data = NFTBurnEventData::default(self.create_asset_identifier(token.clone()));
-        match &identifier.expr {
-            SymbolicExpressionType::AtomValue(_value)
-            | SymbolicExpressionType::LiteralValue(_value) => {
+        data.value_type = self.get_type(identifier);
+        match (&identifier.expr, &data.value_type) {
+            (SymbolicExpressionType::AtomValue(_value), Some(type_signature))
+            | (SymbolicExpressionType::LiteralValue(_value), Some(type_signature)) => {
                 data.value = Some(serialize_type_signature(type_signature, identifier));
             }
             _ => {}
@@ -884,16 +904,11 @@ impl ASTVisitor<'_> for EventCollector<'_, '_> {
         sender: &SymbolicExpression,
         recipient: &SymbolicExpression,
     ) -> bool {
-        let type_signature = self
-            .type_checker
-            .type_map
-            .get_type_expected(identifier)
-            .expect("unable to infer value's type shape");
-
         let mut data = NFTTransferEventData::default(self.create_asset_identifier(token.clone()));
-        match &identifier.expr {
-            SymbolicExpressionType::AtomValue(_value)
-            | SymbolicExpressionType::LiteralValue(_value) => {
+        data.value_type = self.get_type(identifier);
+        match (&identifier.expr, &data.value_type) {
+            (SymbolicExpressionType::AtomValue(_value), Some(type_signature))
+            | (SymbolicExpressionType::LiteralValue(_value), Some(type_signature)) => {
                 data.value = Some(serialize_type_signature(type_signature, identifier));
             }
             _ => {}
@@ -936,16 +951,11 @@ impl ASTVisitor<'_> for EventCollector<'_, '_> {
         identifier: &SymbolicExpression,
         recipient: &SymbolicExpression,
     ) -> bool {
-        let type_signature = self
-            .type_checker
-            .type_map
-            .get_type_expected(identifier)
-            .expect("unable to infer value's type shape");
-
-        let mut data = NFTTransferEventData::default(self.create_asset_identifier(token.clone()));
-        match &identifier.expr {
-            SymbolicExpressionType::AtomValue(_value)
-            | SymbolicExpressionType::LiteralValue(_value) => {
+        let mut data = NFTMintEventData::default(self.create_asset_identifier(token.clone()));
+        data.value_type = self.get_type(identifier);
+        match (&identifier.expr, &data.value_type) {
+            (SymbolicExpressionType::AtomValue(_value), Some(type_signature))
+            | (SymbolicExpressionType::LiteralValue(_value), Some(type_signature)) => {
                 data.value = Some(serialize_type_signature(type_signature, identifier));
             }
             _ => {}
@@ -963,7 +973,7 @@ impl ASTVisitor<'_> for EventCollector<'_, '_> {
             _ => {}
         }
         self.add_event(StacksTransactionEvent::NFTEvent(
-            NFTEventType::NFTTransferEvent(data),
+            NFTEventType::NFTMintEvent(data),
         ));
 
         true
@@ -1007,6 +1017,31 @@ impl ASTVisitor<'_> for EventCollector<'_, '_> {
         true
     }
 
+    fn visit_call_user_defined(
+        &mut self,
+        _expr: &SymbolicExpression,
+        name: &ClarityName,
+        _args: &[SymbolicExpression],
+    ) -> bool {
+        if let Some(contract_identifier) = &self.contract_identifier {
+            self.last_contract_calls
+                .push((contract_identifier.clone(), name.clone()));
+        }
+        true
+    }
+
+    fn visit_static_contract_call(
+        &mut self,
+        _expr: &SymbolicExpression,
+        contract_identifier: &QualifiedContractIdentifier,
+        function_name: &ClarityName,
+        _args: &[SymbolicExpression],
+    ) -> bool {
+        self.last_contract_calls
+            .push((contract_identifier.clone(), function_name.clone()));
+        true
+    }
+
     fn visit_dynamic_contract_call(
         &mut self,
         _expr: &SymbolicExpression,
@@ -6,13 +6,13 @@ extern crate serde_derive;
 extern crate serde_json;
 
 pub mod analysis;
+pub mod schema;
 
 use analysis::{EventCollector, Settings};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use clarinet_files::FileLocation;
-use clarity_repl::clarity::analysis::type_checker::v2_05::TypeChecker;
 use clarity_repl::{
-    clarity::{costs::LimitedCostTracker, EvaluationResult},
+    clarity::EvaluationResult,
     repl::{Session, SessionSettings},
 };
 
@@ -28,12 +28,34 @@ enum Command {
     /// Format file
     #[clap(name = "scan", bin_name = "scan")]
     Scan(Scan),
+    /// Decode the raw_value of an event emitted by the stacks-node
+    #[clap(name = "decode", bin_name = "decode")]
+    Decode(Decode),
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
 struct Scan {
     /// File path
     pub file_path: String,
+    /// Output format
+    #[clap(long = "format", value_enum, default_value_t = Format::Debug)]
+    pub format: Format,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct Decode {
+    /// Hex encoded Clarity value, e.g. the `raw_value` of a contract_event
+    pub raw_value: String,
+}
+
+#[derive(ValueEnum, PartialEq, Clone, Debug)]
+enum Format {
+    /// Events emitted by each method
+    Debug,
+    /// JSON Schema of each event
+    JsonSchema,
+    /// TypeScript type of each event
+    Typescript,
 }
 
 pub fn main() {
@@ -46,11 +68,18 @@ pub fn main() {
     };
 
     match opts.command {
+        Command::Decode(cmd) => match schema::raw_value_to_json(&cmd.raw_value) {
+            Ok(value) => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
+            Err(e) => {
+                println!("{}", e);
+                std::process::exit(1);
+            }
+        },
         Command::Scan(cmd) => {
             let file = FileLocation::from_path_string(&cmd.file_path).unwrap();
             let snippet = file.read_content_as_utf8().unwrap();
             let mut session = Session::new(SessionSettings::default());
-            let mut contract_analysis = match session.eval(snippet, None, false) {
+            let contract_analysis = match session.eval(snippet, None, false) {
                 Ok(execution) => match execution.result {
                     EvaluationResult::Contract(evaluation) => evaluation.contract.analysis,
                     _ => {
@@ -65,12 +94,22 @@ pub fn main() {
             };
 
             {
-                let mut analysis_db = session.interpreter.datastore.as_analysis_db();
-                let cost_track = LimitedCostTracker::new_free();
-                let type_checker = TypeChecker::new(&mut analysis_db, cost_track, true);
                 let settings = Settings::default();
-                let mut event_collector = EventCollector::new(settings, type_checker);
-                let event_map = event_collector.run(&mut contract_analysis);
+                let mut event_collector = EventCollector::new(settings);
+                let event_map = event_collector.run(&contract_analysis);
+                let contract_id = &contract_analysis.contract_identifier;
+                match cmd.format {
+                    Format::JsonSchema => {
+                        let schema = schema::events_json_schema(contract_id, &event_map);
+                        println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+                        return;
+                    }
+                    Format::Typescript => {
+                        print!("{}", schema::events_typescript(contract_id, &event_map));
+                        return;
+                    }
+                    Format::Debug => {}
+                }
                 for (key, events) in event_map.iter() {
                     if events.is_empty() {
                         continue;
@@ -3,4 +3,6 @@ extern crate serde;
 #[macro_use]
 extern crate serde_json;
 
+pub mod abi;
 pub mod analysis;
+pub mod schema;
@@ -0,0 +1,792 @@
+//! JSON Schemas and TypeScript types of the events a contract can emit.
+//!
+//! The schemas describe the events as posted by the stacks-node to its event observers (and
+//! as serialized by `clarity_repl::utils::serialize_event`): the `type` of the event (e.g.
+//! `contract_event` or `nft_mint_event`), and its payload under a key named after the type,
+//! e.g. `{ "type": "contract_event", "contract_event": { "contract_identifier", "topic",
+//! "value", "raw_value" } }`. Amounts are decimal strings.
+//!
+//! The node encodes the Clarity values of `print` and NFT events in `raw_value`, the consensus
+//! serialization of the value as a `0x` prefixed hex string. Once decoded with
+//! `raw_value_to_json`, the value of an event is described by a separate definition, named
+//! after the event with a `Value` suffix (e.g. `ClaimPrintEventValue`).
+//!
+//! Clarity values are decoded to JSON as follows:
+//! - `bool` as a boolean,
+//! - `int` and `uint` as decimal strings (128 bits integers don't fit in JSON numbers),
+//! - `principal` as a string, and `buff` as an hex string prefixed with `0x`,
+//! - `string-ascii` and `string-utf8` as strings,
+//! - `list` as an array and tuples as objects,
+//! - `optional` as the inner value or `null`,
+//! - `response` as `{ "success": true, "value": ... }` or `{ "success": false, "value": ... }`.
+
+use crate::analysis::{FTEventType, NFTEventType, STXEventType, StacksTransactionEvent};
+use clarity_repl::clarity::util::hash;
+use clarity_repl::clarity::vm::types::{
+    CharType, QualifiedContractIdentifier, SequenceData, SequenceSubtype, StringSubtype,
+    TypeSignature, Value,
+};
+use clarity_repl::clarity::vm::ClarityName;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+const JSON_SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Events emitted by each method, as returned by `EventCollector::run`
+pub type EventMap = BTreeMap<Option<ClarityName>, Vec<StacksTransactionEvent>>;
+
+/// An event a method can emit, with the type of the value it carries
+pub struct EventDescription {
+    /// Name of the event, in PascalCase, e.g. `TransferPrintEvent`
+    pub name: String,
+    pub method: Option<String>,
+    /// Type of the event, as posted by the stacks-node, e.g. `contract_event`
+    pub event_type: &'static str,
+    /// Type of the printed value, or of the NFT identifier
+    pub value_type: Option<TypeSignature>,
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Type of the event posted by the stacks-node, the name given to the event in the
+/// schemas, and the type of the value it carries
+fn event_type_and_value(
+    event: &StacksTransactionEvent,
+) -> (&'static str, &'static str, Option<TypeSignature>) {
+    match event {
+        StacksTransactionEvent::SmartContractEvent(data) => {
+            ("contract_event", "print", data.value_type.clone())
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(_)) => {
+            ("stx_transfer_event", "transfer_stx", None)
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXMintEvent(_)) => {
+            ("stx_mint_event", "mint_stx", None)
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(_)) => {
+            ("stx_burn_event", "burn_stx", None)
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(_)) => {
+            ("stx_lock_event", "lock_stx", None)
+        }
+        StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(_)) => {
+            ("ft_transfer_event", "transfer_ft", None)
+        }
+        StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(_)) => {
+            ("ft_mint_event", "mint_ft", None)
+        }
+        StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(_)) => {
+            ("ft_burn_event", "burn_ft", None)
+        }
+        StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(data)) => (
+            "nft_transfer_event",
+            "transfer_nft",
+            data.value_type.clone(),
+        ),
+        StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(data)) => {
+            ("nft_mint_event", "mint_nft", data.value_type.clone())
+        }
+        StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data)) => {
+            ("nft_burn_event", "burn_nft", data.value_type.clone())
+        }
+    }
+}
+
+/// List the events of each method, naming them after the method and the event type.
+/// A method emitting several events of the same type gets numbered names.
+pub fn describe_events(event_map: &EventMap) -> Vec<EventDescription> {
+    let mut descriptions = vec![];
+    for (method, events) in event_map.iter() {
+        let prefix = match method {
+            Some(method) => pascal_case(method),
+            None => "TopLevel".to_string(),
+        };
+        let typed_events = events.iter().map(event_type_and_value).collect::<Vec<_>>();
+        for (event_type, label, value_type) in typed_events.iter() {
+            let count = typed_events
+                .iter()
+                .filter(|(t, _, _)| t == event_type)
+                .count();
+            let index = descriptions
+                .iter()
+                .filter(|d: &&EventDescription| {
+                    d.method.as_deref() == method.as_deref() && &d.event_type == event_type
+                })
+                .count();
+            let mut name = format!("{}{}", prefix, pascal_case(label));
+            if count > 1 {
+                name.push_str(&index.to_string());
+            }
+            name.push_str("Event");
+            descriptions.push(EventDescription {
+                name,
+                method: method.as_ref().map(|method| method.to_string()),
+                event_type: *event_type,
+                value_type: value_type.clone(),
+            });
+        }
+    }
+    descriptions
+}
+
+/// JSON Schema of the JSON encoding of a Clarity value of the given type
+pub fn json_schema(type_signature: &TypeSignature) -> JsonValue {
+    match type_signature {
+        TypeSignature::NoType => json!({}),
+        TypeSignature::BoolType => json!({ "type": "boolean" }),
+        TypeSignature::IntType => json!({ "type": "string", "pattern": "^-?[0-9]+$" }),
+        TypeSignature::UIntType => json!({ "type": "string", "pattern": "^[0-9]+$" }),
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(len)) => json!({
+            "type": "string",
+            "pattern": "^0x([0-9a-f]{2})*$",
+            "maxLength": 2 + 2 * u32::from(len),
+        }),
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(len))) => {
+            json!({ "type": "string", "maxLength": u32::from(len) })
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(len))) => {
+            json!({ "type": "string", "maxLength": u32::from(len) })
+        }
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => json!({
+            "type": "array",
+            "items": json_schema(list.get_list_item_type()),
+            "maxItems": list.get_max_len(),
+        }),
+        TypeSignature::TupleType(tuple) => {
+            let properties = tuple
+                .get_type_map()
+                .iter()
+                .map(|(name, type_signature)| (name.to_string(), json_schema(type_signature)))
+                .collect::<serde_json::Map<_, _>>();
+            let required = tuple
+                .get_type_map()
+                .keys()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        }
+        TypeSignature::OptionalType(inner) => json!({
+            "anyOf": [json_schema(inner), { "type": "null" }],
+        }),
+        TypeSignature::ResponseType(types) => {
+            let (ok_type, err_type) = types.as_ref();
+            let branch = |success: bool, type_signature: &TypeSignature| {
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "success": { "const": success },
+                        "value": json_schema(type_signature),
+                    },
+                    "required": ["success", "value"],
+                    "additionalProperties": false,
+                })
+            };
+            json!({ "oneOf": [branch(true, ok_type), branch(false, err_type)] })
+        }
+        // Principals, and the contracts passed as trait references
+        _ => json!({ "type": "string" }),
+    }
+}
+
+fn typescript_key(name: &str) -> String {
+    if name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+/// TypeScript type of the JSON encoding of a Clarity value of the given type
+pub fn typescript_type(type_signature: &TypeSignature) -> String {
+    match type_signature {
+        TypeSignature::NoType => "never".to_string(),
+        TypeSignature::BoolType => "boolean".to_string(),
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => {
+            let item_type = typescript_type(list.get_list_item_type());
+            if item_type.contains(' ') {
+                format!("({})[]", item_type)
+            } else {
+                format!("{}[]", item_type)
+            }
+        }
+        TypeSignature::TupleType(tuple) => {
+            let fields = tuple
+                .get_type_map()
+                .iter()
+                .map(|(name, type_signature)| {
+                    format!(
+                        "{}: {}",
+                        typescript_key(name),
+                        typescript_type(type_signature)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("{{ {} }}", fields.join("; "))
+        }
+        TypeSignature::OptionalType(inner) => format!("{} | null", typescript_type(inner)),
+        TypeSignature::ResponseType(types) => {
+            let (ok_type, err_type) = types.as_ref();
+            format!(
+                "{{ success: true; value: {} }} | {{ success: false; value: {} }}",
+                typescript_type(ok_type),
+                typescript_type(err_type)
+            )
+        }
+        // Integers, buffers, strings and principals
+        _ => "string".to_string(),
+    }
+}
+
+/// JSON encoding of a Clarity value, as described by `json_schema`
+pub fn value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Bool(value) => json!(value),
+        Value::Int(value) => json!(value.to_string()),
+        Value::UInt(value) => json!(value.to_string()),
+        Value::Principal(principal) => json!(principal.to_string()),
+        Value::CallableContract(callable) => json!(callable.contract_identifier.to_string()),
+        Value::Sequence(SequenceData::Buffer(buffer)) => {
+            json!(format!("0x{}", hash::to_hex(&buffer.data)))
+        }
+        Value::Sequence(SequenceData::String(CharType::ASCII(data))) => {
+            json!(String::from_utf8_lossy(&data.data))
+        }
+        Value::Sequence(SequenceData::String(CharType::UTF8(data))) => {
+            json!(String::from_utf8_lossy(&data.data.concat()))
+        }
+        Value::Sequence(SequenceData::List(list)) => {
+            JsonValue::Array(list.data.iter().map(value_to_json).collect())
+        }
+        Value::Tuple(tuple) => JsonValue::Object(
+            tuple
+                .data_map
+                .iter()
+                .map(|(name, value)| (name.to_string(), value_to_json(value)))
+                .collect(),
+        ),
+        Value::Optional(optional) => match &optional.data {
+            Some(value) => value_to_json(value),
+            None => JsonValue::Null,
+        },
+        Value::Response(response) => json!({
+            "success": response.committed,
+            "value": value_to_json(&response.data),
+        }),
+    }
+}
+
+/// Decode the `raw_value` of an event emitted by the stacks-node to its JSON encoding
+pub fn raw_value_to_json(raw_value: &str) -> Result<JsonValue, String> {
+    let bytes = hash::hex_bytes(raw_value.trim_start_matches("0x"))
+        .map_err(|e| format!("invalid raw_value hex ({})", e))?;
+    let value = Value::deserialize_read(&mut bytes.as_slice(), None, false)
+        .map_err(|e| format!("unable to deserialize raw_value ({})", e))?;
+    Ok(value_to_json(&value))
+}
+
+/// A property of the payload of an event, as posted by the stacks-node
+struct PayloadProperty {
+    name: &'static str,
+    schema: JsonValue,
+    typescript: String,
+    required: bool,
+}
+
+impl PayloadProperty {
+    fn new(name: &'static str, schema: JsonValue, typescript: &str) -> PayloadProperty {
+        PayloadProperty {
+            name,
+            schema,
+            typescript: typescript.to_string(),
+            required: true,
+        }
+    }
+
+    fn optional(mut self) -> PayloadProperty {
+        self.required = false;
+        self
+    }
+}
+
+/// Properties of the payload of an event, e.g. `contract_event`. The `value` of
+/// `print` and NFT events is the node's own encoding of the Clarity value, the typed
+/// value being the decoded `raw_value`.
+fn payload_properties(
+    event: &EventDescription,
+    contract_identifier: &QualifiedContractIdentifier,
+) -> Vec<PayloadProperty> {
+    let string = || json!({ "type": "string" });
+    let amount = || json_schema(&TypeSignature::UIntType);
+    let principal = |name| PayloadProperty::new(name, string(), "string");
+    let value = PayloadProperty::new(
+        "value",
+        json!({ "description": "Clarity value, as encoded by the stacks-node" }),
+        "unknown",
+    );
+    let raw_value = PayloadProperty::new(
+        "raw_value",
+        json!({
+            "type": "string",
+            "pattern": "^0x([0-9a-f]{2})*$",
+            "description": match event.value_type {
+                Some(_) => format!(
+                    "Consensus serialization of the value, described by #/$defs/{}Value once decoded",
+                    event.name
+                ),
+                None => "Consensus serialization of the value".to_string(),
+            },
+        }),
+        "string",
+    )
+    // not emitted by the REPL
+    .optional();
+    match event.event_type {
+        "contract_event" => vec![
+            PayloadProperty::new(
+                "contract_identifier",
+                json!({ "const": contract_identifier.to_string() }),
+                "string",
+            ),
+            PayloadProperty::new("topic", json!({ "const": "print" }), "\"print\""),
+            value,
+            raw_value,
+        ],
+        "stx_transfer_event" => vec![
+            principal("sender"),
+            principal("recipient"),
+            PayloadProperty::new("amount", amount(), "string"),
+            PayloadProperty::new("memo", string(), "string").optional(),
+        ],
+        "stx_mint_event" => vec![
+            principal("recipient"),
+            PayloadProperty::new("amount", amount(), "string"),
+        ],
+        "stx_burn_event" => vec![
+            principal("sender"),
+            PayloadProperty::new("amount", amount(), "string"),
+        ],
+        "stx_lock_event" => vec![
+            PayloadProperty::new("locked_amount", amount(), "string"),
+            PayloadProperty::new("unlock_height", amount(), "string"),
+            principal("locked_address"),
+            principal("contract_identifier").optional(),
+        ],
+        "ft_transfer_event" => vec![
+            principal("asset_identifier"),
+            principal("sender"),
+            principal("recipient"),
+            PayloadProperty::new("amount", amount(), "string"),
+        ],
+        "ft_mint_event" => vec![
+            principal("asset_identifier"),
+            principal("recipient"),
+            PayloadProperty::new("amount", amount(), "string"),
+        ],
+        "ft_burn_event" => vec![
+            principal("asset_identifier"),
+            principal("sender"),
+            PayloadProperty::new("amount", amount(), "string"),
+        ],
+        "nft_transfer_event" => vec![
+            principal("asset_identifier"),
+            principal("sender"),
+            principal("recipient"),
+            value,
+            raw_value,
+        ],
+        "nft_mint_event" => vec![
+            principal("asset_identifier"),
+            principal("recipient"),
+            value,
+            raw_value,
+        ],
+        _ => vec![
+            principal("asset_identifier"),
+            principal("sender"),
+            value,
+            raw_value,
+        ],
+    }
+}
+
+/// JSON Schema document of the events of a contract, with one definition per event,
+/// and one per decoded value
+pub fn events_json_schema(
+    contract_identifier: &QualifiedContractIdentifier,
+    event_map: &EventMap,
+) -> JsonValue {
+    let mut definitions = serde_json::Map::new();
+    let mut references = vec![];
+    for event in describe_events(event_map) {
+        let mut properties = serde_json::Map::new();
+        let mut required = vec![];
+        for property in payload_properties(&event, contract_identifier) {
+            if property.required {
+                required.push(property.name);
+            }
+            properties.insert(property.name.into(), property.schema);
+        }
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "type": { "const": event.event_type },
+                event.event_type: {
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                },
+                // set by the stacks-node
+                "txid": { "type": "string" },
+                "event_index": { "type": "integer" },
+                "committed": { "type": "boolean" },
+            },
+            "required": ["type", event.event_type],
+        });
+        if let Some(method) = &event.method {
+            schema["description"] = json!(format!("Emitted by {}", method));
+        }
+        references.push(json!({ "$ref": format!("#/$defs/{}", event.name) }));
+        definitions.insert(event.name.clone(), schema);
+        if let Some(value_type) = &event.value_type {
+            definitions.insert(format!("{}Value", event.name), json_schema(value_type));
+        }
+    }
+    json!({
+        "$schema": JSON_SCHEMA_DRAFT,
+        "$id": contract_identifier.to_string(),
+        "title": format!("Events of {}", contract_identifier),
+        "oneOf": references,
+        "$defs": definitions,
+    })
+}
+
+/// TypeScript declarations of the events of a contract, with one type per event
+/// and per decoded value, and a union of all the events
+pub fn events_typescript(
+    contract_identifier: &QualifiedContractIdentifier,
+    event_map: &EventMap,
+) -> String {
+    let mut output = format!("// Events of {}\n", contract_identifier);
+    let events = describe_events(event_map);
+    for event in events.iter() {
+        output.push('\n');
+        if let Some(method) = &event.method {
+            output.push_str(&format!("/** Emitted by `{}` */\n", method));
+        }
+        output.push_str(&format!(
+            "export type {} = {{\n  type: \"{}\";\n  {}: {{\n",
+            event.name, event.event_type, event.event_type
+        ));
+        for property in payload_properties(event, contract_identifier) {
+            output.push_str(&format!(
+                "    {}{}: {};\n",
+                property.name,
+                if property.required { "" } else { "?" },
+                property.typescript
+            ));
+        }
+        output.push_str(
+            "  };\n  txid?: string;\n  event_index?: number;\n  committed?: boolean;\n};\n",
+        );
+        if let Some(value_type) = &event.value_type {
+            output.push_str(&format!(
+                "\n/** Value of `{}`, once `raw_value` is decoded */\nexport type {}Value = {};\n",
+                event.name,
+                event.name,
+                typescript_type(value_type)
+            ));
+        }
+    }
+
+    let names = events
+        .iter()
+        .map(|event| event.name.as_str())
+        .collect::<Vec<_>>();
+    output.push_str(&format!(
+        "\nexport type ContractEvent = {};\n",
+        if names.is_empty() {
+            "never".to_string()
+        } else {
+            names.join(" | ")
+        }
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{NFTMintEventData, STXTransferEventData, SmartContractEventData};
+    use clarity_repl::clarity::vm::events as node_events;
+    use clarity_repl::clarity::vm::types::{AssetIdentifier, BuffData, PrincipalData, TupleData};
+    use clarity_repl::utils::serialize_event;
+
+    fn print_event(value_type: TypeSignature) -> StacksTransactionEvent {
+        StacksTransactionEvent::SmartContractEvent(SmartContractEventData {
+            data_type: json!({}),
+            value_type: Some(value_type),
+        })
+    }
+
+    /// Check `instance` against the keywords emitted by `events_json_schema`,
+    /// patterns and lengths aside
+    fn is_valid(root: &JsonValue, schema: &JsonValue, instance: &JsonValue) -> bool {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/$defs/");
+            return is_valid(root, &root["$defs"][name], instance);
+        }
+        if let Some(schemas) = schema["oneOf"].as_array() {
+            let matches = schemas.iter().filter(|s| is_valid(root, s, instance));
+            if matches.count() != 1 {
+                return false;
+            }
+        }
+        if let Some(schemas) = schema["anyOf"].as_array() {
+            if !schemas.iter().any(|s| is_valid(root, s, instance)) {
+                return false;
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != instance {
+                return false;
+            }
+        }
+        let type_matches = match schema["type"].as_str() {
+            Some("object") => instance.is_object(),
+            Some("array") => instance.is_array(),
+            Some("string") => instance.is_string(),
+            Some("boolean") => instance.is_boolean(),
+            Some("integer") => instance.is_i64() || instance.is_u64(),
+            Some("null") => instance.is_null(),
+            _ => true,
+        };
+        if !type_matches {
+            return false;
+        }
+        if let Some(object) = instance.as_object() {
+            let required = schema["required"].as_array().cloned().unwrap_or_default();
+            if required
+                .iter()
+                .any(|name| !object.contains_key(name.as_str().unwrap()))
+            {
+                return false;
+            }
+            for (name, value) in object.iter() {
+                let valid = match schema["properties"].get(name) {
+                    Some(property) => is_valid(root, property, value),
+                    None => schema["additionalProperties"] != json!(false),
+                };
+                if !valid {
+                    return false;
+                }
+            }
+        }
+        if let (Some(items), Some(array)) = (schema.get("items"), instance.as_array()) {
+            if !array.iter().all(|item| is_valid(root, items, item)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn test_schema_matches_node_events() {
+        let contract_id = QualifiedContractIdentifier::local("badges").unwrap();
+        let asset_identifier = AssetIdentifier {
+            contract_identifier: contract_id.clone(),
+            asset_name: "badge".into(),
+        };
+        let tuple_type = TypeSignature::TupleType(
+            vec![
+                ("amount".into(), TypeSignature::UIntType),
+                (
+                    "name".into(),
+                    TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(
+                        16u32.try_into().unwrap(),
+                    ))),
+                ),
+            ]
+            .try_into()
+            .unwrap(),
+        );
+        let mut event_map = EventMap::new();
+        event_map.insert(
+            Some("claim".into()),
+            vec![
+                print_event(tuple_type),
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(NFTMintEventData {
+                    asset_identifier: asset_identifier.clone(),
+                    recipient: None,
+                    value: None,
+                    value_type: Some(TypeSignature::UIntType),
+                })),
+                StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(
+                    STXTransferEventData {
+                        sender: None,
+                        recipient: None,
+                        amount: None,
+                        memo: None,
+                    },
+                )),
+            ],
+        );
+        let schema = events_json_schema(&contract_id, &event_map);
+
+        let principal = PrincipalData::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM").unwrap();
+        let printed_value = Value::Tuple(
+            TupleData::from_data(vec![
+                ("amount".into(), Value::UInt(u128::MAX)),
+                (
+                    "name".into(),
+                    Value::string_ascii_from_bytes(b"gold".to_vec()).unwrap(),
+                ),
+            ])
+            .unwrap(),
+        );
+        let print = serialize_event(&node_events::StacksTransactionEvent::SmartContractEvent(
+            node_events::SmartContractEventData {
+                key: (contract_id.clone(), "print".into()),
+                value: printed_value.clone(),
+            },
+        ));
+        let mint = serialize_event(&node_events::StacksTransactionEvent::NFTEvent(
+            node_events::NFTEventType::NFTMintEvent(node_events::NFTMintEventData {
+                asset_identifier,
+                recipient: principal.clone(),
+                value: Value::UInt(1),
+            }),
+        ));
+        let transfer = serialize_event(&node_events::StacksTransactionEvent::STXEvent(
+            node_events::STXEventType::STXTransferEvent(node_events::STXTransferEventData {
+                sender: principal.clone(),
+                recipient: principal,
+                amount: 100,
+                memo: BuffData::empty(),
+            }),
+        ));
+        for event in [&print, &mint, &transfer] {
+            assert!(is_valid(&schema, &schema, event), "invalid event {}", event);
+        }
+
+        // the node also sends the raw value, and the transaction of the event
+        let raw_value = format!(
+            "0x{}",
+            hash::to_hex(&printed_value.serialize_to_vec().unwrap())
+        );
+        let mut node_print = print.clone();
+        node_print["contract_event"]["raw_value"] = json!(raw_value);
+        node_print["txid"] = json!("0x00");
+        node_print["event_index"] = json!(0);
+        node_print["committed"] = json!(true);
+        assert!(is_valid(&schema, &schema, &node_print));
+        let value_schema = &schema["$defs"]["ClaimPrintEventValue"];
+        assert!(is_valid(
+            &schema,
+            value_schema,
+            &raw_value_to_json(&raw_value).unwrap()
+        ));
+
+        // events printed by other contracts, or in another shape, don't match
+        let mut other_print = print.clone();
+        other_print["contract_event"]["contract_identifier"] =
+            json!("ST000000000000000000002AMW42H.pox");
+        assert!(!is_valid(&schema, &schema, &other_print));
+        let flat_print =
+            json!({ "event_type": "print", "value": print["contract_event"]["value"] });
+        assert!(!is_valid(&schema, &schema, &flat_print));
+    }
+
+    #[test]
+    fn test_json_schema_and_typescript_types() {
+        let tuple = TypeSignature::TupleType(
+            vec![
+                ("amount".into(), TypeSignature::UIntType),
+                (
+                    "memo".into(),
+                    TypeSignature::OptionalType(Box::new(TypeSignature::BoolType)),
+                ),
+            ]
+            .try_into()
+            .unwrap(),
+        );
+        assert_eq!(
+            typescript_type(&tuple),
+            "{ amount: string; memo: boolean | null }"
+        );
+        let schema = json_schema(&tuple);
+        assert_eq!(schema["required"], json!(["amount", "memo"]));
+        assert_eq!(schema["properties"]["amount"]["type"], "string");
+
+        let value = Value::Tuple(
+            TupleData::from_data(vec![
+                ("amount".into(), Value::UInt(u128::MAX)),
+                ("memo".into(), Value::none()),
+            ])
+            .unwrap(),
+        );
+        assert_eq!(
+            value_to_json(&value),
+            json!({ "amount": u128::MAX.to_string(), "memo": null })
+        );
+    }
+
+    #[test]
+    fn test_raw_value_to_json() {
+        let value = Value::okay(Value::UInt(42)).unwrap();
+        let raw_value = format!("0x{}", hash::to_hex(&value.serialize_to_vec().unwrap()));
+        assert_eq!(
+            raw_value_to_json(&raw_value).unwrap(),
+            json!({ "success": true, "value": "42" })
+        );
+        assert!(raw_value_to_json("0xzz").is_err());
+        assert!(raw_value_to_json("0x07").is_err());
+    }
+
+    #[test]
+    fn test_events_names() {
+        let mut event_map = EventMap::new();
+        event_map.insert(
+            Some("transfer-token".into()),
+            vec![
+                print_event(TypeSignature::UIntType),
+                print_event(TypeSignature::BoolType),
+            ],
+        );
+        let contract_id = QualifiedContractIdentifier::transient();
+
+        let schema = events_json_schema(&contract_id, &event_map);
+        assert_eq!(
+            schema["$defs"]["TransferTokenPrint1EventValue"]["type"],
+            "boolean"
+        );
+        assert_eq!(
+            schema["$defs"]["TransferTokenPrint1Event"]["properties"]["type"]["const"],
+            "contract_event"
+        );
+
+        let typescript = events_typescript(&contract_id, &event_map);
+        assert!(typescript.contains("export type TransferTokenPrint0Event = {"));
+        assert!(typescript.contains("  type: \"contract_event\";\n  contract_event: {\n"));
+        assert!(typescript.contains("    raw_value?: string;\n"));
+        assert!(typescript.contains("export type TransferTokenPrint1EventValue = boolean;"));
+        assert!(typescript.contains(
+            "export type ContractEvent = TransferTokenPrint0Event | TransferTokenPrint1Event;"
+        ));
+    }
+}
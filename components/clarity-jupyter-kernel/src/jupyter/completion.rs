@@ -0,0 +1,74 @@
+use clarity_lsp::build_default_native_keywords_list;
+use clarity_repl::clarity::ClarityVersion;
+use clarity_repl::repl::Session;
+
+lazy_static! {
+    // Same native functions and keywords as the ones completed by the LSP
+    static ref KEYWORDS: Vec<String> = {
+        let mut keywords = build_default_native_keywords_list(ClarityVersion::latest())
+            .into_iter()
+            .map(|item| item.label)
+            .collect::<Vec<_>>();
+        keywords.sort();
+        keywords.dedup();
+        keywords
+    };
+}
+
+/// Commands handled by the kernel, in addition to the ones of the session
+pub const KERNEL_COMMANDS: &[&str] = &["::load_project"];
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || "-_!?<>=+*/:".contains(c)
+}
+
+/// Completions of the word ending at `cursor_pos` (in characters, as sent by
+/// Jupyter), with the range of characters they replace
+pub fn complete(code: &str, cursor_pos: usize) -> (Vec<String>, usize, usize) {
+    let chars = code.chars().take(cursor_pos).collect::<Vec<_>>();
+    let start = chars
+        .iter()
+        .rposition(|c| !is_word_char(*c))
+        .map(|position| position + 1)
+        .unwrap_or(0);
+    let word = chars[start..].iter().collect::<String>();
+    if word.is_empty() {
+        return (vec![], cursor_pos, cursor_pos);
+    }
+
+    let candidates: Box<dyn Iterator<Item = &str>> = if word.starts_with("::") {
+        Box::new(KERNEL_COMMANDS.iter().chain(Session::COMMANDS).copied())
+    } else {
+        Box::new(KEYWORDS.iter().map(|keyword| keyword.as_str()))
+    };
+    let matches = candidates
+        .filter(|candidate| candidate.starts_with(&word))
+        .map(|candidate| candidate.to_string())
+        .collect();
+    (matches, start, chars.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_keywords_and_commands() {
+        let (matches, start, end) = complete("(map-g", 6);
+        assert_eq!(matches, vec!["map-get?".to_string()]);
+        assert_eq!((start, end), (1, 6));
+
+        let (matches, start, _) = complete("::get_assets", 12);
+        assert_eq!(matches, vec!["::get_assets_maps".to_string()]);
+        assert_eq!(start, 0);
+
+        let (matches, _, _) = complete("::pro", 5);
+        assert_eq!(matches, vec!["::profile".to_string()]);
+
+        let (matches, _, _) = complete("::load", 6);
+        assert_eq!(matches, vec!["::load_project".to_string()]);
+
+        let (matches, _, _) = complete("(+ u1 ", 6);
+        assert!(matches.is_empty());
+    }
+}
@@ -1,8 +1,13 @@
+use clarity_repl::clarity::EvaluationResult;
+use clarity_repl::repl::diagnostic::output_diagnostic;
 use clarity_repl::repl::{Session, SessionSettings};
 
+use super::completion;
 use super::connection::Connection;
 use super::control_file;
+use super::display::{self, MimeBundle};
 use super::jupyter_message::JupyterMessage;
+use super::project;
 use super::CommandContext;
 
 use colored::*;
@@ -53,7 +58,13 @@ impl Server {
             zmq_context.socket(SocketType::PUB)?,
         )?));
 
-        let session = Session::new(SessionSettings::default());
+        let session = match project::initial_session() {
+            Ok(session) => session,
+            Err(e) => {
+                eprintln!("{}", e);
+                Session::new(SessionSettings::default())
+            }
+        };
 
         let (shutdown_requested_sender, shutdown_requested_receiver) = mpsc::channel();
 
@@ -155,26 +166,33 @@ impl Server {
                 .send(&mut *self.iopub.lock().unwrap())?;
             let mut has_error = false;
             for code in split_code_and_command(src) {
-                match self
-                    .session
-                    .formatted_interpretation(code.to_string(), None, false, None)
-                {
-                    Ok((result, _)) => {
-                        let res = result.join("\n");
-                        let mut data: HashMap<String, JsonValue> = HashMap::new();
-                        data.insert("text/plain".into(), json::from(res));
-                        message
-                            .new_message("execute_result")
-                            .with_content(object! {
-                                "execution_count" => execution_count,
-                                "data" => data,
-                                "metadata" => HashMap::new(),
-                            })
-                            .send(&mut *self.iopub.lock().unwrap())?;
+                match self.execute(&code) {
+                    Ok(mut outputs) => {
+                        // The last output is the result, the previous ones are
+                        // displayed before it (events, costs, etc.)
+                        let result = outputs.pop();
+                        for data in outputs {
+                            message
+                                .new_message("display_data")
+                                .with_content(object! {
+                                    "data" => data,
+                                    "metadata" => HashMap::new(),
+                                })
+                                .send(&mut *self.iopub.lock().unwrap())?;
+                        }
+                        if let Some(data) = result {
+                            message
+                                .new_message("execute_result")
+                                .with_content(object! {
+                                    "execution_count" => execution_count,
+                                    "data" => data,
+                                    "metadata" => HashMap::new(),
+                                })
+                                .send(&mut *self.iopub.lock().unwrap())?;
+                        }
                     }
-                    Err(result) => {
-                        let res = result.join("\n");
-                        has_error = false;
+                    Err(res) => {
+                        has_error = true;
                         message
                             .new_message("error")
                             .with_content(object! {
@@ -202,6 +220,86 @@ impl Server {
         }
     }
 
+    /// Execute a command or a snippet, returning its outputs
+    fn execute(&mut self, code: &str) -> Result<Vec<MimeBundle>, String> {
+        match code.trim() {
+            cmd if cmd.starts_with("::load_project") => {
+                let manifest = match cmd.split_once(' ') {
+                    Some((_, manifest)) => manifest.trim().to_string(),
+                    None => {
+                        let current_dir = std::env::current_dir().map_err(|e| e.to_string())?;
+                        project::find_manifest(&current_dir)
+                            .ok_or("no Clarinet.toml found".to_string())?
+                            .to_string_lossy()
+                            .to_string()
+                    }
+                };
+                self.session = project::load_project(&manifest)?;
+                Ok(vec![display::text(format!("Project {} loaded", manifest))])
+            }
+            cmd if cmd.starts_with("::get_assets_maps") => {
+                Ok(vec![display::assets_maps(&self.session)])
+            }
+            cmd if cmd.starts_with("::get_costs") => match cmd.split_once(' ') {
+                Some((_, snippet)) => self.run_snippet(snippet, true),
+                None => Err("Usage: ::get_costs <expr>".to_string()),
+            },
+            cmd if cmd.starts_with("::") => {
+                let (_, mut output, _) = self.session.handle_command(cmd);
+                if cmd == "::help" {
+                    output.push(format!(
+                        "{}",
+                        "::load_project <manifest>\t\tLoad the simnet deployment of a project"
+                            .yellow()
+                    ));
+                }
+                Ok(vec![display::text(output.join("\n"))])
+            }
+            _ => self.run_snippet(code, self.session.show_costs),
+        }
+    }
+
+    fn run_snippet(&mut self, snippet: &str, cost_track: bool) -> Result<Vec<MimeBundle>, String> {
+        let lines = snippet.lines().map(|l| l.to_string()).collect::<Vec<_>>();
+        let result = match self.session.eval(snippet.to_string(), None, cost_track) {
+            Ok(result) => result,
+            Err(diagnostics) => {
+                let mut output = vec![];
+                for diagnostic in diagnostics.iter() {
+                    output.append(&mut output_diagnostic(diagnostic, "<stdin>", &lines));
+                }
+                return Err(output.join("\n"));
+            }
+        };
+
+        let mut outputs = vec![];
+        let mut output = vec![];
+        for diagnostic in result.diagnostics.iter() {
+            output.append(&mut output_diagnostic(diagnostic, "<stdin>", &lines));
+        }
+        if !output.is_empty() {
+            outputs.push(display::text(output.join("\n")));
+        }
+        if !result.events.is_empty() {
+            outputs.push(display::events(&result.events));
+        }
+        if let Some(cost) = &result.cost {
+            outputs.push(display::costs(cost));
+        }
+        let value = match &result.result {
+            EvaluationResult::Contract(contract_result) => match &contract_result.result {
+                Some(value) => value.to_string(),
+                None => format!(
+                    "{} contract successfully stored",
+                    contract_result.contract.contract_identifier
+                ),
+            },
+            EvaluationResult::Snippet(snippet_result) => snippet_result.result.to_string(),
+        };
+        outputs.push(display::text(value));
+        Ok(outputs)
+    }
+
     fn handle_shell(
         self,
         mut connection: Connection,
@@ -227,6 +325,20 @@ impl Server {
                     .new_reply()
                     .with_content(object! {"status" => "complete"})
                     .send(&mut connection)?;
+            } else if message.message_type() == "complete_request" {
+                let cursor_pos = message.get_content()["cursor_pos"].as_usize().unwrap_or(0);
+                let (matches, cursor_start, cursor_end) =
+                    completion::complete(message.code(), cursor_pos);
+                message
+                    .new_reply()
+                    .with_content(object! {
+                        "status" => "ok",
+                        "matches" => matches,
+                        "cursor_start" => cursor_start,
+                        "cursor_end" => cursor_end,
+                        "metadata" => object!{},
+                    })
+                    .send(&mut connection)?;
             } else if message.message_type() == "execute_request" {
                 execution_channel.send(message)?;
                 execution_reply_receiver.recv()?.send(&mut connection)?;
@@ -0,0 +1,157 @@
+use clarity_repl::clarity::events::StacksTransactionEvent;
+use clarity_repl::clarity::CostSynthesis;
+use clarity_repl::repl::Session;
+use clarity_repl::utils::serialize_event;
+use json::JsonValue;
+use std::collections::HashMap;
+
+/// Outputs of an execution, by MIME type
+pub type MimeBundle = HashMap<String, JsonValue>;
+
+pub fn text(content: String) -> MimeBundle {
+    let mut data = MimeBundle::new();
+    data.insert("text/plain".into(), json::from(content));
+    data
+}
+
+fn escape_html(content: &str) -> String {
+    content
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn html_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut html = String::from("<table>\n<thead><tr>");
+    for header in headers {
+        html.push_str(&format!("<th>{}</th>", escape_html(header)));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", escape_html(cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>");
+    html
+}
+
+fn text_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths = headers.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let format_row = |row: &[String]| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+    let mut lines = vec![format_row(headers)];
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    for row in rows {
+        lines.push(format_row(row));
+    }
+    lines.join("\n")
+}
+
+fn table(headers: Vec<String>, rows: Vec<Vec<String>>) -> MimeBundle {
+    let mut data = text(text_table(&headers, &rows));
+    data.insert("text/html".into(), json::from(html_table(&headers, &rows)));
+    data
+}
+
+/// Balances of the accounts, as returned by `::get_assets_maps`
+pub fn assets_maps(session: &Session) -> MimeBundle {
+    let tokens = session.interpreter.get_tokens();
+    let mut headers = vec!["Address".to_string()];
+    for token in tokens.iter() {
+        headers.push(match token.as_str() {
+            "STX" => "uSTX".to_string(),
+            token => token.to_string(),
+        });
+    }
+
+    let mut rows = vec![];
+    for account in session.interpreter.get_accounts() {
+        let name = session
+            .settings
+            .initial_accounts
+            .iter()
+            .find(|initial_account| initial_account.address == account)
+            .map(|initial_account| initial_account.name.clone());
+        let mut row = vec![match name {
+            Some(name) => format!("{} ({})", account, name),
+            None => account.clone(),
+        }];
+        for token in tokens.iter() {
+            let balance = session.interpreter.get_balance_for_account(&account, token);
+            row.push(balance.to_string());
+        }
+        rows.push(row);
+    }
+    table(headers, rows)
+}
+
+/// Events emitted by an execution, as JSON
+pub fn events(events: &[StacksTransactionEvent]) -> MimeBundle {
+    let events = serde_json::Value::Array(events.iter().map(serialize_event).collect());
+    let mut data = text(serde_json::to_string_pretty(&events).unwrap_or_default());
+    if let Ok(events) = json::parse(&events.to_string()) {
+        data.insert("application/json".into(), events);
+    }
+    data
+}
+
+fn percentage(consumed: u64, limit: u64) -> String {
+    format!("{:.2} %", (consumed as f64 / limit as f64) * 100_f64)
+}
+
+/// Costs of an execution, compared to the block limits
+pub fn costs(cost: &CostSynthesis) -> MimeBundle {
+    let headers = ["", "Consumed", "Limit", "Percentage"]
+        .iter()
+        .map(|header| header.to_string())
+        .collect();
+    let rows = [
+        ("Runtime", cost.total.runtime, cost.limit.runtime),
+        ("Read count", cost.total.read_count, cost.limit.read_count),
+        (
+            "Read length (bytes)",
+            cost.total.read_length,
+            cost.limit.read_length,
+        ),
+        (
+            "Write count",
+            cost.total.write_count,
+            cost.limit.write_count,
+        ),
+        (
+            "Write length (bytes)",
+            cost.total.write_length,
+            cost.limit.write_length,
+        ),
+    ]
+    .iter()
+    .map(|(name, consumed, limit)| {
+        vec![
+            name.to_string(),
+            consumed.to_string(),
+            limit.to_string(),
+            percentage(*consumed, *limit),
+        ]
+    })
+    .collect();
+    table(headers, rows)
+}
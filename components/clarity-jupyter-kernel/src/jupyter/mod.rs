@@ -1,8 +1,11 @@
+pub mod completion;
 pub mod connection;
 pub mod control_file;
 pub mod core;
+pub mod display;
 pub mod install;
 pub mod jupyter_message;
+pub mod project;
 
 use failure::Error;
 use std;
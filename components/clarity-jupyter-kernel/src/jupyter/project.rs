@@ -0,0 +1,45 @@
+use clarinet_deployments::{generate_default_deployment, setup_session_with_deployment};
+use clarinet_files::chainhook_types::StacksNetwork;
+use clarinet_files::{FileLocation, ProjectManifest};
+use clarity_repl::clarity::diagnostic::Level;
+use clarity_repl::repl::{Session, SessionSettings};
+use std::path::{Path, PathBuf};
+
+/// Look for a Clarinet manifest in `dir` and its parent directories
+pub fn find_manifest(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join("Clarinet.toml"))
+        .find(|manifest| manifest.is_file())
+}
+
+/// Build a session with the simnet deployment of a Clarinet project applied
+pub fn load_project(manifest_path: &str) -> Result<Session, String> {
+    let manifest_location = FileLocation::from_path_string(manifest_path)?;
+    let manifest = ProjectManifest::from_location(&manifest_location)?;
+    let future = generate_default_deployment(&manifest, &StacksNetwork::Simnet, false, None, None);
+    let (deployment, artifacts) = hiro_system_kit::nestable_block_on(future)?;
+    if !artifacts.success {
+        let errors = artifacts
+            .diags
+            .values()
+            .flatten()
+            .filter(|diagnostic| diagnostic.level == Level::Error)
+            .map(|diagnostic| diagnostic.message.clone())
+            .collect::<Vec<_>>();
+        return Err(format!(
+            "unable to load {}:\n{}",
+            manifest_path,
+            errors.join("\n")
+        ));
+    }
+    Ok(setup_session_with_deployment(&manifest, &deployment, Some(&artifacts.asts)).session)
+}
+
+/// The session of the project found from the current directory, or a bare session
+pub fn initial_session() -> Result<Session, String> {
+    let current_dir = std::env::current_dir().map_err(|e| e.to_string())?;
+    match find_manifest(&current_dir) {
+        Some(manifest) => load_project(&manifest.to_string_lossy()),
+        None => Ok(Session::new(SessionSettings::default())),
+    }
+}
@@ -1,4 +1,4 @@
-mod requests;
+pub(crate) mod requests;
 
 pub mod backend;
 pub mod state;
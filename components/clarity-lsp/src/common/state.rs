@@ -668,6 +668,7 @@ pub async fn build_state(
         Some(&artifacts.asts),
         false,
         Some(StacksEpochId::Epoch21),
+        None,
     );
     for (contract_id, mut result) in contracts.into_iter() {
         let (_, contract_location) = match deployment.contracts.get(&contract_id) {
@@ -4,5 +4,6 @@ pub mod utils;
 pub mod vscode_bridge;
 
 pub use common::backend;
+pub use common::requests::completion::build_default_native_keywords_list;
 pub use common::state;
 pub use lsp_types;
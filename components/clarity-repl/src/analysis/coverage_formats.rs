@@ -0,0 +1,405 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Coverage data merged from one or several LCOV reports (such as the ones
+/// produced by `CoverageReporter::build_lcov_content`), keeping track of the
+/// tests that hit each line.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CoverageSummary {
+    pub files: BTreeMap<String, FileCoverage>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileCoverage {
+    pub functions: BTreeMap<String, FunctionCoverage>,
+    pub lines: BTreeMap<u32, LineCoverage>,
+    /// Hits per (line, block id, branch number)
+    pub branches: BTreeMap<(u32, String, String), u64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FunctionCoverage {
+    pub line: u32,
+    pub hits: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LineCoverage {
+    pub hits: u64,
+    pub tests: BTreeSet<String>,
+}
+
+impl FileCoverage {
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|l| l.hits > 0).count()
+    }
+
+    pub fn functions_hit(&self) -> usize {
+        self.functions.values().filter(|f| f.hits > 0).count()
+    }
+
+    pub fn branches_hit(&self) -> usize {
+        self.branches.values().filter(|hits| **hits > 0).count()
+    }
+
+    /// (hit, total) branches on a line
+    fn line_branches(&self, line: u32) -> (usize, usize) {
+        let branches = self
+            .branches
+            .iter()
+            .filter(|((l, _, _), _)| *l == line)
+            .collect::<Vec<_>>();
+        let hit = branches.iter().filter(|(_, hits)| **hits > 0).count();
+        (hit, branches.len())
+    }
+}
+
+impl CoverageSummary {
+    pub fn from_lcov(content: &str) -> CoverageSummary {
+        let mut summary = CoverageSummary::default();
+        let mut test_name = String::new();
+        let mut current_file: Option<String> = None;
+
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                if line == "end_of_record" {
+                    current_file = None;
+                }
+                continue;
+            };
+            if key == "TN" {
+                test_name = value.to_string();
+                continue;
+            }
+            if key == "SF" {
+                summary.files.entry(value.to_string()).or_default();
+                current_file = Some(value.to_string());
+                continue;
+            }
+            let Some(file) = current_file.as_ref().and_then(|f| summary.files.get_mut(f)) else {
+                continue;
+            };
+            let fields = value.split(',').collect::<Vec<_>>();
+            match (key, fields.as_slice()) {
+                ("FN", [line, name]) => {
+                    let function = file.functions.entry(name.to_string()).or_default();
+                    function.line = line.parse().unwrap_or_default();
+                }
+                ("FNDA", [hits, name]) => {
+                    let function = file.functions.entry(name.to_string()).or_default();
+                    function.hits += hits.parse::<u64>().unwrap_or_default();
+                }
+                ("DA", [line, hits]) => {
+                    let hits = hits.parse::<u64>().unwrap_or_default();
+                    let line = file
+                        .lines
+                        .entry(line.parse().unwrap_or_default())
+                        .or_default();
+                    line.hits += hits;
+                    if hits > 0 && !test_name.is_empty() {
+                        line.tests.insert(test_name.clone());
+                    }
+                }
+                ("BRDA", [line, block, branch, hits]) => {
+                    let key = (
+                        line.parse().unwrap_or_default(),
+                        block.to_string(),
+                        branch.to_string(),
+                    );
+                    *file.branches.entry(key).or_default() += hits.parse::<u64>().unwrap_or(0);
+                }
+                _ => {}
+            }
+        }
+        summary
+    }
+
+    pub fn build_cobertura_content(&self, timestamp: u64) -> String {
+        let (lines_hit, lines_total, branches_hit, branches_total) =
+            self.files
+                .values()
+                .fold((0, 0, 0, 0), |(lh, lt, bh, bt), file| {
+                    (
+                        lh + file.lines_hit(),
+                        lt + file.lines.len(),
+                        bh + file.branches_hit(),
+                        bt + file.branches.len(),
+                    )
+                });
+
+        let mut xml = String::new();
+        let _ = writeln!(xml, r#"<?xml version="1.0" ?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        );
+        let _ = writeln!(
+            xml,
+            r#"<coverage line-rate="{}" branch-rate="{}" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}" complexity="0" version="0.1" timestamp="{}">"#,
+            rate(lines_hit, lines_total),
+            rate(branches_hit, branches_total),
+            lines_hit,
+            lines_total,
+            branches_hit,
+            branches_total,
+            timestamp
+        );
+        let _ = writeln!(xml, "  <sources><source>.</source></sources>");
+        let _ = writeln!(xml, "  <packages>");
+        let _ = writeln!(
+            xml,
+            r#"    <package name="contracts" line-rate="{}" branch-rate="{}" complexity="0">"#,
+            rate(lines_hit, lines_total),
+            rate(branches_hit, branches_total),
+        );
+        let _ = writeln!(xml, "      <classes>");
+        for (path, file) in self.files.iter() {
+            let _ = writeln!(
+                xml,
+                r#"        <class name="{}" filename="{}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+                escape(&file_stem(path)),
+                escape(path),
+                rate(file.lines_hit(), file.lines.len()),
+                rate(file.branches_hit(), file.branches.len()),
+            );
+            let _ = writeln!(xml, "          <methods>");
+            for (name, function) in file.functions.iter() {
+                let hit = usize::from(function.hits > 0);
+                let _ = writeln!(
+                    xml,
+                    r#"            <method name="{}" signature="" line-rate="{}" branch-rate="{}" complexity="0">"#,
+                    escape(name),
+                    rate(hit, 1),
+                    rate(hit, 1),
+                );
+                let _ = writeln!(
+                    xml,
+                    r#"              <lines><line number="{}" hits="{}"/></lines>"#,
+                    function.line, function.hits
+                );
+                let _ = writeln!(xml, "            </method>");
+            }
+            let _ = writeln!(xml, "          </methods>");
+            let _ = writeln!(xml, "          <lines>");
+            for (number, line) in file.lines.iter() {
+                match file.line_branches(*number) {
+                    (_, 0) => {
+                        let _ = writeln!(
+                            xml,
+                            r#"            <line number="{}" hits="{}" branch="false"/>"#,
+                            number, line.hits
+                        );
+                    }
+                    (hit, total) => {
+                        let _ = writeln!(
+                            xml,
+                            r#"            <line number="{}" hits="{}" branch="true" condition-coverage="{}% ({}/{})"/>"#,
+                            number,
+                            line.hits,
+                            hit * 100 / total,
+                            hit,
+                            total
+                        );
+                    }
+                }
+            }
+            let _ = writeln!(xml, "          </lines>");
+            let _ = writeln!(xml, "        </class>");
+        }
+        let _ = writeln!(xml, "      </classes>");
+        let _ = writeln!(xml, "    </package>");
+        let _ = writeln!(xml, "  </packages>");
+        let _ = writeln!(xml, "</coverage>");
+        xml
+    }
+
+    /// Single page report, with the annotated source of each contract.
+    /// `sources` maps the files paths to their content.
+    pub fn build_html_content(&self, sources: &BTreeMap<String, String>) -> String {
+        let mut html = String::new();
+        let _ = writeln!(html, "<!DOCTYPE html>");
+        let _ = writeln!(
+            html,
+            r#"<html><head><meta charset="utf-8"><title>Clarity coverage</title><style>{}</style></head><body>"#,
+            HTML_STYLE
+        );
+        let _ = writeln!(html, "<h1>Clarity coverage</h1>");
+
+        let _ = writeln!(
+            html,
+            "<table class=\"summary\"><tr><th>File</th><th>Lines</th><th>Functions</th><th>Branches</th></tr>"
+        );
+        for (i, (path, file)) in self.files.iter().enumerate() {
+            let _ = writeln!(
+                html,
+                r##"<tr><td><a href="#file-{}">{}</a></td><td>{}</td><td>{}</td><td>{}</td></tr>"##,
+                i,
+                escape(path),
+                ratio(file.lines_hit(), file.lines.len()),
+                ratio(file.functions_hit(), file.functions.len()),
+                ratio(file.branches_hit(), file.branches.len()),
+            );
+        }
+        let _ = writeln!(html, "</table>");
+
+        for (i, (path, file)) in self.files.iter().enumerate() {
+            let _ = writeln!(html, r#"<h2 id="file-{}">{}</h2>"#, i, escape(path));
+
+            let _ = writeln!(
+                html,
+                "<table class=\"functions\"><tr><th>Function</th><th>Line</th><th>Hits</th></tr>"
+            );
+            for (name, function) in file.functions.iter() {
+                let _ = writeln!(
+                    html,
+                    r#"<tr class="{}"><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+                    if function.hits > 0 { "hit" } else { "miss" },
+                    escape(name),
+                    function.line,
+                    function.hits
+                );
+            }
+            let _ = writeln!(html, "</table>");
+
+            let Some(source) = sources.get(path) else {
+                let _ = writeln!(html, "<p>source unavailable</p>");
+                continue;
+            };
+            let _ = writeln!(html, "<table class=\"source\">");
+            for (n, code) in source.lines().enumerate() {
+                let number = n as u32 + 1;
+                let (class, hits, title) = match file.lines.get(&number) {
+                    Some(line) if line.hits > 0 => (
+                        "hit",
+                        line.hits.to_string(),
+                        line.tests.iter().cloned().collect::<Vec<_>>().join(", "),
+                    ),
+                    Some(_) => ("miss", "0".to_string(), String::new()),
+                    None => ("", String::new(), String::new()),
+                };
+                let branches = match file.line_branches(number) {
+                    (_, 0) => String::new(),
+                    (hit, total) => format!("{}/{}", hit, total),
+                };
+                let _ = writeln!(
+                    html,
+                    r#"<tr class="{}" title="{}"><td class="ln">{}</td><td class="hits">{}</td><td class="br">{}</td><td><pre>{}</pre></td></tr>"#,
+                    class,
+                    escape(&title),
+                    number,
+                    hits,
+                    branches,
+                    escape(code)
+                );
+            }
+            let _ = writeln!(html, "</table>");
+        }
+        let _ = writeln!(html, "</body></html>");
+        html
+    }
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse;margin-bottom:1em}\
+th,td{padding:0 .5em;text-align:left}\
+.summary td,.functions td{border-top:1px solid #ddd}\
+.source pre{margin:0}\
+.source .ln,.source .hits,.source .br{color:#888;text-align:right}\
+tr.hit{background:#e6ffed}\
+tr.miss{background:#ffeef0}";
+
+fn rate(hit: usize, total: usize) -> String {
+    if total == 0 {
+        return "1".to_string();
+    }
+    format!("{:.4}", hit as f64 / total as f64)
+}
+
+fn ratio(hit: usize, total: usize) -> String {
+    if total == 0 {
+        return "-".to_string();
+    }
+    format!("{}/{} ({}%)", hit, total, hit * 100 / total)
+}
+
+fn file_stem(path: &str) -> String {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    file_name
+        .strip_suffix(".clar")
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LCOV: &str = "TN:test-a
+SF:/contracts/counter.clar
+FN:1,increment
+FNDA:1,increment
+FNF:1
+FNH:1
+DA:1,1
+DA:2,1
+DA:3,0
+BRF:2
+BRH:1
+BRDA:2,7,0,1
+BRDA:2,7,1,0
+end_of_record
+TN:test-b
+SF:/contracts/counter.clar
+FN:1,increment
+FNDA:1,increment
+FNF:1
+FNH:1
+DA:1,1
+DA:2,1
+DA:3,1
+BRF:2
+BRH:1
+BRDA:2,7,0,0
+BRDA:2,7,1,1
+end_of_record
+";
+
+    #[test]
+    fn test_merge_lcov_records() {
+        let summary = CoverageSummary::from_lcov(LCOV);
+        let file = summary.files.get("/contracts/counter.clar").unwrap();
+
+        assert_eq!(file.functions.get("increment").unwrap().hits, 2);
+        assert_eq!(file.lines.get(&2).unwrap().hits, 2);
+        let line_3 = file.lines.get(&3).unwrap();
+        assert_eq!(line_3.hits, 1);
+        assert_eq!(line_3.tests, BTreeSet::from(["test-b".to_string()]));
+        assert_eq!(file.branches_hit(), 2);
+    }
+
+    #[test]
+    fn test_cobertura_and_html_output() {
+        let summary = CoverageSummary::from_lcov(LCOV);
+
+        let xml = summary.build_cobertura_content(0);
+        assert!(xml.contains(r#"<class name="counter" filename="/contracts/counter.clar""#));
+        assert!(xml.contains(
+            r#"<line number="2" hits="2" branch="true" condition-coverage="100% (2/2)"/>"#
+        ));
+
+        let sources = BTreeMap::from([(
+            "/contracts/counter.clar".to_string(),
+            "(define-public (increment)\n  (if (> 1 0) (ok 1) (err u1)))\n".to_string(),
+        )]);
+        let html = summary.build_html_content(&sources);
+        assert!(html.contains("(ok 1)"));
+        assert!(html.contains("&gt; 1 0"));
+    }
+}
@@ -4,8 +4,11 @@ pub mod ast_visitor;
 pub mod call_checker;
 pub mod check_checker;
 pub mod coverage;
+pub mod coverage_formats;
 #[cfg(test)]
 mod coverage_tests;
+pub mod trait_checker;
+pub mod trait_registry;
 
 use serde::Serialize;
 
@@ -16,6 +19,7 @@ use clarity::vm::diagnostic::Diagnostic;
 
 use self::call_checker::CallChecker;
 use self::check_checker::CheckChecker;
+use self::trait_checker::TraitChecker;
 
 pub type AnalysisResult = Result<Vec<Diagnostic>, Vec<Diagnostic>>;
 
@@ -24,12 +28,14 @@ pub type AnalysisResult = Result<Vec<Diagnostic>, Vec<Diagnostic>>;
 pub enum Pass {
     All,
     CheckChecker,
+    TraitChecker,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct Settings {
     passes: Vec<Pass>,
     check_checker: check_checker::Settings,
+    trait_checker: trait_checker::Settings,
 }
 
 impl Settings {
@@ -44,6 +50,7 @@ impl Settings {
                     self.passes = ALL_PASSES.to_vec();
                     return;
                 }
+                pass if self.passes.contains(&pass) => (),
                 pass => self.passes.push(pass),
             };
         }
@@ -63,10 +70,11 @@ pub enum OneOrList<T> {
 pub struct SettingsFile {
     passes: Option<OneOrList<Pass>>,
     check_checker: Option<check_checker::SettingsFile>,
+    trait_checker: Option<trait_checker::SettingsFile>,
 }
 
 // Each new pass should be included in this list
-static ALL_PASSES: [Pass; 1] = [Pass::CheckChecker];
+static ALL_PASSES: [Pass; 2] = [Pass::CheckChecker, Pass::TraitChecker];
 
 impl From<SettingsFile> for Settings {
     fn from(from_file: SettingsFile) -> Self {
@@ -94,10 +102,16 @@ impl From<SettingsFile> for Settings {
         } else {
             check_checker::Settings::default()
         };
+        let trait_checker_settings = if let Some(trait_checker_settings) = from_file.trait_checker {
+            trait_checker::Settings::from(trait_checker_settings)
+        } else {
+            trait_checker::Settings::default()
+        };
 
         Self {
             passes,
             check_checker: checker_settings,
+            trait_checker: trait_checker_settings,
         }
     }
 }
@@ -130,6 +144,7 @@ pub fn run_analysis(
     for pass in &settings.passes {
         match pass {
             Pass::CheckChecker => passes.push(CheckChecker::run_pass),
+            Pass::TraitChecker => passes.push(TraitChecker::run_pass),
             Pass::All => panic!("unexpected All in list of passes"),
         }
     }
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use crate::analysis::annotation::Annotation;
+use crate::analysis::trait_registry::{StandardTrait, STANDARD_TRAITS};
+use crate::analysis::{self, AnalysisPass, AnalysisResult};
+
+use clarity::vm::analysis::analysis_db::AnalysisDatabase;
+use clarity::vm::analysis::types::ContractAnalysis;
+use clarity::vm::diagnostic::{Diagnostic, Level};
+use clarity::vm::functions::define::DefineFunctions;
+use clarity::vm::representations::{Span, SymbolicExpression};
+use clarity::vm::types::{FunctionSignature, FunctionType};
+use clarity::vm::ClarityName;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    // Ids of the standard traits to check (e.g. `sip-010`), all of them if empty
+    traits: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SettingsFile {
+    // Ids of the standard traits to check (e.g. `sip-010`), all of them if empty
+    traits: Option<Vec<String>>,
+}
+
+impl From<SettingsFile> for Settings {
+    fn from(from_file: SettingsFile) -> Self {
+        Settings {
+            traits: from_file.traits.unwrap_or_default(),
+        }
+    }
+}
+
+/// Checks the conformance of contracts to the standard traits (SIP-009, SIP-010,
+/// SIP-013) they almost fully define without declaring `impl-trait`, since Clarity
+/// only checks the conformance to the traits declared with `impl-trait`.
+pub struct TraitChecker<'a> {
+    contract_analysis: &'a ContractAnalysis,
+    settings: &'a Settings,
+    // Span of the definition of each function
+    function_spans: HashMap<&'a ClarityName, Span>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn format_signature(signature: &FunctionSignature) -> String {
+    let args = signature
+        .args
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>();
+    format!("({}) -> {}", args.join(" "), signature.returns)
+}
+
+impl<'a> TraitChecker<'a> {
+    fn new(contract_analysis: &'a ContractAnalysis, settings: &'a Settings) -> TraitChecker<'a> {
+        let mut function_spans = HashMap::new();
+        for expr in contract_analysis.expressions.iter() {
+            if let Some((name, span)) = get_function_definition(expr) {
+                function_spans.insert(name, span);
+            }
+        }
+        Self {
+            contract_analysis,
+            settings,
+            function_spans,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> AnalysisResult {
+        let settings = self.settings;
+        for standard_trait in STANDARD_TRAITS.iter().filter(|standard_trait| {
+            settings.traits.is_empty() || settings.traits.iter().any(|id| id == standard_trait.id)
+        }) {
+            self.check_trait(standard_trait);
+        }
+        Ok(self.diagnostics)
+    }
+
+    fn get_function_type(&self, name: &ClarityName) -> Option<&'a FunctionType> {
+        self.contract_analysis
+            .public_function_types
+            .get(name)
+            .or_else(|| self.contract_analysis.read_only_function_types.get(name))
+    }
+
+    fn check_trait(&mut self, standard_trait: &StandardTrait) {
+        let is_implemented = self
+            .contract_analysis
+            .implemented_traits
+            .iter()
+            .any(|trait_id| trait_id.name.as_str() == standard_trait.name);
+        if is_implemented {
+            return;
+        }
+
+        // Contracts defining at least 3/4 of the functions of a trait are meant to implement it
+        let functions = standard_trait.functions();
+        let defined = functions
+            .keys()
+            .filter(|name| self.function_spans.contains_key(name))
+            .count();
+        if defined * 4 < functions.len() * 3 {
+            return;
+        }
+
+        for (name, signature) in functions.iter() {
+            let problem = match self.get_function_type(name) {
+                Some(FunctionType::Fixed(function)) => {
+                    let epoch = &self.contract_analysis.epoch;
+                    let args_conform = function.args.len() == signature.args.len()
+                        && function.args.iter().zip(signature.args.iter()).all(
+                            |(arg, expected)| {
+                                arg.signature.admits_type(epoch, expected).unwrap_or(false)
+                            },
+                        );
+                    let returns_conform = signature
+                        .returns
+                        .admits_type(epoch, &function.returns)
+                        .unwrap_or(false);
+                    if args_conform && returns_conform {
+                        continue;
+                    }
+                    format!(
+                        "function '{}' does not conform to the {} trait (expected {})",
+                        name,
+                        standard_trait.description,
+                        format_signature(signature)
+                    )
+                }
+                Some(_) => continue,
+                None if self.function_spans.contains_key(name) => format!(
+                    "function '{}' of the {} trait must be public or read-only",
+                    name, standard_trait.description
+                ),
+                None => format!(
+                    "function '{}' of the {} trait is missing",
+                    name, standard_trait.description
+                ),
+            };
+            let spans = match self.function_spans.get(name) {
+                Some(span) => vec![span.clone()],
+                None => vec![],
+            };
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                message: problem,
+                spans,
+                suggestion: Some(format!(
+                    "declare (impl-trait '{}) to have the conformance checked at deployment",
+                    standard_trait.trait_id()
+                )),
+            });
+        }
+    }
+}
+
+/// Name and span of a function definition
+fn get_function_definition(expr: &SymbolicExpression) -> Option<(&ClarityName, Span)> {
+    let list = expr.match_list()?;
+    let define = DefineFunctions::lookup_by_name(list.first()?.match_atom()?)?;
+    match define {
+        DefineFunctions::PublicFunction
+        | DefineFunctions::ReadOnlyFunction
+        | DefineFunctions::PrivateFunction => {
+            let name = list.get(1)?.match_list()?.first()?.match_atom()?;
+            Some((name, expr.span.clone()))
+        }
+        _ => None,
+    }
+}
+
+impl AnalysisPass for TraitChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        _analysis_db: &mut AnalysisDatabase,
+        _annotations: &Vec<Annotation>,
+        settings: &analysis::Settings,
+    ) -> AnalysisResult {
+        let checker = TraitChecker::new(contract_analysis, &settings.trait_checker);
+        checker.run()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::Pass;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    fn trait_checker_session(traits: Vec<String>) -> Session {
+        let mut settings = SessionSettings::default();
+        settings.repl_settings.analysis.passes = vec![Pass::TraitChecker];
+        settings.repl_settings.analysis.trait_checker.traits = traits;
+        Session::new(settings)
+    }
+
+    const NON_CONFORMING_TOKEN: &str = "
+(define-fungible-token token)
+(define-public (transfer (amount uint) (sender principal) (recipient principal))
+    (begin
+        (asserts! (is-eq tx-sender sender) (err u1))
+        (ft-transfer? token amount sender recipient)
+    )
+)
+(define-read-only (get-name) (ok \"Token\"))
+(define-read-only (get-symbol) (ok \"TKN\"))
+(define-read-only (get-decimals) (ok u6))
+(define-read-only (get-balance (who principal)) (ok (ft-get-balance token who)))
+(define-read-only (get-total-supply) (ok (ft-get-supply token)))
+";
+
+    #[test]
+    fn conforming_token_without_impl_trait() {
+        let mut session = trait_checker_session(vec![]);
+        let snippet = "
+(define-fungible-token token)
+(define-public (transfer (amount uint) (sender principal) (recipient principal) (memo (optional (buff 34))))
+    (begin
+        (asserts! (is-eq tx-sender sender) (err u1))
+        (ft-transfer? token amount sender recipient)
+    )
+)
+(define-read-only (get-name) (ok \"Token\"))
+(define-read-only (get-symbol) (ok \"TKN\"))
+(define-read-only (get-decimals) (ok u6))
+(define-read-only (get-balance (who principal)) (ok (ft-get-balance token who)))
+(define-read-only (get-total-supply) (ok (ft-get-supply token)))
+(define-read-only (get-token-uri) (ok none))
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn non_conforming_token() {
+        let mut session = trait_checker_session(vec![]);
+        let snippet = NON_CONFORMING_TOKEN.to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                let messages = result
+                    .diagnostics
+                    .iter()
+                    .map(|diagnostic| diagnostic.message.as_str())
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    messages,
+                    vec![
+                        "function 'get-token-uri' of the SIP-010 fungible token trait is missing",
+                        "function 'transfer' does not conform to the SIP-010 fungible token trait (expected (uint principal principal (optional (buff 34))) -> (response bool uint))",
+                    ]
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn checks_enabled_traits_only() {
+        let mut session = trait_checker_session(vec!["sip-009".to_string()]);
+        let snippet = NON_CONFORMING_TOKEN.to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = NON_CONFORMING_TOKEN.to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,143 @@
+use crate::repl::DEFAULT_EPOCH;
+use clarity::vm::ast::build_ast_with_diagnostics;
+use clarity::vm::types::{FunctionSignature, QualifiedContractIdentifier, TypeSignature};
+use clarity::vm::{ClarityName, ClarityVersion};
+use std::collections::{BTreeMap, HashMap};
+
+/// A trait standardized by a SIP, and deployed on mainnet
+pub struct StandardTrait {
+    /// Short name, e.g. `sip-010`
+    pub id: &'static str,
+    pub description: &'static str,
+    /// Contract defining the trait on mainnet
+    pub contract_id: &'static str,
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+pub static STANDARD_TRAITS: [StandardTrait; 3] = [
+    StandardTrait {
+        id: "sip-009",
+        description: "SIP-009 non-fungible token",
+        contract_id: "SP2PABAF9FTAJYNFZH93XENAJ8FVY99RRM50D2JG9.nft-trait",
+        name: "nft-trait",
+        source: r#"(define-trait nft-trait
+  (
+    (get-last-token-id () (response uint uint))
+    (get-token-uri (uint) (response (optional (string-ascii 256)) uint))
+    (get-owner (uint) (response (optional principal) uint))
+    (transfer (uint principal principal) (response bool uint))
+  )
+)"#,
+    },
+    StandardTrait {
+        id: "sip-010",
+        description: "SIP-010 fungible token",
+        contract_id: "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.sip-010-trait-ft-standard",
+        name: "sip-010-trait",
+        source: r#"(define-trait sip-010-trait
+  (
+    (transfer (uint principal principal (optional (buff 34))) (response bool uint))
+    (get-name () (response (string-ascii 32) uint))
+    (get-symbol () (response (string-ascii 32) uint))
+    (get-decimals () (response uint uint))
+    (get-balance (principal) (response uint uint))
+    (get-total-supply () (response uint uint))
+    (get-token-uri () (response (optional (string-utf8 256)) uint))
+  )
+)"#,
+    },
+    StandardTrait {
+        id: "sip-013",
+        description: "SIP-013 semi-fungible token",
+        contract_id: "SPDBEG5X8XD50SPM1JJH0E5CTXGDV5NJTKAKKR5V.sip013-semi-fungible-token-trait",
+        name: "sip013-semi-fungible-token-trait",
+        source: r#"(define-trait sip013-semi-fungible-token-trait
+  (
+    (get-balance (uint principal) (response uint uint))
+    (get-overall-balance (principal) (response uint uint))
+    (get-total-supply (uint) (response uint uint))
+    (get-overall-supply () (response uint uint))
+    (get-decimals (uint) (response uint uint))
+    (get-token-uri (uint) (response (optional (string-ascii 256)) uint))
+    (transfer (uint uint principal principal) (response bool uint))
+    (transfer-memo (uint uint principal principal (buff 34)) (response bool uint))
+  )
+)"#,
+    },
+];
+
+lazy_static! {
+    // The trait definitions are parsed once, they are looked up for every analyzed contract
+    static ref STANDARD_TRAITS_FUNCTIONS:
+        HashMap<&'static str, BTreeMap<ClarityName, FunctionSignature>> =
+        STANDARD_TRAITS
+            .iter()
+            .map(|standard_trait| (standard_trait.id, standard_trait.parse_functions()))
+            .collect();
+}
+
+pub fn get_standard_trait(id: &str) -> Option<&'static StandardTrait> {
+    STANDARD_TRAITS
+        .iter()
+        .find(|standard_trait| standard_trait.id == id)
+}
+
+impl StandardTrait {
+    /// Identifier of the trait, as used in `impl-trait`
+    pub fn trait_id(&self) -> String {
+        format!("{}.{}", self.contract_id, self.name)
+    }
+
+    /// Signatures of the functions of the trait
+    pub fn functions(&self) -> &'static BTreeMap<ClarityName, FunctionSignature> {
+        &STANDARD_TRAITS_FUNCTIONS[self.id]
+    }
+
+    fn parse_functions(&self) -> BTreeMap<ClarityName, FunctionSignature> {
+        let contract_id = QualifiedContractIdentifier::parse(self.contract_id)
+            .expect("invalid standard trait contract id");
+        let clarity_version = ClarityVersion::default_for_epoch(DEFAULT_EPOCH);
+        let (ast, _, success) = build_ast_with_diagnostics(
+            &contract_id,
+            self.source,
+            &mut (),
+            clarity_version,
+            DEFAULT_EPOCH,
+        );
+        let definition = match (success, ast.expressions.first()) {
+            (true, Some(expr)) => expr.match_list(),
+            _ => None,
+        };
+        // (define-trait <name> <functions>)
+        match definition {
+            Some(definition) if definition.len() > 2 => TypeSignature::parse_trait_type_repr(
+                &definition[2..],
+                &mut (),
+                DEFAULT_EPOCH,
+                clarity_version,
+            )
+            .expect("invalid standard trait definition"),
+            _ => panic!("invalid standard trait definition"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_standard_traits() {
+        for standard_trait in STANDARD_TRAITS.iter() {
+            assert!(!standard_trait.functions().is_empty());
+        }
+        let sip_010 = get_standard_trait("sip-010").unwrap();
+        let transfer = &sip_010.functions()[&ClarityName::from("transfer")];
+        assert_eq!(transfer.args.len(), 4);
+        assert_eq!(
+            sip_010.trait_id(),
+            "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.sip-010-trait-ft-standard.sip-010-trait"
+        );
+    }
+}
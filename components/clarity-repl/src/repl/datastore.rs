@@ -63,9 +63,12 @@ pub struct BurnDatastore {
     open_chain_tip: StacksBlockId,
     current_chain_tip: StacksBlockId,
     chain_height: u32,
+    burn_chain_height: u32,
     height_at_chain_tip: HashMap<StacksBlockId, u32>,
     constants: StacksConstants,
     genesis_time: u64,
+    /// PoX payouts of the next burn blocks
+    pox_payout_addrs: (Vec<TupleData>, u128),
 }
 
 fn height_to_hashed_bytes(height: u32) -> [u8; 32] {
@@ -80,8 +83,20 @@ fn height_to_id(height: u32) -> StacksBlockId {
     StacksBlockId(height_to_hashed_bytes(height))
 }
 
-fn height_to_block(height: u32, genesis_time: Option<u64>) -> BlockInfo {
+fn burn_height_to_header_hash(burn_height: u32) -> BurnchainHeaderHash {
+    if burn_height == 0 {
+        return BurnchainHeaderHash([0x00; 32]);
+    }
+    let mut buffer = height_to_hashed_bytes(burn_height);
+    buffer[0] = 2;
+    BurnchainHeaderHash(buffer)
+}
+
+/// The block header hash is specific to the Stacks block, while the burnchain
+/// data is shared by every Stacks block of a tenure (the same burn block)
+fn height_to_block(height: u32, burn_height: u32, genesis_time: Option<u64>) -> BlockInfo {
     let bytes = height_to_hashed_bytes(height);
+    let burn_bytes = height_to_hashed_bytes(burn_height);
     let genesis_time = genesis_time.unwrap_or(0);
 
     let block_header_hash = {
@@ -89,24 +104,20 @@ fn height_to_block(height: u32, genesis_time: Option<u64>) -> BlockInfo {
         buffer[0] = 1;
         BlockHeaderHash(buffer)
     };
-    let burn_block_header_hash = {
-        let mut buffer = bytes;
-        buffer[0] = 2;
-        BurnchainHeaderHash(buffer)
-    };
+    let burn_block_header_hash = burn_height_to_header_hash(burn_height);
     let consensus_hash = {
-        let mut buffer = bytes;
+        let mut buffer = burn_bytes;
         buffer[0] = 3;
         ConsensusHash::from_bytes(&buffer[0..20]).unwrap()
     };
     let vrf_seed = {
-        let mut buffer = bytes;
+        let mut buffer = burn_bytes;
         buffer[0] = 4;
         VRFSeed(buffer)
     };
-    let time_since_genesis: u64 = (height * 1800).into();
+    let time_since_genesis: u64 = (burn_height * 1800).into();
     let burn_block_time: u64 = genesis_time + time_since_genesis;
-    let burn_block_height = height;
+    let burn_block_height = burn_height;
     let miner = StacksAddress::burn_address(true);
     let burnchain_tokens_spent_for_block = 2000;
     let get_burnchain_tokens_spent_for_winning_block = 2000;
@@ -178,6 +189,46 @@ impl Datastore {
         self.current_chain_tip = self.open_chain_tip;
         self.chain_height
     }
+
+    /// Move the chain tip back to `height`, dropping the state of the blocks mined after it
+    pub fn rewind(&mut self, height: u32) -> std::result::Result<u32, String> {
+        if height > self.chain_height {
+            return Err(format!(
+                "height {} is above the chain tip ({})",
+                height, self.chain_height
+            ));
+        }
+        for dropped_height in (height + 1)..=self.chain_height {
+            let id = height_to_id(dropped_height);
+            self.store.remove(&id);
+            self.block_id_lookup.remove(&id);
+            self.height_at_chain_tip.remove(&id);
+        }
+        self.chain_height = height;
+        self.open_chain_tip = height_to_id(height);
+        self.current_chain_tip = self.open_chain_tip;
+
+        // metadata isn't versioned, drop the one of the contracts deployed after `height`
+        let deployed = self
+            .block_id_lookup
+            .get(&self.open_chain_tip)
+            .and_then(|id| self.store.get(id))
+            .cloned()
+            .unwrap_or_default();
+        self.metadata.retain(|(contract, _), _| {
+            deployed.contains_key(&format!("clarity-contract::{}", contract))
+        });
+        Ok(height)
+    }
+
+    pub fn has_contract(&self, contract: &QualifiedContractIdentifier) -> bool {
+        self.block_id_lookup
+            .get(&self.open_chain_tip)
+            .and_then(|id| self.store.get(id))
+            .map_or(false, |store| {
+                store.contains_key(&Self::make_contract_hash_key(contract))
+            })
+    }
 }
 
 impl ClarityBackingStore for Datastore {
@@ -348,37 +399,85 @@ impl BurnDatastore {
             open_chain_tip: id,
             current_chain_tip: id,
             chain_height: 0,
+            burn_chain_height: 0,
             height_at_chain_tip,
             constants,
             genesis_time,
+            pox_payout_addrs: (vec![], 0),
         }
     }
 
+    /// Mine new burn blocks, each of them anchoring a new Stacks block
+    /// (one block per tenure, as before epoch 3.0)
     pub fn advance_chain_tip(&mut self, count: u32) {
-        let cur_height = self.chain_height;
+        for _ in 0..count {
+            self.burn_chain_height += 1;
+            self.append_stacks_block();
+        }
+    }
+
+    /// Mine Stacks blocks in the current tenure, the burn chain tip doesn't move
+    pub fn extend_tenure(&mut self, count: u32) {
+        for _ in 0..count {
+            self.append_stacks_block();
+        }
+    }
+
+    pub fn get_burn_chain_height(&self) -> u32 {
+        self.burn_chain_height
+    }
+
+    /// Move the chain tip back to the Stacks block at `height`, along with its burn block
+    pub fn rewind(&mut self, height: u32) {
+        for dropped_height in (height + 1)..=self.chain_height {
+            let id = height_to_id(dropped_height);
+            self.store.remove(&id);
+            self.block_id_lookup.remove(&id);
+            self.height_at_chain_tip.remove(&id);
+        }
+        let store = &self.store;
+        self.sortition_lookup.retain(|_, id| store.contains_key(id));
+        let sortition_lookup = &self.sortition_lookup;
+        self.consensus_hash_lookup
+            .retain(|_, sortition_id| sortition_lookup.contains_key(sortition_id));
+
+        self.chain_height = height;
+        self.open_chain_tip = height_to_id(height);
+        self.current_chain_tip = self.open_chain_tip;
+        self.burn_chain_height = self
+            .store
+            .get(&self.open_chain_tip)
+            .map_or(0, |block_info| block_info.burn_block_height);
+    }
+
+    pub fn set_pox_payout_addrs(&mut self, pox_payout_addrs: (Vec<TupleData>, u128)) {
+        self.pox_payout_addrs = pox_payout_addrs;
+    }
+
+    fn append_stacks_block(&mut self) {
         let current_lookup_id = *self
             .block_id_lookup
             .get(&self.open_chain_tip)
             .expect("Open chain tip missing in block id lookup table");
-        let genesis_time = self.genesis_time;
-
-        for i in 1..=count {
-            let height = cur_height + i;
-            let bytes = height_to_hashed_bytes(height);
-            let id = StacksBlockId(bytes);
-            let sortition_id = SortitionId(bytes);
-            let block_info = height_to_block(height, Some(genesis_time));
-            self.block_id_lookup.insert(id, current_lookup_id);
-            self.height_at_chain_tip.insert(id, height);
-            self.sortition_lookup.insert(sortition_id, id);
-            self.consensus_hash_lookup
-                .insert(block_info.consensus_hash, sortition_id);
-            self.store.insert(id, block_info);
-        }
 
-        self.chain_height += count;
-        self.open_chain_tip = height_to_id(self.chain_height);
-        self.current_chain_tip = self.open_chain_tip;
+        let height = self.chain_height + 1;
+        let id = height_to_id(height);
+        let sortition_id = SortitionId(height_to_hashed_bytes(self.burn_chain_height));
+        let mut block_info =
+            height_to_block(height, self.burn_chain_height, Some(self.genesis_time));
+        block_info.pox_payout_addrs = self.pox_payout_addrs.clone();
+        self.block_id_lookup.insert(id, current_lookup_id);
+        self.height_at_chain_tip.insert(id, height);
+        // the sortition points to the first Stacks block of its tenure
+        self.sortition_lookup.entry(sortition_id).or_insert(id);
+        self.consensus_hash_lookup
+            .entry(block_info.consensus_hash)
+            .or_insert(sortition_id);
+        self.store.insert(id, block_info);
+
+        self.chain_height = height;
+        self.open_chain_tip = id;
+        self.current_chain_tip = id;
     }
 }
 
@@ -491,13 +590,14 @@ impl BurnStateDB for BurnDatastore {
     /// Returns Some if `self.get_burn_start_height() <= height < self.get_burn_block_height(sorition_id)`, and None otherwise.
     fn get_burn_header_hash(
         &self,
-        _height: u32,
+        height: u32,
         sortition_id: &SortitionId,
     ) -> Option<BurnchainHeaderHash> {
-        self.sortition_lookup
-            .get(sortition_id)
-            .and_then(|id| self.store.get(id))
-            .map(|block_info| block_info.burn_block_header_hash)
+        let sortition_height = self.get_burn_block_height(sortition_id)?;
+        if height > sortition_height {
+            return None;
+        }
+        Some(burn_height_to_header_hash(height))
     }
 
     /// Lookup a `SortitionId` keyed to a `ConsensusHash`.
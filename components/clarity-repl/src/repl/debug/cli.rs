@@ -1,4 +1,4 @@
-use crate::repl::debug::extract_watch_variable;
+use crate::repl::debug::{check_watch_key, extract_watch_variable};
 use clarity::vm::contexts::{Environment, LocalContext};
 use clarity::vm::errors::Error;
 use clarity::vm::representations::Span;
@@ -9,7 +9,7 @@ use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
 
 use super::{
-    AccessType, Breakpoint, BreakpointData, DebugState, FunctionBreakpoint, Source,
+    AccessType, Breakpoint, BreakpointData, DebugState, FunctionBreakpoint, HitCondition, Source,
     SourceBreakpoint, State,
 };
 
@@ -169,15 +169,15 @@ impl CLIDebugger {
                 false
             }
             "w" | "watch" => {
-                self.watch_command(args, env, AccessType::Write);
+                self.watch_command(args, env, context, AccessType::Write);
                 false
             }
             "rw" | "rwatch" => {
-                self.watch_command(args, env, AccessType::Read);
+                self.watch_command(args, env, context, AccessType::Read);
                 false
             }
             "aw" | "awatch" => {
-                self.watch_command(args, env, AccessType::ReadWrite);
+                self.watch_command(args, env, context, AccessType::ReadWrite);
                 false
             }
             "p" | "print" => {
@@ -247,18 +247,17 @@ impl CLIDebugger {
                 }
             }
             _ => {
-                if arg_list.len() != 1 {
-                    println!("{}", format_err!("invalid break command"));
-                    print_help_breakpoint();
-                    return;
-                }
+                let (location, options) = match args.split_once(char::is_whitespace) {
+                    Some((location, options)) => (location, options.trim()),
+                    None => (args, ""),
+                };
 
-                if args.contains(':') {
+                if location.contains(':') {
                     // Handle source breakpoints
                     // - contract:line:column
                     // - contract:line
                     // - :line
-                    let parts: Vec<&str> = args.split(':').collect();
+                    let parts: Vec<&str> = location.split(':').collect();
                     if parts.len() < 2 || parts.len() > 3 {
                         println!("{}", format_err!("invalid breakpoint format"));
                         print_help_breakpoint();
@@ -317,11 +316,24 @@ impl CLIDebugger {
                         0
                     };
 
+                    let (condition, hit_condition, log_message) =
+                        match parse_breakpoint_options(options) {
+                            Ok(options) => options,
+                            Err(e) => {
+                                println!("{}", format_err!(e));
+                                print_help_breakpoint();
+                                return;
+                            }
+                        };
+
                     self.state.add_breakpoint(Breakpoint {
                         id: 0,
                         data: BreakpointData::Source(SourceBreakpoint {
                             line,
                             column: if column == 0 { None } else { Some(column) },
+                            condition,
+                            hit_condition,
+                            log_message,
                         }),
                         source: Source { name: contract_id },
                         span: Some(Span {
@@ -336,6 +348,11 @@ impl CLIDebugger {
                     // - principal.contract.function
                     // - .contract.function
                     // - function
+                    if !options.is_empty() {
+                        println!("{}", format_err!("invalid break command"));
+                        print_help_breakpoint();
+                        return;
+                    }
                     let parts: Vec<&str> = args.split('.').collect();
                     let (contract_id, function_name) = match parts.len() {
                         1 => (env.contract_context.contract_identifier.clone(), parts[0]),
@@ -398,7 +415,13 @@ impl CLIDebugger {
         }
     }
 
-    fn watch_command(&mut self, args: &str, env: &mut Environment, access_type: AccessType) {
+    fn watch_command(
+        &mut self,
+        args: &str,
+        env: &mut Environment,
+        context: &LocalContext,
+        access_type: AccessType,
+    ) {
         if args.is_empty() {
             println!("{}", format_err!("invalid watch command"));
             print_help_watchpoint();
@@ -440,23 +463,44 @@ impl CLIDebugger {
                 }
             }
             _ => {
-                if arg_list.len() != 1 {
-                    println!("{}", format_err!("invalid watch command"));
-                    print_help_watchpoint();
-                    return;
-                }
+                // The variable can be followed by a map key or a token owner
+                let (variable, key) = match args.split_once(char::is_whitespace) {
+                    Some((variable, key)) => (variable, Some(key.trim())),
+                    None => (args, None),
+                };
 
-                match extract_watch_variable(env, args, None) {
-                    Ok((contract, name)) => self.state.add_watchpoint(
-                        &contract.contract_context.contract_identifier,
-                        name,
-                        access_type,
-                    ),
+                let (contract, name) = match extract_watch_variable(env, variable, None) {
+                    Ok(variable) => variable,
                     Err(e) => {
                         println!("{}", format_err!(e));
                         print_help_watchpoint();
+                        return;
                     }
                 };
+                let key = match key {
+                    Some(key) => match self.state.evaluate(env, context, key) {
+                        Ok(key) => {
+                            if let Err(e) = check_watch_key(&contract, name, &key) {
+                                println!("{}", format_err!(e));
+                                return;
+                            }
+                            Some(key)
+                        }
+                        Err(errors) => {
+                            for error in errors {
+                                println!("{}", error);
+                            }
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                self.state.add_watchpoint(
+                    &contract.contract_context.contract_identifier,
+                    name,
+                    key,
+                    access_type,
+                );
             }
         }
     }
@@ -469,19 +513,14 @@ impl EvalHook for CLIDebugger {
         context: &LocalContext,
         expr: &SymbolicExpression,
     ) {
-        if !self.state.will_begin_eval(env, context, expr) {
+        let proceed = self.state.will_begin_eval(env, context, expr);
+        for message in self.state.take_log_messages() {
+            println!("{}", message);
+        }
+        if !proceed {
             match self.state.state {
                 State::Break(id) => println!("{} hit breakpoint {}", black!("*"), id),
-                State::DataBreak(id, access_type) => println!(
-                    "{} hit watchpoint {} ({})",
-                    black!("*"),
-                    id,
-                    if access_type == AccessType::Read {
-                        "read"
-                    } else {
-                        "write"
-                    }
-                ),
+                State::DataBreak(id, access_type) => print_watchpoint_hit(id, access_type),
                 _ => (),
             }
             self.print_source(env, expr);
@@ -506,6 +545,12 @@ impl EvalHook for CLIDebugger {
                 Err(e) => println!("{}", format_err!(e)),
             }
         }
+        // A watchpoint scoped to a key is hit once the key is evaluated
+        if let State::DataBreak(id, access_type) = self.state.state {
+            print_watchpoint_hit(id, access_type);
+            self.print_source(env, expr);
+            self.prompt(env, context, expr);
+        }
     }
 
     fn did_complete(
@@ -515,6 +560,102 @@ impl EvalHook for CLIDebugger {
     }
 }
 
+fn print_watchpoint_hit(id: usize, access_type: AccessType) {
+    println!(
+        "{} hit watchpoint {} ({})",
+        black!("*"),
+        id,
+        if access_type == AccessType::Read {
+            "read"
+        } else {
+            "write"
+        }
+    );
+}
+
+type BreakpointOptions = (Option<String>, Option<HitCondition>, Option<String>);
+
+const BREAKPOINT_OPTIONS: [&str; 3] = ["if", "hits", "log"];
+
+// Parse the options following the location of a source breakpoint:
+// `if <condition>`, `hits <hit-condition>` and `log <message>`, which must be last.
+// The keywords are only looked for outside of parentheses and string literals, and
+// the values are taken as written: the log message is the rest of the command line.
+fn parse_breakpoint_options(options: &str) -> Result<BreakpointOptions, String> {
+    let options = options.trim();
+
+    // the keywords found, with the position of the keyword and of its value
+    let mut keywords: Vec<(&str, usize, usize)> = vec![];
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut previous = ' ';
+    for (i, c) in options.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ if depth == 0 && previous.is_whitespace() => {
+                    let keyword = BREAKPOINT_OPTIONS.into_iter().find(|keyword| {
+                        options[i..].strip_prefix(keyword).map_or(false, |rest| {
+                            rest.is_empty() || rest.starts_with(char::is_whitespace)
+                        })
+                    });
+                    if let Some(keyword) = keyword {
+                        keywords.push((keyword, i, i + keyword.len()));
+                        if keyword == "log" {
+                            break;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        previous = c;
+    }
+
+    if let Some(token) = options.split_whitespace().next() {
+        if keywords.first().map_or(true, |(_, start, _)| *start > 0) {
+            return Err(format!("unexpected breakpoint option: {}", token));
+        }
+    }
+
+    let mut condition = None;
+    let mut hit_condition = None;
+    let mut log_message = None;
+    for (i, (keyword, _, value_start)) in keywords.iter().enumerate() {
+        let value_end = keywords
+            .get(i + 1)
+            .map_or(options.len(), |(_, start, _)| *start);
+        let value = options[*value_start..value_end].trim();
+        if value.is_empty() {
+            return Err("invalid breakpoint options".to_string());
+        }
+        let option = match *keyword {
+            "if" => &mut condition,
+            "hits" => &mut hit_condition,
+            _ => &mut log_message,
+        };
+        if option.replace(value.to_string()).is_some() {
+            return Err(format!("duplicate breakpoint option: {}", keyword));
+        }
+    }
+
+    let hit_condition = match hit_condition {
+        Some(hit_condition) => Some(HitCondition::parse(&hit_condition)?),
+        None => None,
+    };
+    Ok((condition, hit_condition, log_message))
+}
+
 fn print_help(args: &str) {
     match args {
         "b" | "breakpoint" => print_help_breakpoint(),
@@ -562,6 +703,17 @@ fn print_help_breakpoint() {
     :12
         Break at line 12 of the current contract
 
+  b <location> if <condition> hits <hit-condition> log <message>
+    :12 if (> amount u100)
+        Break at line 12 of the current contract when amount is over 100
+    :12 hits >= 3
+        Break at line 12 from the third hit onwards, hit conditions are
+          <n>, == <n>, > <n>, >= <n>, < <n>, <= <n> or % <n>
+    :12 log transfer of {amount}
+        Print a message at line 12 instead of stopping, expressions
+          between braces are evaluated
+    Options can be combined, log must be last
+
   b <principal>.<contract>.<function>
     SP000000000000000000002Q6VF78.bns.name-preorder
         Break at the function name-preorder from the bns contract deployed by
@@ -605,6 +757,12 @@ fn print_help_watchpoint() {
   w <name>
     something
         Watch the variable 'something' from the current contract
+  w <map> <key>
+    .foo.owners { id: u1 }
+        Break on writes to the key { id: u1 } of the map 'owners'
+  w <token> <principal>
+    .foo.token tx-sender
+        Break on changes to the 'token' balance of the sender
 
 Default watchpoints break when the variable or map is written. Using the same
 formats, the command 'rwatch' sets a read watchpoint to break when the variable
@@ -625,3 +783,180 @@ Delete all watchpoints
 "#
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repl::{Session, SessionSettings};
+    use clarity::vm::ExecutionResult;
+
+    #[test]
+    fn test_parse_breakpoint_options() {
+        assert_eq!(parse_breakpoint_options(""), Ok((None, None, None)));
+        assert_eq!(
+            parse_breakpoint_options("if (> (len names) u2) hits % 2 log {names} is too long"),
+            Ok((
+                Some("(> (len names) u2)".to_string()),
+                Some(HitCondition::Multiple(2)),
+                Some("{names} is too long".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_breakpoint_options("if (is-eq mode \"log\")"),
+            Ok((Some("(is-eq mode \"log\")".to_string()), None, None))
+        );
+        assert!(parse_breakpoint_options("hits").is_err());
+        assert!(parse_breakpoint_options("if").is_err());
+        assert!(parse_breakpoint_options("foo").is_err());
+        assert!(parse_breakpoint_options("hits 2 hits 3").is_err());
+
+        // the values are taken as written
+        assert_eq!(
+            parse_breakpoint_options(r#"if (is-eq name "a  (b")  log name:  {name}"#),
+            Ok((
+                Some(r#"(is-eq name "a  (b")"#.to_string()),
+                None,
+                Some("name:  {name}".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_breakpoint_options(r#"if (is-eq name "\" hits ")"#),
+            Ok((Some(r#"(is-eq name "\" hits ")"#.to_string()), None, None))
+        );
+    }
+
+    const NAMES: &str = r#"(define-data-var names (list 10 (string-ascii 10)) (list))
+(define-private (add-name (name (string-ascii 10)))
+    (var-set names (unwrap-panic (as-max-len? (append (var-get names) name) u10))))
+(add-name "alice")
+(add-name "b  b")
+(add-name "carol")
+(add-name "dave")
+"#;
+
+    /// Resume right away, recording the value of `name` at each break
+    struct BreaksRecorder {
+        state: DebugState,
+        breakpoint: Option<Breakpoint>,
+        breaks: Vec<Value>,
+    }
+
+    impl EvalHook for BreaksRecorder {
+        fn will_begin_eval(
+            &mut self,
+            env: &mut Environment,
+            context: &LocalContext,
+            expr: &SymbolicExpression,
+        ) {
+            if let Some(mut breakpoint) = self.breakpoint.take() {
+                breakpoint.source.name = env.contract_context.contract_identifier.clone();
+                self.state.add_breakpoint(breakpoint);
+            }
+            if !self.state.will_begin_eval(env, context, expr) {
+                if let State::Break(_) = self.state.state {
+                    self.breaks
+                        .push(context.lookup_variable("name").unwrap().clone());
+                }
+                self.state.state = State::Continue;
+            }
+        }
+
+        fn did_finish_eval(
+            &mut self,
+            env: &mut Environment,
+            context: &LocalContext,
+            expr: &SymbolicExpression,
+            res: &Result<Value, Error>,
+        ) {
+            self.state.did_finish_eval(env, context, expr, res);
+        }
+
+        fn did_complete(&mut self, _result: Result<&mut ExecutionResult, String>) {}
+    }
+
+    // Run `NAMES` with a breakpoint in `add-name`, set with the `options` of the
+    // break command, returns the names the execution stopped on and the logs
+    fn run_with_breakpoint(options: &str) -> (Vec<Value>, Vec<String>) {
+        let (condition, hit_condition, log_message) = parse_breakpoint_options(options).unwrap();
+        let contract_id = QualifiedContractIdentifier::transient();
+        let mut recorder = BreaksRecorder {
+            state: DebugState::new(&contract_id, ""),
+            breakpoint: Some(Breakpoint {
+                id: 0,
+                data: BreakpointData::Source(SourceBreakpoint {
+                    line: 3,
+                    column: None,
+                    condition,
+                    hit_condition,
+                    log_message,
+                }),
+                source: Source { name: contract_id },
+                span: Some(Span {
+                    start_line: 3,
+                    start_column: 0,
+                    end_line: 3,
+                    end_column: 0,
+                }),
+            }),
+            breaks: vec![],
+        };
+        recorder.state.state = State::Continue;
+
+        let mut session = Session::new(SessionSettings::default());
+        session
+            .eval(NAMES.to_string(), Some(vec![&mut recorder]), false)
+            .unwrap();
+        let logs = recorder.state.take_log_messages();
+        (recorder.breaks, logs)
+    }
+
+    fn ascii(name: &str) -> Value {
+        Value::string_ascii_from_bytes(name.as_bytes().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_breakpoint_condition() {
+        let (breaks, logs) = run_with_breakpoint(r#"if (is-eq name "b  b")"#);
+        assert_eq!(breaks, vec![ascii("b  b")]);
+        assert!(logs.is_empty());
+
+        // conditions which can't be evaluated are logged, and don't break
+        let (breaks, logs) = run_with_breakpoint("if (is-eq unknown u1)");
+        assert!(breaks.is_empty());
+        assert_eq!(logs.len(), 4);
+        assert!(logs[0].starts_with("breakpoint 1: unable to evaluate condition"));
+    }
+
+    #[test]
+    fn test_breakpoint_hit_condition() {
+        let (breaks, _) = run_with_breakpoint("hits % 2");
+        assert_eq!(breaks, vec![ascii("b  b"), ascii("dave")]);
+
+        let (breaks, _) = run_with_breakpoint("hits > 2");
+        assert_eq!(breaks, vec![ascii("carol"), ascii("dave")]);
+
+        // hits are only counted when the condition holds
+        let (breaks, _) = run_with_breakpoint(r#"if (not (is-eq name "alice")) hits == 2"#);
+        assert_eq!(breaks, vec![ascii("carol")]);
+    }
+
+    #[test]
+    fn test_logpoint() {
+        let (breaks, logs) =
+            run_with_breakpoint("hits <= 2 log adding {name}  to {(len (var-get names))} names");
+        assert!(breaks.is_empty());
+        assert_eq!(
+            logs,
+            vec![
+                r#"adding "alice"  to u0 names"#,
+                r#"adding "b  b"  to u1 names"#
+            ]
+        );
+
+        // unbalanced braces and failing expressions are logged as they are
+        let (_, logs) = run_with_breakpoint("hits == 1 log {unknown} {name");
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].starts_with('<'));
+        assert!(logs[0].ends_with(" {name"));
+    }
+}
@@ -1,9 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use crate::repl::DEFAULT_EPOCH;
 
-use super::{extract_watch_variable, AccessType, State};
+use super::{check_watch_key, extract_watch_variable, AccessType, ExceptionFilter, State};
 use clarity::vm::callables::FunctionIdentifier;
 use clarity::vm::contexts::{ContractContext, GlobalContext};
 use clarity::vm::errors::Error;
@@ -12,7 +13,7 @@ use clarity::vm::types::{PrincipalData, SequenceData, StandardPrincipalData, Val
 use clarity::vm::{
     contexts::{Environment, LocalContext},
     types::QualifiedContractIdentifier,
-    EvalHook, SymbolicExpression,
+    EvalHook, SymbolicExpression, SymbolicExpressionType,
 };
 use clarity::vm::{EvaluationResult, ExecutionResult};
 use debug_types::events::*;
@@ -21,6 +22,7 @@ use debug_types::responses::*;
 use debug_types::types::*;
 use debug_types::*;
 use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
 use tokio;
 use tokio::io::{Stdin, Stdout};
 use tokio::runtime::Runtime;
@@ -52,24 +54,237 @@ pub mod codec;
  *        |<-- threads response -------|
  */
 
+/// Contract call debugged by a launch request. The arguments are Clarity
+/// expressions, the sender defaults to the deployer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ContractCallLaunch {
+    pub contract: String,
+    pub method: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub sender: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchTarget {
+    /// A single expression, evaluated after the default deployment
+    Expression(String),
+    /// A public or read-only function call, after the default deployment
+    ContractCall(ContractCallLaunch),
+    /// Each step of a deployment plan: `default` or a path relative to the project
+    DeploymentPlan(String),
+    /// A console script, replayed line by line after the default deployment
+    Script(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchConfiguration {
+    pub manifest: String,
+    pub target: LaunchTarget,
+}
+
+/// Arguments of the launch request, as specified in the launch configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchArguments {
+    pub manifest: Option<String>,
+    pub expression: Option<String>,
+    pub contract_call: Option<ContractCallLaunch>,
+    pub deployment_plan: Option<String>,
+    pub script: Option<String>,
+}
+
+impl LaunchArguments {
+    pub fn into_configuration(self) -> Result<LaunchConfiguration, String> {
+        let manifest = self
+            .manifest
+            .ok_or("manifest must be specified".to_string())?;
+        let mut targets = vec![];
+        if let Some(expression) = self.expression {
+            targets.push(LaunchTarget::Expression(expression));
+        }
+        if let Some(contract_call) = self.contract_call {
+            targets.push(LaunchTarget::ContractCall(contract_call));
+        }
+        if let Some(deployment_plan) = self.deployment_plan {
+            targets.push(LaunchTarget::DeploymentPlan(deployment_plan));
+        }
+        if let Some(script) = self.script {
+            targets.push(LaunchTarget::Script(script));
+        }
+        if targets.len() != 1 {
+            return Err(
+                "exactly one of expression, contractCall, deploymentPlan or script must be specified"
+                    .to_string(),
+            );
+        }
+        Ok(LaunchConfiguration {
+            manifest,
+            target: targets.remove(0),
+        })
+    }
+}
+
 struct Current {
     expr_id: u64,
     stack: Vec<FunctionIdentifier>,
 }
 
+/// Maximum number of expressions recorded to step back through an execution
+const MAX_TRACE_LENGTH: usize = 10_000;
+
+/// State of the debugger when an expression began its evaluation
+struct Snapshot {
+    contract_id: QualifiedContractIdentifier,
+    span: Span,
+    depth: usize,
+    stack_frames: Vec<StackFrame>,
+    /// Scopes and variables of each stack frame, by frame id
+    frames: HashMap<i32, Rc<FrameState>>,
+}
+
+impl Snapshot {
+    fn scopes(&self, frame_id: i32) -> Option<&Vec<Scope>> {
+        self.frames.get(&frame_id).map(|frame| &frame.scopes)
+    }
+
+    fn variables(&self, variables_reference: i32) -> Option<&Vec<Variable>> {
+        self.frames
+            .values()
+            .find_map(|frame| frame.variables.get(&variables_reference))
+    }
+}
+
+/// Scopes and variables of a stack frame. Only the frame at the top of the stack
+/// is updated while it runs: the snapshots share the state of the frames below it.
+struct FrameState {
+    scopes: Vec<Scope>,
+    variables: HashMap<i32, Vec<Variable>>,
+}
+
+/// Execution trace of the current run, and the position in it when stepping back
+struct Trace {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+    /// `None` while following the live execution
+    position: Option<usize>,
+}
+
+impl Trace {
+    fn new(capacity: usize) -> Self {
+        Self {
+            snapshots: VecDeque::new(),
+            capacity,
+            position: None,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.snapshots.clear();
+        self.position = None;
+    }
+
+    fn is_replaying(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// Snapshot at the current position, when stepping back
+    fn current(&self) -> Option<&Snapshot> {
+        self.position.and_then(|index| self.snapshots.get(index))
+    }
+
+    fn last(&self) -> Option<&Snapshot> {
+        self.snapshots.back()
+    }
+
+    /// Record a snapshot, dropping the oldest one when the trace is full
+    fn push(&mut self, snapshot: Snapshot) {
+        if self.snapshots.len() >= self.capacity {
+            self.snapshots.pop_front();
+            self.position = self.position.map(|index| index.saturating_sub(1));
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Move through the recorded trace and return the index reached, `None` if
+    /// nothing was recorded. Reaching the last snapshot returns to the live execution.
+    fn move_to(
+        &mut self,
+        replay_move: ReplayMove,
+        has_breakpoint: impl Fn(&Snapshot) -> bool,
+    ) -> Option<usize> {
+        let last = self.snapshots.len().checked_sub(1)?;
+        let from = self.position.unwrap_or(last).min(last);
+        let depth = self.snapshots[from].depth;
+
+        let mut forward = (from + 1)..=last;
+        let target = match replay_move {
+            ReplayMove::StepIn => Some((from + 1).min(last)),
+            ReplayMove::Next => forward.find(|i| self.snapshots[*i].depth <= depth),
+            ReplayMove::StepOut => forward.find(|i| self.snapshots[*i].depth < depth),
+            ReplayMove::Continue => forward.find(|i| has_breakpoint(&self.snapshots[*i])),
+            ReplayMove::StepBack => Some(from.saturating_sub(1)),
+            ReplayMove::ReverseContinue => (0..from)
+                .rev()
+                .find(|i| has_breakpoint(&self.snapshots[*i])),
+        };
+        let target = target.unwrap_or(match replay_move {
+            ReplayMove::StepBack | ReplayMove::ReverseContinue => 0,
+            _ => last,
+        });
+        self.position = if target == last { None } else { Some(target) };
+        Some(target)
+    }
+}
+
+/// Contract data a data breakpoint can be set on: a variable, a map or a
+/// token, possibly scoped to a map key or a token owner
+#[derive(Clone)]
+struct DataTarget {
+    contract_id: QualifiedContractIdentifier,
+    name: String,
+    key: Option<Value>,
+}
+
+impl std::fmt::Display for DataTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "{}.{} {}", self.contract_id, self.name, key),
+            None => write!(f, "{}.{}", self.contract_id, self.name),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ReplayMove {
+    StepIn,
+    Next,
+    StepOut,
+    Continue,
+    StepBack,
+    ReverseContinue,
+}
+
 pub struct DAPDebugger {
     rt: Runtime,
     default_sender: Option<StandardPrincipalData>,
     pub path_to_contract_id: HashMap<PathBuf, QualifiedContractIdentifier>,
     pub contract_id_to_path: HashMap<QualifiedContractIdentifier, PathBuf>,
-    reader: FramedRead<Stdin, DebugAdapterCodec<ProtocolMessage>>,
+    reader: FramedRead<Stdin, DebugAdapterCodec<serde_json::Value>>,
     writer: FramedWrite<Stdout, DebugAdapterCodec<ProtocolMessage>>,
     state: Option<DebugState>,
     send_seq: i64,
-    launched: Option<(String, String)>,
+    launched: Option<LaunchConfiguration>,
     launch_seq: i64,
     current: Option<Current>,
     init_complete: bool,
+    trace: Trace,
+    /// Data targets of the variables shown, by variables reference and name
+    variable_data_targets: HashMap<(i32, String), DataTarget>,
+    /// Data targets returned by `dataBreakpointInfo`, by data id
+    data_targets: HashMap<String, DataTarget>,
+    /// Watchpoints set by `setDataBreakpoints`
+    data_breakpoints: Vec<usize>,
 
     stack_frames: HashMap<FunctionIdentifier, StackFrame>,
     scopes: HashMap<i32, Vec<Scope>>,
@@ -87,7 +302,7 @@ impl DAPDebugger {
         let stdin = tokio::io::stdin();
         let stdout = tokio::io::stdout();
 
-        let reader = FramedRead::new(stdin, DebugAdapterCodec::<ProtocolMessage>::default());
+        let reader = FramedRead::new(stdin, DebugAdapterCodec::<serde_json::Value>::default());
         let writer = FramedWrite::new(stdout, DebugAdapterCodec::<ProtocolMessage>::default());
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -106,6 +321,10 @@ impl DAPDebugger {
             launch_seq: 0,
             current: None,
             init_complete: false,
+            trace: Trace::new(MAX_TRACE_LENGTH),
+            variable_data_targets: HashMap::new(),
+            data_targets: HashMap::new(),
+            data_breakpoints: Vec::new(),
             stack_frames: HashMap::new(),
             scopes: HashMap::new(),
             variables: HashMap::new(),
@@ -117,7 +336,7 @@ impl DAPDebugger {
     }
 
     // Process all messages before launching the REPL
-    pub fn init(&mut self) -> Result<(String, String), ParseError> {
+    pub fn init(&mut self) -> Result<LaunchConfiguration, ParseError> {
         while self.launched.is_none() {
             match self.wait_for_command(None, None) {
                 Ok(_) => (),
@@ -135,9 +354,19 @@ impl DAPDebugger {
     ) -> Result<bool, ParseError> {
         if let Some(msg) = self.rt.block_on(self.reader.next()) {
             match msg {
-                Ok(msg) => {
+                Ok(raw) => {
                     use debug_types::MessageKind::*;
+                    let msg: ProtocolMessage = serde_json::from_value(raw.clone())?;
                     Ok(match msg.message {
+                        // the launch configuration has fields unknown to `LaunchRequestArguments`
+                        Request(RequestCommand::Launch(_)) => {
+                            let arguments = raw
+                                .get("arguments")
+                                .cloned()
+                                .map(serde_json::from_value)
+                                .unwrap_or(Ok(LaunchArguments::default()))?;
+                            self.launch(msg.seq, arguments)
+                        }
                         Request(command) => self.handle_request(env, context, msg.seq, command),
                         Response(response) => {
                             self.handle_response(msg.seq, response);
@@ -190,6 +419,29 @@ impl DAPDebugger {
         self.send_seq += 1;
     }
 
+    fn wait_until_resumed(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr_id: u64,
+        stack: Vec<FunctionIdentifier>,
+    ) {
+        // Save the current state, which may be needed to respond to incoming requests
+        self.current = Some(Current { expr_id, stack });
+
+        let mut proceed = false;
+        while !proceed {
+            proceed = match self.wait_for_command(Some(env), Some(context)) {
+                Ok(proceed) => proceed,
+                Err(e) => {
+                    self.log(format!("error: {}", e));
+                    false
+                }
+            };
+        }
+        self.current = None;
+    }
+
     pub fn handle_request(
         &mut self,
         env: Option<&mut Environment>,
@@ -201,10 +453,19 @@ impl DAPDebugger {
 
         match command {
             Initialize(arguments) => self.initialize(seq, arguments),
-            Launch(arguments) => self.launch(seq, arguments),
+            Launch(arguments) => self.launch(
+                seq,
+                LaunchArguments {
+                    manifest: arguments.manifest,
+                    expression: arguments.expression,
+                    ..Default::default()
+                },
+            ),
             ConfigurationDone => self.configuration_done(seq),
             SetBreakpoints(arguments) => self.set_breakpoints(seq, arguments),
             SetExceptionBreakpoints(arguments) => self.set_exception_breakpoints(seq, arguments),
+            DataBreakpointInfo(arguments) => self.data_breakpoint_info(seq, arguments),
+            SetDataBreakpoints(arguments) => self.set_data_breakpoints(seq, arguments),
             Disconnect(arguments) => self.quit(seq, arguments),
             Threads => self.threads(seq),
             StackTrace(arguments) => self.stack_trace(seq, arguments),
@@ -214,6 +475,12 @@ impl DAPDebugger {
             StepOut(arguments) => self.step_out(seq, arguments),
             Next(arguments) => self.next(seq, arguments),
             Continue(arguments) => self.continue_(seq, arguments),
+            StepBack(_) => self.replay(seq, ReplayMove::StepBack, ResponseBody::StepBack),
+            ReverseContinue(_) => self.replay(
+                seq,
+                ReplayMove::ReverseContinue,
+                ResponseBody::ReverseContinue,
+            ),
             Pause(arguments) => self.pause(seq, arguments),
             Evaluate(arguments) => self.evaluate(seq, arguments, env, context),
             _ => {
@@ -259,11 +526,23 @@ impl DAPDebugger {
             supports_loaded_sources_request: Some(true),
             supports_data_breakpoints: Some(true),
             supports_breakpoint_locations_request: Some(true),
-            supports_conditional_breakpoints: None,
-            supports_hit_conditional_breakpoints: None,
+            supports_conditional_breakpoints: Some(true),
+            supports_hit_conditional_breakpoints: Some(true),
             supports_evaluate_for_hovers: None,
-            exception_breakpoint_filters: None,
-            supports_step_back: None,
+            exception_breakpoint_filters: Some(
+                ExceptionFilter::ALL
+                    .iter()
+                    .map(|filter| ExceptionBreakpointsFilter {
+                        filter: filter.id().to_string(),
+                        label: filter.label().to_string(),
+                        description: None,
+                        default: Some(false),
+                        supports_condition: None,
+                        condition_description: None,
+                    })
+                    .collect(),
+            ),
+            supports_step_back: Some(true),
             supports_set_variable: None,
             supports_restart_frame: None,
             supports_goto_targets_request: None,
@@ -278,7 +557,7 @@ impl DAPDebugger {
             supports_exception_info_request: None,
             support_suspend_debuggee: None,
             supports_delayed_stack_trace_loading: None,
-            supports_log_points: None,
+            supports_log_points: Some(true),
             supports_terminate_threads_request: None,
             supports_set_expression: None,
             supports_terminate_request: None,
@@ -344,27 +623,15 @@ impl DAPDebugger {
         }));
     }
 
-    fn launch(&mut self, seq: i64, arguments: LaunchRequestArguments) -> bool {
-        // Verify that the manifest and expression were specified
-        let manifest = match arguments.manifest {
-            Some(manifest) => manifest,
-            None => {
-                self.send_response(Response {
-                    request_seq: seq,
-                    success: false,
-                    message: Some("manifest must be specified".to_string()),
-                    body: None,
-                });
-                return false;
-            }
-        };
-        let expression = match arguments.expression {
-            Some(expression) => expression,
-            None => {
+    fn launch(&mut self, seq: i64, arguments: LaunchArguments) -> bool {
+        // Verify that the manifest and the target to debug were specified
+        let configuration = match arguments.into_configuration() {
+            Ok(configuration) => configuration,
+            Err(message) => {
                 self.send_response(Response {
                     request_seq: seq,
                     success: false,
-                    message: Some("expression to debug must be specified".to_string()),
+                    message: Some(message),
                     body: None,
                 });
                 return false;
@@ -372,8 +639,12 @@ impl DAPDebugger {
         };
 
         let contract_id = QualifiedContractIdentifier::transient();
-        self.state = Some(DebugState::new(&contract_id, &expression));
-        self.launched = Some((manifest, expression));
+        let snippet = match &configuration.target {
+            LaunchTarget::Expression(expression) => expression.as_str(),
+            _ => "",
+        };
+        self.state = Some(DebugState::new(&contract_id, snippet));
+        self.launched = Some(configuration);
 
         self.launch_seq = seq;
 
@@ -426,11 +697,37 @@ impl DAPDebugger {
             };
             for breakpoint in breakpoints {
                 let column = breakpoint.column.unwrap_or(0);
+                let hit_condition = match breakpoint
+                    .hit_condition
+                    .as_deref()
+                    .map(super::HitCondition::parse)
+                    .transpose()
+                {
+                    Ok(hit_condition) => hit_condition,
+                    Err(message) => {
+                        results.push(Breakpoint {
+                            id: None,
+                            verified: false,
+                            message: Some(message),
+                            source: Some(arguments.source.clone()),
+                            line: Some(breakpoint.line),
+                            column: breakpoint.column,
+                            end_line: None,
+                            end_column: None,
+                            instruction_reference: None,
+                            offset: None,
+                        });
+                        continue;
+                    }
+                };
                 let source_breakpoint = super::Breakpoint {
                     id: 0,
                     data: super::BreakpointData::Source(super::SourceBreakpoint {
                         line: breakpoint.line,
                         column: breakpoint.column,
+                        condition: breakpoint.condition.clone(),
+                        hit_condition,
+                        log_message: breakpoint.log_message.clone(),
                     }),
                     source: source.clone(),
                     span: Some(Span {
@@ -444,7 +741,7 @@ impl DAPDebugger {
                 results.push(Breakpoint {
                     id: Some(id),
                     verified: true,
-                    message: breakpoint.log_message,
+                    message: None,
                     source: Some(arguments.source.clone()),
                     line: Some(breakpoint.line),
                     column: breakpoint.column,
@@ -471,8 +768,15 @@ impl DAPDebugger {
     fn set_exception_breakpoints(
         &mut self,
         seq: i64,
-        _arguments: SetExceptionBreakpointsArguments,
+        arguments: SetExceptionBreakpointsArguments,
     ) -> bool {
+        let filters = arguments
+            .filters
+            .iter()
+            .filter_map(|filter| ExceptionFilter::from_id(filter))
+            .collect();
+        self.get_state().set_exception_filters(filters);
+
         self.send_response(Response {
             request_seq: seq,
             success: true,
@@ -485,6 +789,108 @@ impl DAPDebugger {
         false
     }
 
+    fn data_breakpoint_info(&mut self, seq: i64, arguments: DataBreakpointInfoArguments) -> bool {
+        let target = arguments.variables_reference.and_then(|reference| {
+            self.variable_data_targets
+                .get(&(reference, arguments.name.clone()))
+                .cloned()
+        });
+        let body = match target {
+            Some(target) => {
+                let data_id = target.to_string();
+                self.data_targets.insert(data_id.clone(), target);
+                DataBreakpointInfoResponse {
+                    data_id: Some(data_id.clone()),
+                    description: data_id,
+                    access_types: Some(vec![
+                        DataBreakpointAccessType::Read,
+                        DataBreakpointAccessType::Write,
+                        DataBreakpointAccessType::ReadWrite,
+                    ]),
+                    can_persist: Some(false),
+                }
+            }
+            None => DataBreakpointInfoResponse {
+                data_id: None,
+                description: format!("no data breakpoint can be set on {}", arguments.name),
+                access_types: None,
+                can_persist: None,
+            },
+        };
+        self.send_response(Response {
+            request_seq: seq,
+            success: true,
+            message: None,
+            body: Some(ResponseBody::DataBreakpointInfo(body)),
+        });
+        false
+    }
+
+    fn set_data_breakpoints(&mut self, seq: i64, arguments: SetDataBreakpointsArguments) -> bool {
+        // The data breakpoints replace the ones previously set
+        for id in std::mem::take(&mut self.data_breakpoints) {
+            self.get_state().delete_watchpoint(id);
+        }
+
+        let mut results = vec![];
+        for breakpoint in arguments.breakpoints {
+            let target = match self.data_targets.get(&breakpoint.data_id) {
+                Some(target) => target.clone(),
+                None => {
+                    results.push(Breakpoint {
+                        id: None,
+                        verified: false,
+                        message: Some(format!("unknown data id: {}", breakpoint.data_id)),
+                        source: None,
+                        line: None,
+                        column: None,
+                        end_line: None,
+                        end_column: None,
+                        instruction_reference: None,
+                        offset: None,
+                    });
+                    continue;
+                }
+            };
+            let access_type = match breakpoint.access_type {
+                Some(DataBreakpointAccessType::Read) => AccessType::Read,
+                Some(DataBreakpointAccessType::ReadWrite) => AccessType::ReadWrite,
+                Some(DataBreakpointAccessType::Write) | None => AccessType::Write,
+            };
+            let id = self.get_state().add_watchpoint(
+                &target.contract_id,
+                &target.name,
+                target.key,
+                access_type,
+            );
+            self.data_breakpoints.push(id);
+            results.push(Breakpoint {
+                id: Some(id),
+                verified: true,
+                message: None,
+                source: None,
+                line: None,
+                column: None,
+                end_line: None,
+                end_column: None,
+                instruction_reference: None,
+                offset: None,
+            });
+        }
+
+        self.send_response(Response {
+            request_seq: seq,
+            success: true,
+            message: None,
+            body: Some(ResponseBody::SetDataBreakpoints(
+                SetDataBreakpointsResponse {
+                    breakpoints: results,
+                },
+            )),
+        });
+        false
+    }
+
     fn threads(&mut self, seq: i64) -> bool {
         // There is only ever 1 thread
         self.send_response(Response {
@@ -528,6 +934,21 @@ impl DAPDebugger {
     }
 
     fn stack_trace(&mut self, seq: i64, _arguments: StackTraceArguments) -> bool {
+        if let Some(snapshot) = self.trace.current() {
+            let frames = snapshot.stack_frames.clone();
+            let len = frames.len() as i32;
+            self.send_response(Response {
+                request_seq: seq,
+                success: true,
+                message: None,
+                body: Some(ResponseBody::StackTrace(StackTraceResponse {
+                    stack_frames: frames,
+                    total_frames: Some(len),
+                })),
+            });
+            return false;
+        }
+
         let current = self.current.as_ref().unwrap();
         let frames: Vec<_> = current
             .stack
@@ -551,19 +972,27 @@ impl DAPDebugger {
     }
 
     fn scopes(&mut self, seq: i64, arguments: ScopesArguments) -> bool {
+        let scopes = match self.trace.current() {
+            Some(snapshot) => snapshot.scopes(arguments.frame_id),
+            None => self.scopes.get(&arguments.frame_id),
+        };
         self.send_response(Response {
             request_seq: seq,
             success: true,
             message: None,
             body: Some(ResponseBody::Scopes(ScopesResponse {
-                scopes: self.scopes[&arguments.frame_id].clone(),
+                scopes: scopes.cloned().unwrap_or_default(),
             })),
         });
         false
     }
 
     fn variables(&mut self, seq: i64, arguments: VariablesArguments) -> bool {
-        let variables = match self.variables.get(&arguments.variables_reference) {
+        let variables = match self.trace.current() {
+            Some(snapshot) => snapshot.variables(arguments.variables_reference),
+            None => self.variables.get(&arguments.variables_reference),
+        };
+        let variables = match variables {
             Some(variables) => variables.clone(),
             None => {
                 self.log("unknown variable reference");
@@ -581,6 +1010,9 @@ impl DAPDebugger {
     }
 
     fn step_in(&mut self, seq: i64, _arguments: StepInArguments) -> bool {
+        if self.trace.is_replaying() {
+            return self.replay(seq, ReplayMove::StepIn, ResponseBody::StepIn);
+        }
         self.get_state().step_in();
 
         self.send_response(Response {
@@ -593,6 +1025,9 @@ impl DAPDebugger {
     }
 
     fn step_out(&mut self, seq: i64, _arguments: StepOutArguments) -> bool {
+        if self.trace.is_replaying() {
+            return self.replay(seq, ReplayMove::StepOut, ResponseBody::StepOut);
+        }
         self.get_state().finish();
 
         self.send_response(Response {
@@ -605,6 +1040,9 @@ impl DAPDebugger {
     }
 
     fn next(&mut self, seq: i64, _arguments: NextArguments) -> bool {
+        if self.trace.is_replaying() {
+            return self.replay(seq, ReplayMove::Next, ResponseBody::Next);
+        }
         let expr_id = self.current.as_ref().unwrap().expr_id;
         self.get_state().step_over(expr_id);
 
@@ -618,6 +1056,12 @@ impl DAPDebugger {
     }
 
     fn continue_(&mut self, seq: i64, _arguments: ContinueArguments) -> bool {
+        if self.trace.is_replaying() {
+            let body = ResponseBody::Continue(ContinueResponse {
+                all_threads_continued: None,
+            });
+            return self.replay(seq, ReplayMove::Continue, body);
+        }
         self.get_state().continue_execution();
 
         self.send_response(Response {
@@ -650,6 +1094,15 @@ impl DAPDebugger {
         env: Option<&mut Environment>,
         context: Option<&LocalContext>,
     ) -> bool {
+        if self.trace.is_replaying() {
+            self.send_response(Response {
+                request_seq: seq,
+                success: false,
+                message: Some("expressions can not be evaluated while stepping back".to_string()),
+                body: None,
+            });
+            return false;
+        }
         let (env, context) = match (env, context) {
             (Some(env), Some(context)) => (env, context),
             _ => {
@@ -685,8 +1138,12 @@ impl DAPDebugger {
                             false
                         };
                         if !exists {
-                            self.get_state()
-                                .add_watchpoint(contract_id, name, AccessType::Write);
+                            self.get_state().add_watchpoint(
+                                contract_id,
+                                name,
+                                None,
+                                AccessType::Write,
+                            );
                         }
 
                         if let Some(data_types) = contract.contract_context.meta_data_var.get(name)
@@ -743,6 +1200,33 @@ impl DAPDebugger {
                                     memory_reference: None,
                                 })),
                             }
+                        } else if contract.contract_context.meta_ft.get(name).is_some() {
+                            let supply = env
+                                .global_context
+                                .database
+                                .get_ft_supply(contract_id, name)
+                                .unwrap();
+                            Response {
+                                request_seq: seq,
+                                success: true,
+                                message: None,
+                                body: Some(ResponseBody::Evaluate(EvaluateResponse {
+                                    result: format!("u{}", supply),
+                                    result_type: Some("ft".to_string()),
+                                    presentation_hint: Some(VariablePresentationHint {
+                                        kind: Some(VariableKind::Data),
+                                        attributes: Some(vec![
+                                            VariableAttribute::HasDataBreakpoint,
+                                        ]),
+                                        visibility: None,
+                                        lazy: None,
+                                    }),
+                                    variables_reference: 0,
+                                    named_variables: None,
+                                    indexed_variables: None,
+                                    memory_reference: None,
+                                })),
+                            }
                         } else {
                             Response {
                                 request_seq: seq,
@@ -803,6 +1287,113 @@ impl DAPDebugger {
         true
     }
 
+    // Move through the recorded trace, without executing. Moving forward past
+    // the last recorded expression returns to the live execution.
+    fn replay(&mut self, seq: i64, replay_move: ReplayMove, body: ResponseBody) -> bool {
+        let state = self.state.as_ref().unwrap();
+        let target = self.trace.move_to(replay_move, |snapshot| {
+            !state
+                .breakpoints_at(&snapshot.contract_id, &snapshot.span)
+                .is_empty()
+        });
+        let Some(target) = target else {
+            self.send_response(Response {
+                request_seq: seq,
+                success: false,
+                message: Some("no execution recorded".to_string()),
+                body: None,
+            });
+            return false;
+        };
+        let snapshot = &self.trace.snapshots[target];
+        let hit_breakpoint_ids = state.breakpoints_at(&snapshot.contract_id, &snapshot.span);
+
+        self.send_response(Response {
+            request_seq: seq,
+            success: true,
+            message: None,
+            body: Some(body),
+        });
+        let hit_breakpoint = !hit_breakpoint_ids.is_empty()
+            && matches!(
+                replay_move,
+                ReplayMove::Continue | ReplayMove::ReverseContinue
+            );
+        let reason = if hit_breakpoint {
+            StoppedReason::Breakpoint
+        } else if replay_move == ReplayMove::ReverseContinue && target == 0 {
+            StoppedReason::Entry
+        } else {
+            StoppedReason::Step
+        };
+        self.send_event(EventBody::Stopped(StoppedEvent {
+            reason,
+            description: None,
+            thread_id: Some(0),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: if hit_breakpoint {
+                Some(hit_breakpoint_ids)
+            } else {
+                None
+            },
+        }));
+        false
+    }
+
+    fn record_snapshot(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        expr: &SymbolicExpression,
+        stack: &[FunctionIdentifier],
+    ) {
+        let stack_frames: Vec<StackFrame> = stack
+            .iter()
+            .rev()
+            .filter(|function| !function.to_string().starts_with("_native_:"))
+            .filter_map(|function| self.stack_frames.get(function).cloned())
+            .collect();
+        // Only the top frame is cloned, the others are shared with the previous snapshot
+        let top_frame_id = stack_frames.first().map(|frame| frame.id);
+        let mut frames = HashMap::new();
+        for frame in stack_frames.iter() {
+            let shared = match self.trace.last() {
+                Some(previous) if Some(frame.id) != top_frame_id => {
+                    previous.frames.get(&frame.id).cloned()
+                }
+                _ => None,
+            };
+            if let Some(frame_state) = shared {
+                frames.insert(frame.id, frame_state);
+                continue;
+            }
+            let Some(frame_scopes) = self.scopes.get(&frame.id) else {
+                continue;
+            };
+            let mut variables = HashMap::new();
+            for scope in frame_scopes {
+                if let Some(scope_variables) = self.variables.get(&scope.variables_reference) {
+                    variables.insert(scope.variables_reference, scope_variables.clone());
+                }
+            }
+            let frame_state = FrameState {
+                scopes: frame_scopes.clone(),
+                variables,
+            };
+            frames.insert(frame.id, Rc::new(frame_state));
+        }
+
+        let depth = self.get_state().depth();
+        self.trace.push(Snapshot {
+            contract_id: contract_id.clone(),
+            span: expr.span.clone(),
+            depth,
+            stack_frames,
+            frames,
+        });
+    }
+
     fn save_scopes_for_frame(
         &mut self,
         stack_frame: &StackFrame,
@@ -886,6 +1477,12 @@ impl DAPDebugger {
             end_line: None,
             end_column: None,
         });
+        let contract_id = &contract_context.contract_identifier;
+        let data_target = |name: &str, key: Option<Value>| DataTarget {
+            contract_id: contract_id.clone(),
+            name: name.to_string(),
+            key,
+        };
         let mut variables = Vec::new();
 
         // Constants
@@ -915,11 +1512,15 @@ impl DAPDebugger {
                     &DEFAULT_EPOCH,
                 )
                 .unwrap();
+            self.variable_data_targets.insert(
+                (scope_id, name.to_string()),
+                data_target(name.as_str(), None),
+            );
             variables.push(Variable {
                 name: name.to_string(),
                 value: value.to_string(),
                 var_type: Some(format!("{}", metadata.value_type)),
-                presentation_hint: None,
+                presentation_hint: Some(data_presentation_hint()),
                 evaluate_name: None,
                 variables_reference: 0,
                 named_variables: None,
@@ -928,21 +1529,109 @@ impl DAPDebugger {
             });
         }
 
-        // Maps
+        // Maps and tokens can't be iterated, only their entries accessed
+        // during the execution are shown, so that they can be watched.
+        let mut entries_id = stack_frame.id * 1000 + 100;
         for (name, metadata) in &contract_context.meta_data_map {
-            // We do not grab any values for maps. Users can query map values in the console.
             let map_type = format!("{{{}: {}}}", metadata.key_type, metadata.value_type);
+            let keys = self
+                .get_state()
+                .get_data_keys(contract_id, name.as_str())
+                .to_vec();
+            let mut entries = Vec::new();
+            for key in keys {
+                let value = global_context
+                    .database
+                    .fetch_entry(contract_id, name.as_str(), &key, metadata, &DEFAULT_EPOCH)
+                    .unwrap();
+                self.variable_data_targets.insert(
+                    (entries_id, key.to_string()),
+                    data_target(name.as_str(), Some(key.clone())),
+                );
+                entries.push(Variable {
+                    name: key.to_string(),
+                    value: value.to_string(),
+                    var_type: Some(type_for_value(&value)),
+                    presentation_hint: Some(data_presentation_hint()),
+                    evaluate_name: None,
+                    variables_reference: 0,
+                    named_variables: None,
+                    indexed_variables: None,
+                    memory_reference: None,
+                });
+            }
+
+            self.variable_data_targets.insert(
+                (scope_id, name.to_string()),
+                data_target(name.as_str(), None),
+            );
             variables.push(Variable {
                 name: name.to_string(),
                 value: map_type.clone(),
                 var_type: Some(map_type),
-                presentation_hint: None,
+                presentation_hint: Some(data_presentation_hint()),
                 evaluate_name: None,
-                variables_reference: 0,
-                named_variables: None,
+                variables_reference: if entries.is_empty() { 0 } else { entries_id },
+                named_variables: Some(entries.len()),
                 indexed_variables: None,
                 memory_reference: None,
             });
+            self.variables.insert(entries_id, entries);
+            entries_id += 1;
+        }
+
+        for (name, metadata) in &contract_context.meta_ft {
+            let owners = self
+                .get_state()
+                .get_data_keys(contract_id, name.as_str())
+                .to_vec();
+            let mut balances = Vec::new();
+            for owner in owners {
+                let balance = match &owner {
+                    Value::Principal(principal) => global_context
+                        .database
+                        .get_ft_balance(contract_id, name.as_str(), principal, Some(metadata))
+                        .unwrap(),
+                    _ => continue,
+                };
+                self.variable_data_targets.insert(
+                    (entries_id, owner.to_string()),
+                    data_target(name.as_str(), Some(owner.clone())),
+                );
+                balances.push(Variable {
+                    name: owner.to_string(),
+                    value: format!("u{}", balance),
+                    var_type: Some("uint".to_string()),
+                    presentation_hint: Some(data_presentation_hint()),
+                    evaluate_name: None,
+                    variables_reference: 0,
+                    named_variables: None,
+                    indexed_variables: None,
+                    memory_reference: None,
+                });
+            }
+
+            let supply = global_context
+                .database
+                .get_ft_supply(contract_id, name)
+                .unwrap();
+            self.variable_data_targets.insert(
+                (scope_id, name.to_string()),
+                data_target(name.as_str(), None),
+            );
+            variables.push(Variable {
+                name: name.to_string(),
+                value: format!("u{}", supply),
+                var_type: Some("ft".to_string()),
+                presentation_hint: Some(data_presentation_hint()),
+                evaluate_name: None,
+                variables_reference: if balances.is_empty() { 0 } else { entries_id },
+                named_variables: Some(balances.len()),
+                indexed_variables: None,
+                memory_reference: None,
+            });
+            self.variables.insert(entries_id, balances);
+            entries_id += 1;
         }
         self.variables.insert(scope_id, variables);
 
@@ -1027,7 +1716,18 @@ impl EvalHook for DAPDebugger {
             }
         }
 
-        if !self.get_state().will_begin_eval(env, context, expr) {
+        let proceed = self.get_state().will_begin_eval(env, context, expr);
+        if let SymbolicExpressionType::List(_) = expr.expr {
+            self.record_snapshot(
+                &env.contract_context.contract_identifier,
+                expr,
+                &stack_trace,
+            );
+        }
+        for message in self.get_state().take_log_messages() {
+            self.stdout(format!("{}\n", message));
+        }
+        if !proceed {
             if self.get_state().state == State::Start {
                 // Sending this initialized event triggers the configuration
                 // (e.g. setting breakpoints), after which the ConfigurationDone
@@ -1075,23 +1775,7 @@ impl EvalHook for DAPDebugger {
                 self.send_event(EventBody::Stopped(stopped));
             }
 
-            // Save the current state, which may be needed to respond to incoming requests
-            self.current = Some(Current {
-                expr_id: expr.id,
-                stack: stack_trace,
-            });
-
-            let mut proceed = false;
-            while !proceed {
-                proceed = match self.wait_for_command(Some(env), Some(context)) {
-                    Ok(proceed) => proceed,
-                    Err(e) => {
-                        self.log(format!("error: {}", e));
-                        false
-                    }
-                };
-            }
-            self.current = None;
+            self.wait_until_resumed(env, context, expr.id, stack_trace);
         } else {
             // TODO: If there is already a message waiting, process it before
             //       continuing. This would be needed for a pause request.
@@ -1107,9 +1791,39 @@ impl EvalHook for DAPDebugger {
         res: &Result<Value, Error>,
     ) {
         self.get_state().did_finish_eval(env, context, expr, res);
+
+        // Stop on the failing expression, while its call stack and locals are available
+        if let State::Exception(filter) = self.get_state().state {
+            let description = self.get_state().get_exception().cloned();
+            self.send_event(EventBody::Stopped(StoppedEvent {
+                reason: StoppedReason::Exception,
+                description: Some(filter.label().to_string()),
+                thread_id: Some(0),
+                preserve_focus_hint: None,
+                text: description,
+                all_threads_stopped: None,
+                hit_breakpoint_ids: None,
+            }));
+            let stack_trace = env.call_stack.make_stack_trace();
+            self.wait_until_resumed(env, context, expr.id, stack_trace);
+        } else if let State::DataBreak(breakpoint, _access_type) = self.get_state().state {
+            // A watchpoint scoped to a key is hit once the key is evaluated
+            self.send_event(EventBody::Stopped(StoppedEvent {
+                reason: StoppedReason::DataBreakpoint,
+                description: None,
+                thread_id: Some(0),
+                preserve_focus_hint: None,
+                text: None,
+                all_threads_stopped: None,
+                hit_breakpoint_ids: Some(vec![breakpoint]),
+            }));
+            let stack_trace = env.call_stack.make_stack_trace();
+            self.wait_until_resumed(env, context, expr.id, stack_trace);
+        }
     }
 
     fn did_complete(&mut self, result: Result<&mut ExecutionResult, String>) {
+        self.trace.clear();
         match result {
             Ok(result) => {
                 self.log("Execution completed.\n");
@@ -1139,6 +1853,16 @@ impl EvalHook for DAPDebugger {
     }
 }
 
+/// Presentation of the contract data which data breakpoints can be set on
+fn data_presentation_hint() -> VariablePresentationHint {
+    VariablePresentationHint {
+        kind: Some(VariableKind::Data),
+        attributes: Some(vec![VariableAttribute::HasDataBreakpoint]),
+        visibility: None,
+        lazy: None,
+    }
+}
+
 fn type_for_value(value: &Value) -> String {
     match value {
         Value::Int(_) => "int".to_string(),
@@ -1170,3 +1894,136 @@ fn type_for_value(value: &Value) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_configuration() {
+        let arguments: LaunchArguments = serde_json::from_value(serde_json::json!({
+            "type": "clarinet",
+            "request": "launch",
+            "manifest": "Clarinet.toml",
+            "contractCall": { "contract": "counter", "method": "add", "args": ["u2"] },
+        }))
+        .unwrap();
+        assert_eq!(
+            arguments.into_configuration(),
+            Ok(LaunchConfiguration {
+                manifest: "Clarinet.toml".to_string(),
+                target: LaunchTarget::ContractCall(ContractCallLaunch {
+                    contract: "counter".to_string(),
+                    method: "add".to_string(),
+                    args: vec!["u2".to_string()],
+                    sender: None,
+                }),
+            })
+        );
+
+        let arguments = LaunchArguments {
+            manifest: Some("Clarinet.toml".to_string()),
+            expression: Some("(+ 1 2)".to_string()),
+            script: Some("script.txt".to_string()),
+            ..Default::default()
+        };
+        assert!(arguments.into_configuration().is_err());
+        assert!(LaunchArguments::default().into_configuration().is_err());
+    }
+
+    /// A trace of `(depth, line)` expressions, with breakpoints at line 0
+    fn trace(expressions: &[(usize, u32)]) -> Trace {
+        let mut trace = Trace::new(MAX_TRACE_LENGTH);
+        for (depth, line) in expressions.iter() {
+            trace.push(Snapshot {
+                contract_id: QualifiedContractIdentifier::transient(),
+                span: Span {
+                    start_line: *line,
+                    start_column: 1,
+                    end_line: *line,
+                    end_column: 1,
+                },
+                depth: *depth,
+                stack_frames: vec![],
+                frames: HashMap::new(),
+            });
+        }
+        trace
+    }
+
+    fn has_breakpoint(snapshot: &Snapshot) -> bool {
+        snapshot.span.start_line == 0
+    }
+
+    #[test]
+    fn test_replay_moves() {
+        let mut trace = trace(&[(1, 1), (2, 0), (3, 3), (2, 4), (1, 0), (2, 6), (1, 7)]);
+        assert!(!trace.is_replaying());
+
+        assert_eq!(trace.move_to(ReplayMove::StepBack, has_breakpoint), Some(5));
+        assert_eq!(trace.current().unwrap().span.start_line, 6);
+        assert_eq!(
+            trace.move_to(ReplayMove::ReverseContinue, has_breakpoint),
+            Some(4)
+        );
+        assert_eq!(
+            trace.move_to(ReplayMove::ReverseContinue, has_breakpoint),
+            Some(1)
+        );
+        assert_eq!(trace.move_to(ReplayMove::StepIn, has_breakpoint), Some(2));
+        // next stays at the same depth, step out goes to a shallower one
+        assert_eq!(trace.move_to(ReplayMove::Next, has_breakpoint), Some(3));
+        assert_eq!(trace.move_to(ReplayMove::StepOut, has_breakpoint), Some(4));
+        assert!(trace.is_replaying());
+
+        // without a breakpoint ahead, continue goes back to the live execution
+        assert_eq!(trace.move_to(ReplayMove::Continue, has_breakpoint), Some(6));
+        assert!(!trace.is_replaying());
+        assert!(trace.current().is_none());
+
+        assert_eq!(
+            trace.move_to(ReplayMove::ReverseContinue, has_breakpoint),
+            Some(4)
+        );
+        assert_eq!(
+            trace.move_to(ReplayMove::ReverseContinue, has_breakpoint),
+            Some(1)
+        );
+        // reverse continue stops at the first expression without an earlier breakpoint
+        assert_eq!(
+            trace.move_to(ReplayMove::ReverseContinue, has_breakpoint),
+            Some(0)
+        );
+        assert_eq!(trace.move_to(ReplayMove::StepBack, has_breakpoint), Some(0));
+        assert_eq!(trace.move_to(ReplayMove::Next, has_breakpoint), Some(4));
+        assert_eq!(trace.move_to(ReplayMove::Continue, has_breakpoint), Some(6));
+        assert!(!trace.is_replaying());
+
+        // step in and next from the last expression return to the live execution
+        assert_eq!(trace.move_to(ReplayMove::StepBack, has_breakpoint), Some(5));
+        assert_eq!(trace.move_to(ReplayMove::StepIn, has_breakpoint), Some(6));
+        assert!(!trace.is_replaying());
+
+        trace.clear();
+        assert_eq!(trace.move_to(ReplayMove::StepBack, has_breakpoint), None);
+    }
+
+    #[test]
+    fn test_trace_eviction() {
+        let mut trace = trace(&[(1, 1), (1, 2), (1, 3)]);
+        trace.capacity = 3;
+        assert_eq!(trace.move_to(ReplayMove::StepBack, has_breakpoint), Some(1));
+
+        // the oldest snapshot is dropped, the position still points to the same expression
+        let mut newer = self::trace(&[(1, 4)]);
+        trace.push(newer.snapshots.pop_front().unwrap());
+        assert_eq!(trace.snapshots.len(), 3);
+        assert_eq!(trace.snapshots[0].span.start_line, 2);
+        assert_eq!(trace.current().unwrap().span.start_line, 2);
+        assert_eq!(trace.position, Some(0));
+
+        assert_eq!(trace.move_to(ReplayMove::StepBack, has_breakpoint), Some(0));
+        assert_eq!(trace.move_to(ReplayMove::Continue, has_breakpoint), Some(2));
+        assert!(!trace.is_replaying());
+    }
+}
@@ -3,14 +3,17 @@ use std::fmt::Display;
 
 use crate::repl::diagnostic::output_diagnostic;
 use clarity::vm::ast::build_ast_with_diagnostics;
+use clarity::vm::callables::DefinedFunction;
 use clarity::vm::contexts::{Environment, LocalContext};
 use clarity::vm::contracts::Contract;
 use clarity::vm::diagnostic::Level;
-use clarity::vm::errors::Error;
+use clarity::vm::errors::{Error, ShortReturnType};
 use clarity::vm::functions::NativeFunctions;
 use clarity::vm::representations::Span;
 use clarity::vm::representations::SymbolicExpression;
-use clarity::vm::types::{QualifiedContractIdentifier, StandardPrincipalData, Value};
+use clarity::vm::types::{
+    PrincipalData, QualifiedContractIdentifier, StandardPrincipalData, Value,
+};
 use clarity::vm::{eval, ClarityVersion};
 use clarity::vm::{ContractName, SymbolicExpressionType};
 
@@ -63,6 +66,11 @@ impl Display for BreakpointData {
 pub struct SourceBreakpoint {
     line: u32,
     column: Option<u32>,
+    /// Clarity expression, the breakpoint is only hit when it evaluates to `true`
+    condition: Option<String>,
+    hit_condition: Option<HitCondition>,
+    /// Message logged instead of stopping, `{expr}` is replaced by the value of `expr`
+    log_message: Option<String>,
 }
 
 impl Display for SourceBreakpoint {
@@ -72,7 +80,78 @@ impl Display for SourceBreakpoint {
         } else {
             String::new()
         };
-        write!(f, ":{}{}", self.line, column)
+        write!(f, ":{}{}", self.line, column)?;
+        if let Some(condition) = &self.condition {
+            write!(f, " if {}", condition)?;
+        }
+        if let Some(hit_condition) = &self.hit_condition {
+            write!(f, " hits {}", hit_condition)?;
+        }
+        if let Some(log_message) = &self.log_message {
+            write!(f, " log {}", log_message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of hits required for a breakpoint to trigger. A plain number `n`
+/// is equivalent to `>= n`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+pub enum HitCondition {
+    Equal(u32),
+    Greater(u32),
+    GreaterOrEqual(u32),
+    Less(u32),
+    LessOrEqual(u32),
+    Multiple(u32),
+}
+
+impl HitCondition {
+    pub fn parse(hit_condition: &str) -> Result<HitCondition, String> {
+        let hit_condition = hit_condition.trim();
+        let operators: [(&str, fn(u32) -> HitCondition); 7] = [
+            (">=", HitCondition::GreaterOrEqual),
+            ("<=", HitCondition::LessOrEqual),
+            ("==", HitCondition::Equal),
+            (">", HitCondition::Greater),
+            ("<", HitCondition::Less),
+            ("%", HitCondition::Multiple),
+            ("", HitCondition::GreaterOrEqual),
+        ];
+        for (operator, constructor) in operators {
+            if let Some(count) = hit_condition.strip_prefix(operator) {
+                return match count.trim().parse::<u32>() {
+                    Ok(0) if operator == "%" => Err("hit count modulo must be positive".into()),
+                    Ok(count) => Ok(constructor(count)),
+                    Err(_) => Err(format!("invalid hit condition: {}", hit_condition)),
+                };
+            }
+        }
+        unreachable!()
+    }
+
+    pub fn is_met(&self, hits: u32) -> bool {
+        match *self {
+            HitCondition::Equal(count) => hits == count,
+            HitCondition::Greater(count) => hits > count,
+            HitCondition::GreaterOrEqual(count) => hits >= count,
+            HitCondition::Less(count) => hits < count,
+            HitCondition::LessOrEqual(count) => hits <= count,
+            HitCondition::Multiple(count) => hits % count == 0,
+        }
+    }
+}
+
+impl Display for HitCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HitCondition::Equal(count) => write!(f, "== {}", count),
+            HitCondition::Greater(count) => write!(f, "> {}", count),
+            HitCondition::GreaterOrEqual(count) => write!(f, ">= {}", count),
+            HitCondition::Less(count) => write!(f, "< {}", count),
+            HitCondition::LessOrEqual(count) => write!(f, "<= {}", count),
+            HitCondition::Multiple(count) => write!(f, "% {}", count),
+        }
     }
 }
 
@@ -95,12 +174,50 @@ impl Display for AccessType {
 
 pub struct DataBreakpoint {
     name: String,
+    /// Map key or token owner the watchpoint is scoped to
+    key: Option<Value>,
     access_type: AccessType,
 }
 
 impl Display for DataBreakpoint {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, ".{} {}", self.name, self.access_type)
+        match &self.key {
+            Some(key) => write!(f, ".{} {} {}", self.name, key, self.access_type),
+            None => write!(f, ".{} {}", self.name, self.access_type),
+        }
+    }
+}
+
+/// Access to a data-var, map or fungible token made by an expression
+struct DataAccess<'a> {
+    name: String,
+    access_type: AccessType,
+    /// Expressions of the map key or of the token owners accessed
+    keys: Vec<&'a SymbolicExpression>,
+}
+
+impl<'a> DataAccess<'a> {
+    fn from_expr(expr: &'a SymbolicExpression) -> Option<DataAccess<'a>> {
+        let (function_name, args) = expr.match_list()?.split_first()?;
+        let native_function = NativeFunctions::lookup_by_name_at_version(
+            function_name.match_atom()?,
+            &ClarityVersion::latest(),
+        )?;
+        use clarity::vm::functions::NativeFunctions::*;
+        let (access_type, keys) = match native_function {
+            FetchVar | GetTokenSupply => (AccessType::Read, vec![]),
+            SetVar => (AccessType::Write, vec![]),
+            FetchEntry | GetTokenBalance => (AccessType::Read, vec![args.get(1)?]),
+            SetEntry | InsertEntry | DeleteEntry => (AccessType::Write, vec![args.get(1)?]),
+            MintToken | BurnToken => (AccessType::Write, vec![args.get(2)?]),
+            TransferToken => (AccessType::Write, vec![args.get(2)?, args.get(3)?]),
+            _ => return None,
+        };
+        Some(DataAccess {
+            name: args.first()?.match_atom()?.to_string(),
+            access_type,
+            keys,
+        })
     }
 }
 
@@ -114,6 +231,41 @@ impl Display for FunctionBreakpoint {
     }
 }
 
+/// Kinds of errors on which the debugger can stop
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ExceptionFilter {
+    /// Runtime errors and failed `asserts!`
+    RuntimeError,
+    /// Public function calls returning an err response, the err responses of
+    /// read-only functions are not reported
+    ErrResponse,
+}
+
+impl ExceptionFilter {
+    pub const ALL: [ExceptionFilter; 2] =
+        [ExceptionFilter::RuntimeError, ExceptionFilter::ErrResponse];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            ExceptionFilter::RuntimeError => "runtime-error",
+            ExceptionFilter::ErrResponse => "err-response",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExceptionFilter::RuntimeError => "Runtime error",
+            ExceptionFilter::ErrResponse => "Public function returns err",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ExceptionFilter> {
+        ExceptionFilter::ALL
+            .into_iter()
+            .find(|filter| filter.id() == id)
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub(crate) enum State {
     Start,
@@ -124,6 +276,7 @@ pub(crate) enum State {
     Finished,
     Break(usize),
     DataBreak(usize, AccessType),
+    Exception(ExceptionFilter),
     Pause,
     Quit,
 }
@@ -153,7 +306,19 @@ pub struct DebugState {
     watchpoints: BTreeMap<usize, Breakpoint>,
     break_locations: HashMap<QualifiedContractIdentifier, HashSet<usize>>,
     watch_variables: HashMap<(QualifiedContractIdentifier, String), HashSet<usize>>,
+    /// Map keys and token owners accessed during the execution
+    data_keys: HashMap<(QualifiedContractIdentifier, String), Vec<Value>>,
+    /// Key expressions being evaluated, with the map or token they access and how
+    pending_data_keys: HashMap<u64, ((QualifiedContractIdentifier, String), AccessType)>,
     active_breakpoints: HashSet<usize>,
+    hit_counts: HashMap<usize, u32>,
+    /// Output of the logpoints, to be displayed by the debugger
+    log_messages: Vec<String>,
+    exception_filters: HashSet<ExceptionFilter>,
+    /// Description of the exception the debugger stopped on
+    exception: Option<String>,
+    /// Set while an error, already reported, propagates to the parent expressions
+    exception_reported: bool,
     state: State,
     stack: Vec<ExprState>,
     unique_id: usize,
@@ -168,7 +333,14 @@ impl DebugState {
             watchpoints: BTreeMap::new(),
             break_locations: HashMap::new(),
             watch_variables: HashMap::new(),
+            data_keys: HashMap::new(),
+            pending_data_keys: HashMap::new(),
             active_breakpoints: HashSet::new(),
+            hit_counts: HashMap::new(),
+            log_messages: Vec::new(),
+            exception_filters: HashSet::new(),
+            exception: None,
+            exception_reported: false,
             state: State::Start,
             stack: Vec::new(),
             unique_id: 0,
@@ -251,12 +423,14 @@ impl DebugState {
         &mut self,
         contract_id: &QualifiedContractIdentifier,
         name: &str,
+        key: Option<Value>,
         access_type: AccessType,
-    ) {
+    ) -> usize {
         let breakpoint = Breakpoint {
             id: self.get_unique_id(),
             data: BreakpointData::Data(DataBreakpoint {
                 name: name.to_string(),
+                key,
                 access_type,
             }),
             source: Source {
@@ -278,7 +452,9 @@ impl DebugState {
             self.watch_variables.insert(key, set);
         }
 
-        self.watchpoints.insert(breakpoint.id, breakpoint);
+        let id = breakpoint.id;
+        self.watchpoints.insert(id, breakpoint);
+        id
     }
 
     fn delete_all_watchpoints(&mut self) {
@@ -354,19 +530,186 @@ impl DebugState {
         }
     }
 
-    fn did_hit_source_breakpoint(
+    pub(crate) fn set_exception_filters(&mut self, filters: HashSet<ExceptionFilter>) {
+        self.exception_filters = filters;
+    }
+
+    pub(crate) fn get_exception(&self) -> Option<&String> {
+        self.exception.as_ref()
+    }
+
+    // Check if the result of an expression is an exception the debugger
+    // should stop on, errors are only reported by the innermost expression.
+    fn did_hit_exception(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+        res: &Result<Value, Error>,
+    ) -> Option<(ExceptionFilter, String)> {
+        let error = match res {
+            Ok(value) => {
+                self.exception_reported = false;
+                if !self
+                    .exception_filters
+                    .contains(&ExceptionFilter::ErrResponse)
+                {
+                    return None;
+                }
+                let Value::Response(response) = value else {
+                    return None;
+                };
+                if response.committed {
+                    return None;
+                }
+                let list = expr.match_list()?;
+                let function = list.first().and_then(|head| head.match_atom())?;
+                if !is_public_function_call(env, context, list) {
+                    return None;
+                }
+                return Some((
+                    ExceptionFilter::ErrResponse,
+                    format!("{} returned {}", function, value),
+                ));
+            }
+            Err(error) => error,
+        };
+
+        // `try!`, `unwrap!` and `unwrap-err!` return their value early, the err
+        // responses are left to the ErrResponse filter
+        if let Error::ShortReturn(ShortReturnType::ExpectedValue(_)) = error {
+            return None;
+        }
+        if self.exception_reported
+            || !self
+                .exception_filters
+                .contains(&ExceptionFilter::RuntimeError)
+        {
+            return None;
+        }
+        self.exception_reported = true;
+        let description = match error {
+            Error::ShortReturn(ShortReturnType::AssertionFailed(value)) => {
+                format!("assertion failed: {}", value)
+            }
+            error => format!("runtime error: {}", error),
+        };
+        Some((ExceptionFilter::RuntimeError, description))
+    }
+
+    /// Number of expressions being evaluated
+    pub(crate) fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub(crate) fn take_log_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.log_messages)
+    }
+
+    /// Replace the `{expr}` placeholders of a log message by their values
+    fn format_log_message(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        message: &str,
+    ) -> String {
+        let mut output = String::new();
+        let mut expression = String::new();
+        // tuples can be written with braces, so they are balanced
+        let mut depth = 0;
+        for c in message.chars() {
+            match (c, depth) {
+                ('{', 0) => depth = 1,
+                ('}', 1) => {
+                    depth = 0;
+                    match self.evaluate(env, context, &expression) {
+                        Ok(value) => output.push_str(&value.to_string()),
+                        Err(errors) => output.push_str(&format!("<{}>", errors.join(" "))),
+                    }
+                    expression.clear();
+                }
+                (_, 0) => output.push(c),
+                (c, _) => {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => (),
+                    }
+                    expression.push(c);
+                }
+            }
+        }
+        if depth > 0 {
+            output.push('{');
+            output.push_str(&expression);
+        }
+        output
+    }
+
+    // Evaluate the condition, hit condition and log message of a breakpoint
+    // whose location was reached, returns true if execution should stop.
+    fn should_break(&mut self, id: usize, env: &mut Environment, context: &LocalContext) -> bool {
+        let source = match self.breakpoints.get(&id) {
+            Some(Breakpoint {
+                data: BreakpointData::Source(source),
+                ..
+            }) => source.clone(),
+            _ => return true,
+        };
+
+        if let Some(condition) = &source.condition {
+            match self.evaluate(env, context, condition) {
+                Ok(Value::Bool(true)) => (),
+                Ok(_) => return false,
+                Err(errors) => {
+                    self.log_messages.push(format!(
+                        "breakpoint {}: unable to evaluate condition: {}",
+                        id,
+                        errors.join("\n")
+                    ));
+                    return false;
+                }
+            }
+        }
+
+        let hits = self.hit_counts.entry(id).or_insert(0);
+        *hits += 1;
+        if let Some(hit_condition) = source.hit_condition {
+            if !hit_condition.is_met(*hits) {
+                return false;
+            }
+        }
+
+        if let Some(log_message) = &source.log_message {
+            let message = self.format_log_message(env, context, log_message);
+            self.log_messages.push(message);
+            return false;
+        }
+        true
+    }
+
+    fn did_hit_source_breakpoints(
         &self,
         contract_id: &QualifiedContractIdentifier,
         span: &Span,
-    ) -> Option<usize> {
+    ) -> Vec<usize> {
+        // Don't break in a subexpression of an expression which has
+        // already triggered this breakpoint
+        self.breakpoints_at(contract_id, span)
+            .into_iter()
+            .filter(|id| !self.active_breakpoints.contains(id))
+            .collect()
+    }
+
+    /// Breakpoints located at `span`, regardless of their conditions
+    pub(crate) fn breakpoints_at(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+        span: &Span,
+    ) -> Vec<usize> {
+        let mut hits = vec![];
         if let Some(set) = self.break_locations.get(contract_id) {
             for id in set {
-                // Don't break in a subexpression of an expression which has
-                // already triggered this breakpoint
-                if self.active_breakpoints.contains(id) {
-                    continue;
-                }
-
                 let breakpoint = match self.breakpoints.get(id) {
                     Some(breakpoint) => breakpoint,
                     None => panic!("internal error: breakpoint {} not found", id),
@@ -377,88 +720,73 @@ impl DebugState {
                         && (break_span.start_column == 0
                             || break_span.start_column == span.start_column)
                     {
-                        return Some(breakpoint.id);
+                        hits.push(breakpoint.id);
                     }
                 }
             }
         }
+        hits.sort();
+        hits
+    }
+
+    /// Watchpoint set on `name` for this access type, scoped to `key` (if any)
+    fn matching_watchpoint(
+        &self,
+        name: &(QualifiedContractIdentifier, String),
+        access_type: AccessType,
+        key: Option<&Value>,
+    ) -> Option<usize> {
+        let mut ids = self.watch_variables.get(name)?.iter().collect::<Vec<_>>();
+        ids.sort();
+        for id in ids {
+            let watchpoint = match self.watchpoints.get(id) {
+                Some(watchpoint) => watchpoint,
+                None => panic!("internal error: watchpoint {} not found", id),
+            };
+
+            if let BreakpointData::Data(data) = &watchpoint.data {
+                match (data.access_type, access_type) {
+                    (AccessType::Read, AccessType::Read)
+                    | (AccessType::Write, AccessType::Write)
+                    | (AccessType::ReadWrite, AccessType::Read)
+                    | (AccessType::ReadWrite, AccessType::Write) => (),
+                    _ => continue,
+                }
+                if data.key.as_ref() == key {
+                    return Some(watchpoint.id);
+                }
+            }
+        }
         None
     }
 
+    /// Check the watchpoints on the data accessed by `expr`. The ones scoped to a
+    /// key are checked once the key expressions are evaluated, in `did_finish_eval`.
     fn did_hit_data_breakpoint(
-        &self,
+        &mut self,
         contract_id: &QualifiedContractIdentifier,
         expr: &SymbolicExpression,
     ) -> Option<(usize, AccessType)> {
-        match &expr.expr {
-            SymbolicExpressionType::List(list) => {
-                // Check if we hit a data breakpoint
-                if let Some((function_name, args)) = list.split_first() {
-                    if let Some(function_name) = function_name.match_atom() {
-                        if let Some(native_function) = NativeFunctions::lookup_by_name_at_version(
-                            function_name,
-                            &ClarityVersion::latest(),
-                        ) {
-                            use clarity::vm::functions::NativeFunctions::*;
-                            if let Some((name, access_type)) = match native_function {
-                                FetchVar => Some((
-                                    args[0].match_atom().unwrap().to_string(),
-                                    AccessType::Read,
-                                )),
-                                SetVar => Some((
-                                    args[0].match_atom().unwrap().to_string(),
-                                    AccessType::Write,
-                                )),
-                                FetchEntry => Some((
-                                    args[0].match_atom().unwrap().to_string(),
-                                    AccessType::Read,
-                                )),
-                                SetEntry => Some((
-                                    args[0].match_atom().unwrap().to_string(),
-                                    AccessType::Write,
-                                )),
-                                InsertEntry => Some((
-                                    args[0].match_atom().unwrap().to_string(),
-                                    AccessType::Write,
-                                )),
-                                DeleteEntry => Some((
-                                    args[0].match_atom().unwrap().to_string(),
-                                    AccessType::Write,
-                                )),
-                                _ => None,
-                            } {
-                                let key = (contract_id.clone(), name);
-                                if let Some(set) = self.watch_variables.get(&key) {
-                                    for id in set {
-                                        let watchpoint = match self.watchpoints.get(id) {
-                                            Some(watchpoint) => watchpoint,
-                                            None => panic!(
-                                                "internal error: watchpoint {} not found",
-                                                id
-                                            ),
-                                        };
-
-                                        if let BreakpointData::Data(data) = &watchpoint.data {
-                                            match (data.access_type, access_type) {
-                                                (AccessType::Read, AccessType::Read)
-                                                | (AccessType::Write, AccessType::Write)
-                                                | (AccessType::ReadWrite, AccessType::Read)
-                                                | (AccessType::ReadWrite, AccessType::Write) => {
-                                                    return Some((watchpoint.id, access_type))
-                                                }
-                                                _ => (),
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                None
-            }
-            _ => None,
+        let access = DataAccess::from_expr(expr)?;
+        let name = (contract_id.clone(), access.name);
+        for key_expr in &access.keys {
+            self.pending_data_keys
+                .insert(key_expr.id, (name.clone(), access.access_type));
         }
+        let watchpoint = self.matching_watchpoint(&name, access.access_type, None)?;
+        Some((watchpoint, access.access_type))
+    }
+
+    /// Map keys and token owners accessed so far for the given map or token
+    pub(crate) fn get_data_keys(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+        name: &str,
+    ) -> &[Value] {
+        self.data_keys
+            .get(&(contract_id.clone(), name.to_string()))
+            .map(|keys| keys.as_slice())
+            .unwrap_or_default()
     }
 
     // Returns a bool which indicates if execution should resume (true) or if
@@ -466,7 +794,7 @@ impl DebugState {
     fn will_begin_eval(
         &mut self,
         env: &mut Environment,
-        _context: &LocalContext,
+        context: &LocalContext,
         expr: &SymbolicExpression,
     ) -> bool {
         self.stack.push(ExprState::new(expr.id));
@@ -476,15 +804,19 @@ impl DebugState {
             return true;
         }
 
-        // Check if we have hit a source breakpoint
-        if let Some(breakpoint) =
-            self.did_hit_source_breakpoint(&env.contract_context.contract_identifier, &expr.span)
+        // Check if we have hit a source breakpoint. Its conditions are only
+        // evaluated once for an expression and its subexpressions.
+        for breakpoint in
+            self.did_hit_source_breakpoints(&env.contract_context.contract_identifier, &expr.span)
         {
             self.active_breakpoints.insert(breakpoint);
             let top = self.stack.last_mut().unwrap();
             top.active_breakpoints.push(breakpoint);
 
-            self.state = State::Break(breakpoint);
+            if self.should_break(breakpoint, env, context) {
+                self.state = State::Break(breakpoint);
+                break;
+            }
         }
 
         // Always skip over non-list expressions (values).
@@ -500,7 +832,7 @@ impl DebugState {
         }
 
         match self.state {
-            State::Continue | State::Quit | State::Finish(_) => return true,
+            State::Continue | State::Quit | State::Finish(_) | State::Exception(_) => return true,
             State::StepOver(step_over_id) => {
                 if self.stack.iter().any(|state| state.id == step_over_id) {
                     // We're still inside the expression which should be stepped over,
@@ -519,13 +851,15 @@ impl DebugState {
         false
     }
 
-    // Returns a bool which indicates if the result should be printed (finish)
+    // Returns a bool which indicates if the result should be printed (finish).
+    // The state is set to `State::Exception` or `State::DataBreak` if the debugger
+    // should stop on the result.
     fn did_finish_eval(
         &mut self,
-        _env: &mut Environment,
-        _context: &LocalContext,
+        env: &mut Environment,
+        context: &LocalContext,
         expr: &SymbolicExpression,
-        _res: &Result<Value, Error>,
+        res: &Result<Value, Error>,
     ) -> bool {
         let state = self.stack.pop().unwrap();
         assert_eq!(state.id, expr.id);
@@ -535,14 +869,84 @@ impl DebugState {
             self.active_breakpoints.remove(&breakpoint);
         }
 
+        // The value of a key expression, as evaluated by the interpreter, is compared
+        // with the keys of the watchpoints before the data is accessed
+        if let (Some((name, access_type)), Ok(value)) =
+            (self.pending_data_keys.remove(&expr.id), res)
+        {
+            if self.state != State::Quit {
+                if let Some(watchpoint) = self.matching_watchpoint(&name, access_type, Some(value))
+                {
+                    self.state = State::DataBreak(watchpoint, access_type);
+                }
+            }
+            let keys = self.data_keys.entry(name).or_default();
+            if !keys.contains(value) {
+                keys.push(value.clone());
+            }
+        }
+
         // Only print the returned value if this resolves a finish command
-        match self.state {
+        let finished = match self.state {
             State::Finish(finish_id) if finish_id == state.id => {
                 self.state = State::Finished;
                 true
             }
             _ => false,
+        };
+
+        self.exception = None;
+        if self.state != State::Quit {
+            if let Some((filter, description)) = self.did_hit_exception(env, context, expr, res) {
+                self.state = State::Exception(filter);
+                self.exception = Some(description);
+            }
         }
+        finished
+    }
+}
+
+// Whether a function call calls a public function, of the current contract or
+// of the contract called with `contract-call?`. Read-only functions are not
+// public functions: their err responses are not transaction failures.
+fn is_public_function_call(
+    env: &mut Environment,
+    context: &LocalContext,
+    list: &[SymbolicExpression],
+) -> bool {
+    let Some(function) = list.first().and_then(|head| head.match_atom()) else {
+        return false;
+    };
+    let is_public = |function: Option<DefinedFunction>| {
+        function.map_or(false, |function| {
+            function.is_public() && !function.is_read_only()
+        })
+    };
+    if function.as_str() != "contract-call?" {
+        return is_public(env.contract_context.lookup_function(function.as_str()));
+    }
+
+    // the called contract is either a literal or a trait reference
+    let contract_id = match list.get(1).map(|contract| &contract.expr) {
+        Some(SymbolicExpressionType::LiteralValue(Value::Principal(PrincipalData::Contract(
+            contract_id,
+        )))) => contract_id.clone(),
+        Some(SymbolicExpressionType::Atom(name)) => match context.lookup_callable_contract(name) {
+            Some(callable) => callable.contract_identifier.clone(),
+            None => return false,
+        },
+        _ => return false,
+    };
+    let Some(called_function) = list.get(2).and_then(|name| name.match_atom()) else {
+        return false;
+    };
+    match env.global_context.database.get_contract(&contract_id) {
+        Ok(contract) => is_public(
+            contract
+                .contract_context
+                .lookup_function(called_function.as_str()),
+        ),
+        Err(_) => false,
     }
 }
 
@@ -603,9 +1007,312 @@ pub fn extract_watch_variable<'a>(
 
     if contract.contract_context.meta_data_var.get(name).is_none()
         && contract.contract_context.meta_data_map.get(name).is_none()
+        && contract.contract_context.meta_ft.get(name).is_none()
     {
         return Err(format!("no such variable: {}.{}", contract_id, name));
     }
 
     Ok((contract, name))
 }
+
+/// Check that a watchpoint on `name` can be scoped to `key`: maps can be
+/// watched for a key, fungible tokens for the balance of a principal.
+pub fn check_watch_key(contract: &Contract, name: &str, key: &Value) -> Result<(), String> {
+    let contract_context = &contract.contract_context;
+    if contract_context.meta_data_map.get(name).is_some() {
+        Ok(())
+    } else if contract_context.meta_ft.get(name).is_some() {
+        match key {
+            Value::Principal(_) => Ok(()),
+            _ => Err(format!("expected a principal, found {}", key)),
+        }
+    } else {
+        Err(format!(
+            "only maps and fungible tokens can be watched for a key: {}",
+            name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repl::{
+        ClarityCodeSource, ClarityContract, ContractDeployer, Session, SessionSettings,
+    };
+    use clarity::vm::types::TupleData;
+    use clarity::vm::{EvalHook, EvaluationResult, ExecutionResult};
+
+    /// Resume right away, recording the watchpoints hit
+    struct WatchpointsRecorder {
+        state: DebugState,
+        watchpoints: Vec<(String, Option<Value>, AccessType)>,
+        hits: Vec<(usize, AccessType)>,
+    }
+
+    impl WatchpointsRecorder {
+        fn record_hit(&mut self) {
+            if let State::DataBreak(id, access_type) = self.state.state {
+                self.hits.push((id, access_type));
+            }
+            self.state.state = State::Continue;
+        }
+    }
+
+    impl EvalHook for WatchpointsRecorder {
+        fn will_begin_eval(
+            &mut self,
+            env: &mut Environment,
+            context: &LocalContext,
+            expr: &SymbolicExpression,
+        ) {
+            for (name, key, access_type) in self.watchpoints.drain(..) {
+                let contract_id = env.contract_context.contract_identifier.clone();
+                self.state
+                    .add_watchpoint(&contract_id, &name, key, access_type);
+            }
+            if !self.state.will_begin_eval(env, context, expr) {
+                self.record_hit();
+            }
+        }
+
+        fn did_finish_eval(
+            &mut self,
+            env: &mut Environment,
+            context: &LocalContext,
+            expr: &SymbolicExpression,
+            res: &Result<Value, Error>,
+        ) {
+            self.state.did_finish_eval(env, context, expr, res);
+            self.record_hit();
+        }
+
+        fn did_complete(&mut self, _result: Result<&mut ExecutionResult, String>) {}
+    }
+
+    /// Resume right away, recording the exceptions hit
+    struct ExceptionsRecorder {
+        state: DebugState,
+        exceptions: Vec<(ExceptionFilter, String)>,
+    }
+
+    impl ExceptionsRecorder {
+        fn new(filters: &[ExceptionFilter]) -> ExceptionsRecorder {
+            let mut state = DebugState::new(&QualifiedContractIdentifier::transient(), "");
+            state.set_exception_filters(filters.iter().copied().collect());
+            state.state = State::Continue;
+            ExceptionsRecorder {
+                state,
+                exceptions: vec![],
+            }
+        }
+    }
+
+    impl EvalHook for ExceptionsRecorder {
+        fn will_begin_eval(
+            &mut self,
+            env: &mut Environment,
+            context: &LocalContext,
+            expr: &SymbolicExpression,
+        ) {
+            self.state.will_begin_eval(env, context, expr);
+        }
+
+        fn did_finish_eval(
+            &mut self,
+            env: &mut Environment,
+            context: &LocalContext,
+            expr: &SymbolicExpression,
+            res: &Result<Value, Error>,
+        ) {
+            self.state.did_finish_eval(env, context, expr, res);
+            if let State::Exception(filter) = self.state.state {
+                let description = self.state.get_exception().unwrap().clone();
+                self.exceptions.push((filter, description));
+                self.state.state = State::Continue;
+            }
+        }
+
+        fn did_complete(&mut self, _result: Result<&mut ExecutionResult, String>) {}
+    }
+
+    #[test]
+    fn test_hit_condition() {
+        assert_eq!(
+            HitCondition::parse("3"),
+            Ok(HitCondition::GreaterOrEqual(3))
+        );
+        assert_eq!(HitCondition::parse("> 3"), Ok(HitCondition::Greater(3)));
+        assert_eq!(HitCondition::parse("<=2"), Ok(HitCondition::LessOrEqual(2)));
+        assert!(HitCondition::parse("% 0").is_err());
+        assert!(HitCondition::parse("!= 2").is_err());
+
+        let multiple = HitCondition::parse("%2").unwrap();
+        let hits = (1..=6)
+            .filter(|hits| multiple.is_met(*hits))
+            .collect::<Vec<_>>();
+        assert_eq!(hits, vec![2, 4, 6]);
+        assert!(HitCondition::Equal(2).is_met(2));
+        assert!(!HitCondition::Less(2).is_met(2));
+        assert_eq!(HitCondition::Multiple(2).to_string(), "% 2");
+    }
+
+    #[test]
+    fn test_data_access() {
+        let snippet = "(map-get? owners { id: u1 }) (var-set counter u2) (ft-transfer? token u1 tx-sender 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM) (+ u1 u2)";
+        let (ast, _, success) = build_ast_with_diagnostics(
+            &QualifiedContractIdentifier::transient(),
+            snippet,
+            &mut (),
+            ClarityVersion::latest(),
+            crate::repl::DEFAULT_EPOCH,
+        );
+        assert!(success);
+
+        let access = DataAccess::from_expr(&ast.expressions[0]).unwrap();
+        assert_eq!(access.name, "owners");
+        assert_eq!(access.access_type, AccessType::Read);
+        assert_eq!(access.keys.len(), 1);
+
+        let access = DataAccess::from_expr(&ast.expressions[1]).unwrap();
+        assert_eq!(access.name, "counter");
+        assert_eq!(access.access_type, AccessType::Write);
+        assert!(access.keys.is_empty());
+
+        let access = DataAccess::from_expr(&ast.expressions[2]).unwrap();
+        assert_eq!(access.name, "token");
+        assert_eq!(access.access_type, AccessType::Write);
+        assert_eq!(access.keys.len(), 2);
+
+        assert!(DataAccess::from_expr(&ast.expressions[3]).is_none());
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let snippet = r#"
+(define-data-var calls uint u0)
+(define-map owners { id: uint } principal)
+(define-private (next-id)
+    (begin
+        (var-set calls (+ (var-get calls) u1))
+        { id: (var-get calls) }))
+(map-set owners (next-id) tx-sender)
+(map-set owners (next-id) tx-sender)
+(var-get calls)
+"#;
+        let watched_key =
+            Value::Tuple(TupleData::from_data(vec![("id".into(), Value::UInt(2))]).unwrap());
+        let mut recorder = WatchpointsRecorder {
+            state: DebugState::new(&QualifiedContractIdentifier::transient(), ""),
+            watchpoints: vec![
+                ("calls".to_string(), None, AccessType::Write),
+                ("owners".to_string(), Some(watched_key), AccessType::Write),
+            ],
+            hits: vec![],
+        };
+        recorder.state.state = State::Continue;
+
+        let mut session = Session::new(SessionSettings::default());
+        let result = session
+            .eval(snippet.to_string(), Some(vec![&mut recorder]), false)
+            .unwrap();
+
+        // the key expressions are only evaluated by the interpreter
+        match result.result {
+            EvaluationResult::Contract(contract) => {
+                assert_eq!(contract.result, Some(Value::UInt(2)))
+            }
+            _ => panic!("expected a contract"),
+        }
+        assert_eq!(
+            recorder.hits,
+            vec![
+                (1, AccessType::Write),
+                (1, AccessType::Write),
+                (2, AccessType::Write)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stop_on_runtime_error() {
+        let snippet = r#"
+(define-private (get-value (value (optional uint)))
+    (unwrap-panic value))
+(define-public (fail)
+    (err u1))
+(define-public (early (value (optional uint)))
+    (ok (unwrap! value (err u2))))
+(fail)
+(early none)
+(get-value none)
+"#;
+        let mut recorder = ExceptionsRecorder::new(&[ExceptionFilter::RuntimeError]);
+        let mut session = Session::new(SessionSettings::default());
+        assert!(session
+            .eval(snippet.to_string(), Some(vec![&mut recorder]), false)
+            .is_err());
+
+        // the error is only reported by the failing expression, neither the err
+        // response nor the early return of unwrap! is a runtime error
+        assert_eq!(recorder.exceptions.len(), 1);
+        let (filter, description) = &recorder.exceptions[0];
+        assert_eq!(*filter, ExceptionFilter::RuntimeError);
+        assert!(description.starts_with("runtime error:"));
+    }
+
+    #[test]
+    fn test_stop_on_err_response() {
+        let deployer = "ST000000000000000000002AMW42H";
+        let other = r#"
+(define-public (withdraw)
+    (err u2))
+(define-read-only (get-balance)
+    (err u3))
+"#;
+        let snippet = format!(
+            r#"
+(define-public (fail)
+    (err u1))
+(define-read-only (read)
+    (err u4))
+(fail)
+(read)
+(contract-call? '{deployer}.other withdraw)
+(contract-call? '{deployer}.other get-balance)
+"#
+        );
+
+        let mut session = Session::new(SessionSettings::default());
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(other.to_string()),
+            name: "other".to_string(),
+            deployer: ContractDeployer::Address(deployer.to_string()),
+            clarity_version: ClarityVersion::Clarity1,
+            epoch: crate::repl::DEFAULT_EPOCH,
+        };
+        session
+            .deploy_contract(&contract, None, false, None, &mut None)
+            .unwrap();
+
+        let mut recorder = ExceptionsRecorder::new(&[ExceptionFilter::ErrResponse]);
+        session
+            .eval(snippet, Some(vec![&mut recorder]), false)
+            .unwrap();
+
+        // the err responses of read-only functions are not reported
+        assert_eq!(
+            recorder.exceptions,
+            vec![
+                (
+                    ExceptionFilter::ErrResponse,
+                    "fail returned (err u1)".to_string()
+                ),
+                (
+                    ExceptionFilter::ErrResponse,
+                    "contract-call? returned (err u2)".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -5,6 +5,7 @@ use crate::analysis::ast_dependency_detector::{ASTDependencyDetector, Dependency
 use crate::analysis::{self};
 use crate::repl::datastore::BurnDatastore;
 use crate::repl::datastore::Datastore;
+use crate::repl::pox::{self, PoxState};
 use crate::repl::Settings;
 use clarity::consts::CHAIN_ID_TESTNET;
 use clarity::types::StacksEpochId;
@@ -17,7 +18,7 @@ use clarity::vm::contracts::Contract;
 use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
 use clarity::vm::database::{ClarityDatabase, StoreType};
 use clarity::vm::diagnostic::{Diagnostic, Level};
-use clarity::vm::errors::Error;
+use clarity::vm::errors::{CheckErrors, Error};
 use clarity::vm::representations::SymbolicExpressionType::{Atom, List};
 use clarity::vm::representations::{Span, SymbolicExpression};
 use clarity::vm::types::{
@@ -40,6 +41,21 @@ pub const BLOCK_LIMIT_MAINNET: ExecutionCost = ExecutionCost {
     runtime: 5_000_000_000,
 };
 
+pub const BLOCK_LIMIT_MAINNET_20: ExecutionCost = ExecutionCost {
+    write_length: 15_000_000,
+    write_count: 7_750,
+    read_length: 100_000_000,
+    read_count: 7_750,
+    runtime: 5_000_000_000,
+};
+
+pub fn block_limit_for_epoch(epoch: StacksEpochId) -> ExecutionCost {
+    match epoch {
+        StacksEpochId::Epoch10 | StacksEpochId::Epoch20 => BLOCK_LIMIT_MAINNET_20,
+        _ => BLOCK_LIMIT_MAINNET,
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ClarityInterpreter {
     pub datastore: Datastore,
@@ -48,6 +64,16 @@ pub struct ClarityInterpreter {
     tx_sender: StandardPrincipalData,
     accounts: BTreeSet<String>,
     tokens: BTreeMap<String, BTreeMap<String, u128>>,
+    /// Balances as they were before their first change in each block, keyed by
+    /// (token, account), to be restored when the chain is rewound. Only the
+    /// changed balances are recorded, not the whole assets maps.
+    tokens_history: BTreeMap<u32, BTreeMap<(String, String), Option<u128>>>,
+    /// Costs left in the simnet block being mined. When set, every execution
+    /// is cost tracked against it, whether or not costs are reported.
+    pub block_budget: Option<ExecutionCost>,
+    /// Whether the last execution failed because it exceeded `block_budget`
+    pub block_limit_reached: bool,
+    pub pox: PoxState,
 }
 
 #[derive(Debug)]
@@ -68,7 +94,11 @@ impl ClarityInterpreter {
             datastore: Datastore::new(),
             accounts: BTreeSet::new(),
             tokens: BTreeMap::new(),
+            tokens_history: BTreeMap::new(),
             burn_datastore: BurnDatastore::new(constants),
+            block_budget: None,
+            block_limit_reached: false,
+            pox: PoxState::default(),
         }
     }
 
@@ -430,6 +460,7 @@ impl ClarityInterpreter {
         cost_track: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
     ) -> Result<ExecutionResult, String> {
+        self.block_limit_reached = false;
         let contract_id = contract.expect_resolved_contract_identifier(Some(&self.tx_sender));
         let snippet = contract.expect_in_memory_code_source();
         let mut contract_context =
@@ -445,18 +476,8 @@ impl ClarityInterpreter {
         conn.set_clarity_epoch_version(contract.epoch)
             .map_err(|e| e.to_string())?;
         conn.commit().map_err(|e| e.to_string())?;
-        let cost_tracker = if cost_track {
-            LimitedCostTracker::new(
-                false,
-                CHAIN_ID_TESTNET,
-                BLOCK_LIMIT_MAINNET.clone(),
-                &mut conn,
-                contract.epoch,
-            )
-            .map_err(|e| format!("failed to initialize cost tracker: {e}"))?
-        } else {
-            LimitedCostTracker::new_free()
-        };
+        let cost_tracker =
+            Self::new_cost_tracker(cost_track, &self.block_budget, &mut conn, contract.epoch)?;
         let mut global_context =
             GlobalContext::new(false, CHAIN_ID_TESTNET, conn, cost_tracker, contract.epoch);
 
@@ -541,6 +562,15 @@ impl ClarityInterpreter {
             eval_all(&contract_ast.expressions, &mut contract_context, g, None)
         });
 
+        if let Err(e) = &result {
+            self.block_limit_reached = self.block_budget.is_some() && is_block_limit_error(e);
+            if !self.block_limit_reached {
+                Self::consume_failed_execution_budget(
+                    &mut self.block_budget,
+                    &global_context.cost_track,
+                );
+            }
+        }
         let value = result.map_err(|e| {
             let err = format!("Runtime error while interpreting {}: {:?}", contract_id, e);
             if let Some(mut eval_hooks) = global_context.eval_hooks.take() {
@@ -553,7 +583,7 @@ impl ClarityInterpreter {
         })?;
 
         let mut cost = None;
-        if cost_track {
+        if cost_track || self.block_budget.is_some() {
             cost = Some(CostSynthesis::from_cost_tracker(&global_context.cost_track));
         }
 
@@ -638,6 +668,7 @@ impl ClarityInterpreter {
         for (account, token, value) in accounts_to_debit.drain(..) {
             self.debit_token(account, token, value);
         }
+        self.consume_block_budget(&execution_result);
 
         if contract_saved {
             let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
@@ -659,6 +690,7 @@ impl ClarityInterpreter {
         cost_track: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
     ) -> Result<ExecutionResult, String> {
+        self.block_limit_reached = false;
         let contract_id = contract.expect_resolved_contract_identifier(Some(&self.tx_sender));
         let snippet = contract.expect_in_memory_code_source();
         let mut contract_context =
@@ -674,18 +706,8 @@ impl ClarityInterpreter {
         conn.set_clarity_epoch_version(contract.epoch)
             .expect("failed to set epoch");
         conn.commit().expect("failed to commit");
-        let cost_tracker = if cost_track {
-            LimitedCostTracker::new(
-                false,
-                CHAIN_ID_TESTNET,
-                BLOCK_LIMIT_MAINNET.clone(),
-                &mut conn,
-                contract.epoch,
-            )
-            .map_err(|e| format!("failed to initialize cost tracker: {e}"))?
-        } else {
-            LimitedCostTracker::new_free()
-        };
+        let cost_tracker =
+            Self::new_cost_tracker(cost_track, &self.block_budget, &mut conn, contract.epoch)?;
         let mut global_context =
             GlobalContext::new(false, CHAIN_ID_TESTNET, conn, cost_tracker, contract.epoch);
 
@@ -781,6 +803,15 @@ impl ClarityInterpreter {
             initialize_contract(g, &mut contract_context, None, &analysis)
         });
 
+        if let Err(e) = &result {
+            self.block_limit_reached = self.block_budget.is_some() && is_block_limit_error(e);
+            if !self.block_limit_reached {
+                Self::consume_failed_execution_budget(
+                    &mut self.block_budget,
+                    &global_context.cost_track,
+                );
+            }
+        }
         let value = result.map_err(|e| {
             let err = format!("Runtime error while interpreting {}: {:?}", contract_id, e);
             if let Some(mut eval_hooks) = global_context.eval_hooks.take() {
@@ -793,7 +824,7 @@ impl ClarityInterpreter {
         })?;
 
         let mut cost = None;
-        if cost_track {
+        if cost_track || self.block_budget.is_some() {
             cost = Some(CostSynthesis::from_cost_tracker(&global_context.cost_track));
         }
 
@@ -878,6 +909,7 @@ impl ClarityInterpreter {
         for (account, token, value) in accounts_to_debit.drain(..) {
             self.debit_token(account, token, value);
         }
+        self.consume_block_budget(&execution_result);
 
         if contract_saved {
             let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
@@ -900,6 +932,7 @@ impl ClarityInterpreter {
         allow_private: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
     ) -> Result<ExecutionResult, String> {
+        self.block_limit_reached = false;
         let mut conn = ClarityDatabase::new(
             &mut self.datastore,
             &self.burn_datastore,
@@ -910,18 +943,8 @@ impl ClarityInterpreter {
         conn.set_clarity_epoch_version(epoch)
             .map_err(|e| e.to_string())?;
         conn.commit().map_err(|e| e.to_string())?;
-        let cost_tracker = if track_costs {
-            LimitedCostTracker::new(
-                false,
-                CHAIN_ID_TESTNET,
-                BLOCK_LIMIT_MAINNET.clone(),
-                &mut conn,
-                epoch,
-            )
-            .map_err(|e| format!("failed to initialize cost tracker: {e}"))?
-        } else {
-            LimitedCostTracker::new_free()
-        };
+        let cost_tracker =
+            Self::new_cost_tracker(track_costs, &self.block_budget, &mut conn, epoch)?;
 
         let mut global_context =
             GlobalContext::new(false, CHAIN_ID_TESTNET, conn, cost_tracker, epoch);
@@ -950,10 +973,8 @@ impl ClarityInterpreter {
 
             let mut args = vec![];
             for arg in raw_args {
-                let value =
-                    Value::deserialize_read(&mut arg.as_slice(), None, false).map_err(|_| {
-                        Error::Unchecked(clarity::vm::errors::CheckErrors::InvalidUTF8Encoding)
-                    })?;
+                let value = Value::deserialize_read(&mut arg.as_slice(), None, false)
+                    .map_err(|_| Error::Unchecked(CheckErrors::InvalidUTF8Encoding))?;
                 args.push(SymbolicExpression::atom_value(value));
             }
 
@@ -963,6 +984,15 @@ impl ClarityInterpreter {
             }
         });
 
+        if let Err(e) = &result {
+            self.block_limit_reached = self.block_budget.is_some() && is_block_limit_error(e);
+            if !self.block_limit_reached {
+                Self::consume_failed_execution_budget(
+                    &mut self.block_budget,
+                    &global_context.cost_track,
+                );
+            }
+        }
         let value = result.map_err(|e| {
             let err = format!("Runtime error while interpreting {}: {:?}", contract_id, e);
             if let Some(mut eval_hooks) = global_context.eval_hooks.take() {
@@ -975,7 +1005,7 @@ impl ClarityInterpreter {
         })?;
 
         let mut cost = None;
-        if track_costs {
+        if track_costs || self.block_budget.is_some() {
             cost = Some(CostSynthesis::from_cost_tracker(&global_context.cost_track));
         }
 
@@ -1011,6 +1041,7 @@ impl ClarityInterpreter {
         for (account, token, value) in accounts_to_debit.drain(..) {
             self.debit_token(account, token, value);
         }
+        self.consume_block_budget(&execution_result);
 
         Ok(execution_result)
     }
@@ -1158,6 +1189,94 @@ impl ClarityInterpreter {
         Ok(format!("→ {}: {} µSTX", recipient, final_balance))
     }
 
+    pub fn debit_stx_balance(
+        &mut self,
+        sender: PrincipalData,
+        amount: u64,
+        epoch: StacksEpochId,
+    ) -> Result<u128, String> {
+        let final_balance = {
+            let conn = ClarityDatabase::new(
+                &mut self.datastore,
+                &self.burn_datastore,
+                &self.burn_datastore,
+            );
+
+            let mut global_context = GlobalContext::new(
+                false,
+                CHAIN_ID_TESTNET,
+                conn,
+                LimitedCostTracker::new_free(),
+                epoch,
+            );
+            global_context.begin();
+            let mut cur_balance = global_context
+                .database
+                .get_stx_balance_snapshot(&sender)
+                .map_err(|e| e.to_string())?;
+            let available = cur_balance
+                .get_available_balance()
+                .map_err(|e| e.to_string())?;
+            if available < amount as u128 {
+                return Err(format!(
+                    "insufficient balance: {} has {} µSTX, {} µSTX needed",
+                    sender, available, amount
+                ));
+            }
+            cur_balance
+                .debit(amount as u128)
+                .map_err(|e| e.to_string())?;
+            let final_balance = cur_balance
+                .get_available_balance()
+                .map_err(|e| e.to_string())?;
+            cur_balance.save().map_err(|e| e.to_string())?;
+            global_context
+                .database
+                .decrement_ustx_liquid_supply(amount as u128)
+                .map_err(|e| e.to_string())?;
+            global_context.commit().map_err(|e| e.to_string())?;
+            final_balance
+        };
+        self.debit_token(sender.to_string(), "STX".to_string(), amount.into());
+        Ok(final_balance)
+    }
+
+    /// Unlock the STX of an account whose lock period is over, returns the unlocked amount
+    pub fn unlock_stx(
+        &mut self,
+        stacker: &PrincipalData,
+        epoch: StacksEpochId,
+    ) -> Result<u128, String> {
+        let conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        let mut global_context = GlobalContext::new(
+            false,
+            CHAIN_ID_TESTNET,
+            conn,
+            LimitedCostTracker::new_free(),
+            epoch,
+        );
+        global_context.begin();
+        let unlocked = {
+            let mut snapshot = global_context
+                .database
+                .get_stx_balance_snapshot(stacker)
+                .map_err(|e| e.to_string())?;
+            let unlocked = snapshot
+                .unlock_available_tokens_if_any()
+                .map_err(|e| e.to_string())?;
+            if unlocked > 0 {
+                snapshot.save().map_err(|e| e.to_string())?;
+            }
+            unlocked
+        };
+        global_context.commit().map_err(|e| e.to_string())?;
+        Ok(unlocked)
+    }
+
     pub fn set_tx_sender(&mut self, tx_sender: StandardPrincipalData) {
         self.tx_sender = tx_sender;
     }
@@ -1166,16 +1285,141 @@ impl ClarityInterpreter {
         self.tx_sender.clone()
     }
 
-    pub fn advance_chain_tip(&mut self, count: u32) -> u32 {
-        self.burn_datastore.advance_chain_tip(count);
+    /// Mine burn blocks, each with a Stacks block. With pox-4 deployed, the STX
+    /// locked until these blocks are unlocked in `epoch`.
+    pub fn advance_chain_tip(&mut self, count: u32, epoch: StacksEpochId) -> u32 {
+        let Some(config) = pox::read_config(self) else {
+            self.burn_datastore.advance_chain_tip(count);
+            return self.datastore.advance_chain_tip(count);
+        };
+
+        // with pox-4 deployed, the reward cycles are processed burn block by burn block
+        let mut pox_state = std::mem::take(&mut self.pox);
+        for _ in 0..count {
+            let burn_height = self.burn_datastore.get_burn_chain_height() + 1;
+            let payouts = pox::payout_addrs(&pox_state, &config, burn_height);
+            self.burn_datastore.set_pox_payout_addrs(payouts);
+            self.burn_datastore.advance_chain_tip(1);
+            self.datastore.advance_chain_tip(1);
+            pox::process_burn_block(self, &mut pox_state, &config, burn_height, epoch);
+        }
+        self.pox = pox_state;
+        self.datastore.get_current_block_height()
+    }
+
+    /// Mine Stacks blocks in the current tenure, without new burn blocks
+    pub fn advance_stacks_chain_tip(&mut self, count: u32) -> u32 {
+        self.burn_datastore.extend_tenure(count);
         self.datastore.advance_chain_tip(count)
     }
 
+    /// Move the chain tip back to `height`, dropping the blocks mined after it
+    pub fn rewind(&mut self, height: u32) -> Result<u32, String> {
+        self.datastore.rewind(height)?;
+        self.burn_datastore.rewind(height);
+
+        // undo the changes of the dropped blocks, latest first
+        let dropped = self.tokens_history.split_off(&(height + 1));
+        for changes in dropped.into_values().rev() {
+            for ((token, account), balance) in changes {
+                self.restore_balance(token, account, balance);
+            }
+        }
+
+        let burn_height = self.burn_datastore.get_burn_chain_height();
+        self.pox.config = None;
+        self.pox
+            .unlocks
+            .retain(|unlock| unlock.burn_height <= burn_height);
+        if let Some(config) = pox::read_config(self) {
+            let prepared_cycle = config.prepared_cycle(burn_height);
+            self.pox
+                .reward_sets
+                .retain(|cycle, _| *cycle <= prepared_cycle);
+        }
+        Ok(height)
+    }
+
+    pub fn has_contract(&self, contract_id: &QualifiedContractIdentifier) -> bool {
+        self.datastore.has_contract(contract_id)
+    }
+
     pub fn get_block_height(&mut self) -> u32 {
         self.datastore.get_current_block_height()
     }
 
+    pub fn get_burn_block_height(&self) -> u32 {
+        self.burn_datastore.get_burn_chain_height()
+    }
+
+    fn new_cost_tracker(
+        track_costs: bool,
+        block_budget: &Option<ExecutionCost>,
+        conn: &mut ClarityDatabase,
+        epoch: StacksEpochId,
+    ) -> Result<LimitedCostTracker, String> {
+        if !track_costs && block_budget.is_none() {
+            return Ok(LimitedCostTracker::new_free());
+        }
+        let limit = block_budget.clone().unwrap_or(BLOCK_LIMIT_MAINNET);
+        LimitedCostTracker::new(false, CHAIN_ID_TESTNET, limit, conn, epoch)
+            .map_err(|e| format!("failed to initialize cost tracker: {e}"))
+    }
+
+    fn consume_block_budget(&mut self, execution: &ExecutionResult) {
+        if let (Some(budget), Some(cost)) = (&mut self.block_budget, &execution.cost) {
+            // the execution was limited by the budget, it can not underflow
+            let _ = budget.sub(&cost.total);
+        }
+    }
+
+    // as on chain, an execution failing at runtime is included in the block and
+    // its cost is charged as well
+    fn consume_failed_execution_budget(
+        block_budget: &mut Option<ExecutionCost>,
+        cost_tracker: &LimitedCostTracker,
+    ) {
+        if let Some(budget) = block_budget {
+            let cost = CostSynthesis::from_cost_tracker(cost_tracker);
+            let _ = budget.sub(&cost.total);
+        }
+    }
+
+    fn save_tokens_history(&mut self, account: &str, token: &str) {
+        let height = self.datastore.get_current_block_height();
+        let balance = self
+            .tokens
+            .get(token)
+            .and_then(|balances| balances.get(account))
+            .copied();
+        self.tokens_history
+            .entry(height)
+            .or_default()
+            .entry((token.to_string(), account.to_string()))
+            .or_insert(balance);
+    }
+
+    fn restore_balance(&mut self, token: String, account: String, balance: Option<u128>) {
+        match balance {
+            Some(balance) => {
+                self.tokens
+                    .entry(token)
+                    .or_default()
+                    .insert(account, balance);
+            }
+            None => {
+                if let Entry::Occupied(mut balances) = self.tokens.entry(token) {
+                    balances.get_mut().remove(&account);
+                    if balances.get().is_empty() {
+                        balances.remove();
+                    }
+                }
+            }
+        }
+    }
+
     fn credit_token(&mut self, account: String, token: String, value: u128) {
+        self.save_tokens_history(&account, &token);
         self.accounts.insert(account.clone());
         match self.tokens.entry(token) {
             Entry::Occupied(balances) => {
@@ -1194,6 +1438,7 @@ impl ClarityInterpreter {
     }
 
     fn debit_token(&mut self, account: String, token: String, value: u128) {
+        self.save_tokens_history(&account, &token);
         self.accounts.insert(account.clone());
         match self.tokens.entry(token) {
             Entry::Occupied(balances) => {
@@ -1234,6 +1479,15 @@ impl ClarityInterpreter {
     }
 }
 
+/// Whether an execution error comes from exceeding the costs left in the block
+/// (`CostErrors::CostBalanceExceeded`, converted into a check error by the VM)
+fn is_block_limit_error(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Unchecked(CheckErrors::CostBalanceExceeded(..))
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1286,10 +1540,42 @@ mod tests {
             ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
         let count = 5;
         let initial_block_height = interpreter.get_block_height();
-        interpreter.advance_chain_tip(count);
+        interpreter.advance_chain_tip(count, DEFAULT_EPOCH);
         assert_eq!(interpreter.get_block_height(), initial_block_height + count);
     }
 
+    #[test]
+    fn test_advance_stacks_chain_tip() {
+        let mut interpreter =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        interpreter.advance_chain_tip(2, DEFAULT_EPOCH);
+        assert_eq!(interpreter.get_burn_block_height(), 2);
+
+        interpreter.advance_stacks_chain_tip(3);
+        assert_eq!(interpreter.get_block_height(), 5);
+        assert_eq!(interpreter.get_burn_block_height(), 2);
+
+        // the blocks of a tenure share the same burn block
+        let snippet = [
+            "{",
+            "  burn-3: (get-block-info? burnchain-header-hash u3),",
+            "  burn-4: (get-block-info? burnchain-header-hash u4),",
+            "  same-header: (is-eq (get-block-info? header-hash u3) (get-block-info? header-hash u4))",
+            "}",
+        ]
+        .join("\n");
+        let contract = ClarityContractBuilder::new().code_source(snippet).build();
+        let result = interpreter
+            .run(&contract, &mut None, false, None)
+            .expect("failed to run snippet");
+        let EvaluationResult::Snippet(result) = result.result else {
+            panic!("expected a snippet result");
+        };
+        let tuple = result.result.expect_tuple().unwrap();
+        assert_eq!(tuple.get("burn-3").unwrap(), tuple.get("burn-4").unwrap());
+        assert_eq!(tuple.get("same-header").unwrap(), &Value::Bool(false));
+    }
+
     #[test]
     fn test_get_assets_maps() {
         let mut interpreter =
@@ -1319,6 +1605,31 @@ mod tests {
         assert_eq!(tokens, ["STX"]);
     }
 
+    #[test]
+    fn test_rewind_restores_balances() {
+        let mut interpreter =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        let addr = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        interpreter.credit_token(addr.into(), "STX".into(), 1000);
+
+        interpreter.advance_chain_tip(1, DEFAULT_EPOCH);
+        interpreter.debit_token(addr.into(), "STX".into(), 100);
+        interpreter.debit_token(addr.into(), "STX".into(), 100);
+        interpreter.credit_token(addr.into(), "ft".into(), 10);
+        interpreter.advance_chain_tip(1, DEFAULT_EPOCH);
+        interpreter.debit_token(addr.into(), "STX".into(), 300);
+        interpreter.credit_token(addr.into(), "ft".into(), 5);
+
+        assert_eq!(interpreter.rewind(1), Ok(1));
+        assert_eq!(interpreter.get_balance_for_account(addr, "STX"), 800);
+        assert_eq!(interpreter.get_balance_for_account(addr, "ft"), 10);
+
+        // tokens credited after the new tip are dropped
+        assert_eq!(interpreter.rewind(0), Ok(0));
+        assert_eq!(interpreter.get_balance_for_account(addr, "STX"), 1000);
+        assert_eq!(interpreter.get_tokens(), ["STX"]);
+    }
+
     #[test]
     fn test_get_accounts() {
         let mut interpreter =
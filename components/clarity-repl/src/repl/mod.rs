@@ -3,6 +3,8 @@ pub mod clarity_values;
 pub mod datastore;
 pub mod diagnostic;
 pub mod interpreter;
+pub mod pox;
+pub mod profiler;
 pub mod session;
 pub mod settings;
 pub mod tracer;
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+
+use clarity::types::StacksEpochId;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, TupleData, Value};
+use clarity::vm::{ClarityVersion, EvaluationResult};
+
+use super::interpreter::ClarityInterpreter;
+use super::DEFAULT_EPOCH;
+
+pub const POX_4_CONTRACT: &str = "ST000000000000000000002AMW42H.pox-4";
+
+/// Number of PoX addresses rewarded by each block commit
+const OUTPUTS_PER_COMMIT: usize = 2;
+/// Amount committed by the simulated miner of each burn block
+const BLOCK_COMMIT_AMOUNT: u128 = 2000;
+
+/// Burnchain parameters, as configured in the pox-4 contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoxConfig {
+    pub first_burn_height: u32,
+    pub reward_cycle_length: u32,
+    pub prepare_length: u32,
+}
+
+impl PoxConfig {
+    pub fn reward_cycle(&self, burn_height: u32) -> u32 {
+        burn_height.saturating_sub(self.first_burn_height) / self.reward_cycle_length
+    }
+
+    pub fn reward_cycle_start(&self, reward_cycle: u32) -> u32 {
+        self.first_burn_height + reward_cycle * self.reward_cycle_length
+    }
+
+    fn position_in_cycle(&self, burn_height: u32) -> u32 {
+        burn_height.saturating_sub(self.first_burn_height) % self.reward_cycle_length
+    }
+
+    /// The prepare phase of a cycle spans the last blocks of the previous cycle
+    /// and its first block
+    pub fn is_in_prepare_phase(&self, burn_height: u32) -> bool {
+        let position = self.position_in_cycle(burn_height);
+        position == 0 || position > self.reward_cycle_length - self.prepare_length
+    }
+
+    /// Cycle whose reward set is calculated when this burn block is processed
+    pub fn prepared_cycle(&self, burn_height: u32) -> u32 {
+        self.reward_cycle(burn_height + self.prepare_length)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StxUnlock {
+    pub stacker: String,
+    pub amount: u128,
+    pub burn_height: u32,
+}
+
+/// State of the PoX simulation of a simnet session
+#[derive(Debug, Clone, Default)]
+pub struct PoxState {
+    /// Reward slots of each reward cycle: a PoX address appears once per slot it earned
+    pub reward_sets: BTreeMap<u32, Vec<TupleData>>,
+    pub unlocks: Vec<StxUnlock>,
+    /// Burnchain parameters, and the reward cycle in which they were read from pox-4
+    pub config: Option<(u32, PoxConfig)>,
+}
+
+/// Read the burnchain parameters from pox-4, None if the contract isn't deployed.
+/// They are only read again once the reward cycle they were read in is over.
+pub fn read_config(interpreter: &mut ClarityInterpreter) -> Option<PoxConfig> {
+    let burn_height = interpreter.get_burn_block_height();
+    if let Some((reward_cycle, config)) = &interpreter.pox.config {
+        if config.reward_cycle(burn_height) == *reward_cycle {
+            return Some(config.clone());
+        }
+    }
+
+    let contract_id = QualifiedContractIdentifier::parse(POX_4_CONTRACT).ok()?;
+    if !interpreter.has_contract(&contract_id) {
+        return None;
+    }
+    let config = read_pox_info(interpreter)?;
+    interpreter.pox.config = Some((config.reward_cycle(burn_height), config.clone()));
+    Some(config)
+}
+
+fn read_pox_info(interpreter: &mut ClarityInterpreter) -> Option<PoxConfig> {
+    let info = call_read_only(interpreter, "get-pox-info", &[])?
+        .expect_result_ok()
+        .ok()?
+        .expect_tuple()
+        .ok()?;
+    let get_u32 = |name: &str| -> Option<u32> {
+        let value = info.get(name).ok()?.clone().expect_u128().ok()?;
+        u32::try_from(value).ok()
+    };
+    let config = PoxConfig {
+        first_burn_height: get_u32("first-burnchain-block-height")?,
+        reward_cycle_length: get_u32("reward-cycle-length")?,
+        prepare_length: get_u32("prepare-cycle-length")?,
+    };
+    if config.reward_cycle_length == 0 || config.prepare_length > config.reward_cycle_length {
+        return None;
+    }
+    Some(config)
+}
+
+/// PoX payouts of a burn block: during the reward phase, the block commit is
+/// transferred to addresses of the reward set, otherwise it is burnt
+pub fn payout_addrs(
+    state: &PoxState,
+    config: &PoxConfig,
+    burn_height: u32,
+) -> (Vec<TupleData>, u128) {
+    if config.is_in_prepare_phase(burn_height) {
+        return (vec![], BLOCK_COMMIT_AMOUNT);
+    }
+    let reward_set = match state.reward_sets.get(&config.reward_cycle(burn_height)) {
+        Some(reward_set) if !reward_set.is_empty() => reward_set,
+        _ => return (vec![], BLOCK_COMMIT_AMOUNT),
+    };
+
+    let position = config.position_in_cycle(burn_height) as usize;
+    let addrs = (0..OUTPUTS_PER_COMMIT)
+        .map(|i| reward_set[(position * OUTPUTS_PER_COMMIT + i) % reward_set.len()].clone())
+        .collect();
+    (addrs, BLOCK_COMMIT_AMOUNT / OUTPUTS_PER_COMMIT as u128)
+}
+
+/// Process a new burn block: the reward set of the next cycle is calculated
+/// during the prepare phase, and locked STX are unlocked when a cycle starts.
+/// The STX are unlocked in `epoch`.
+pub fn process_burn_block(
+    interpreter: &mut ClarityInterpreter,
+    state: &mut PoxState,
+    config: &PoxConfig,
+    burn_height: u32,
+    epoch: StacksEpochId,
+) {
+    if config.is_in_prepare_phase(burn_height) {
+        let reward_cycle = config.prepared_cycle(burn_height);
+        if !state.reward_sets.contains_key(&reward_cycle) {
+            let reward_set = calculate_reward_set(interpreter, reward_cycle);
+            state.reward_sets.insert(reward_cycle, reward_set);
+        }
+    }
+
+    let reward_cycle = config.reward_cycle(burn_height);
+    if config.position_in_cycle(burn_height) == 0 && reward_cycle > 0 {
+        for stacker in ending_cycle_stackers(interpreter, reward_cycle - 1) {
+            let Ok(principal) = PrincipalData::parse(&stacker) else {
+                continue;
+            };
+            if let Ok(amount) = interpreter.unlock_stx(&principal, epoch) {
+                if amount > 0 {
+                    state.unlocks.push(StxUnlock {
+                        stacker,
+                        amount,
+                        burn_height,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Principals whose STX may unlock at the end of `reward_cycle`: the stackers of
+/// its pox-4 entries. The entries of pooled STX don't name their delegators, so
+/// the accounts of the session are checked as well.
+fn ending_cycle_stackers(interpreter: &mut ClarityInterpreter, reward_cycle: u32) -> Vec<String> {
+    let mut stackers: Vec<String> = reward_set_entries(interpreter, reward_cycle)
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .get("stacker")
+                .ok()
+                .and_then(|stacker| stacker.clone().expect_optional().ok().flatten())
+                .and_then(|stacker| stacker.expect_principal().ok())
+                .map(|stacker| stacker.to_string())
+        })
+        .collect();
+    stackers.extend(interpreter.get_accounts());
+    stackers.sort();
+    stackers.dedup();
+    stackers
+}
+
+/// Entries registered in pox-4 for a reward cycle
+fn reward_set_entries(interpreter: &mut ClarityInterpreter, reward_cycle: u32) -> Vec<TupleData> {
+    let cycle = Value::UInt(reward_cycle.into());
+    let size = call_read_only(interpreter, "get-reward-set-size", &[cycle.clone()])
+        .and_then(|size| size.expect_u128().ok())
+        .unwrap_or(0);
+    (0..size)
+        .filter_map(|index| {
+            call_read_only(
+                interpreter,
+                "get-reward-set-pox-address",
+                &[cycle.clone(), Value::UInt(index)],
+            )
+            .and_then(|entry| entry.expect_optional().ok().flatten())
+            .and_then(|entry| entry.expect_tuple().ok())
+        })
+        .collect()
+}
+
+/// Aggregate the entries registered in pox-4 by PoX address, and give each
+/// address one slot per stacking threshold it reaches
+pub fn calculate_reward_set(
+    interpreter: &mut ClarityInterpreter,
+    reward_cycle: u32,
+) -> Vec<TupleData> {
+    let threshold = call_read_only(interpreter, "get-stacking-minimum", &[])
+        .and_then(|threshold| threshold.expect_u128().ok())
+        .unwrap_or(0)
+        .max(1);
+
+    let mut stacked: Vec<(TupleData, u128)> = vec![];
+    for entry in reward_set_entries(interpreter, reward_cycle) {
+        let pox_addr = entry
+            .get("pox-addr")
+            .ok()
+            .and_then(|addr| addr.clone().expect_tuple().ok());
+        let total_ustx = entry
+            .get("total-ustx")
+            .ok()
+            .and_then(|total| total.clone().expect_u128().ok());
+        let (Some(pox_addr), Some(total_ustx)) = (pox_addr, total_ustx) else {
+            continue;
+        };
+        match stacked.iter_mut().find(|(addr, _)| *addr == pox_addr) {
+            Some((_, total)) => *total += total_ustx,
+            None => stacked.push((pox_addr, total_ustx)),
+        }
+    }
+
+    let mut reward_set = vec![];
+    for (pox_addr, total_ustx) in stacked.into_iter() {
+        for _ in 0..(total_ustx / threshold) {
+            reward_set.push(pox_addr.clone());
+        }
+    }
+    reward_set
+}
+
+fn call_read_only(
+    interpreter: &mut ClarityInterpreter,
+    method: &str,
+    args: &[Value],
+) -> Option<Value> {
+    let contract_id = QualifiedContractIdentifier::parse(POX_4_CONTRACT).ok()?;
+    let args = args
+        .iter()
+        .map(|arg| arg.serialize_to_vec())
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let execution = interpreter
+        .call_contract_fn(
+            &contract_id,
+            method,
+            &args,
+            DEFAULT_EPOCH,
+            ClarityVersion::Clarity2,
+            false,
+            false,
+            None,
+        )
+        .ok()?;
+    match execution.result {
+        EvaluationResult::Snippet(result) => Some(result.result),
+        EvaluationResult::Contract(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PoxConfig {
+        PoxConfig {
+            first_burn_height: 0,
+            reward_cycle_length: 10,
+            prepare_length: 3,
+        }
+    }
+
+    fn pox_addr(byte: u8) -> TupleData {
+        TupleData::from_data(vec![
+            ("version".into(), Value::buff_from(vec![0x00]).unwrap()),
+            (
+                "hashbytes".into(),
+                Value::buff_from(vec![byte; 20]).unwrap(),
+            ),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reward_cycle_phases() {
+        let config = config();
+        assert_eq!(config.reward_cycle(9), 0);
+        assert_eq!(config.reward_cycle(10), 1);
+        assert_eq!(config.reward_cycle_start(2), 20);
+
+        let prepare_phase = (0..=20)
+            .filter(|height| config.is_in_prepare_phase(*height))
+            .collect::<Vec<_>>();
+        assert_eq!(prepare_phase, vec![0, 8, 9, 10, 18, 19, 20]);
+        assert_eq!(config.prepared_cycle(8), 1);
+        assert_eq!(config.prepared_cycle(10), 1);
+    }
+
+    #[test]
+    fn test_payout_addrs() {
+        let config = config();
+        let mut state = PoxState::default();
+        assert_eq!(payout_addrs(&state, &config, 12), (vec![], 2000));
+
+        state
+            .reward_sets
+            .insert(1, vec![pox_addr(1), pox_addr(1), pox_addr(2)]);
+        assert_eq!(payout_addrs(&state, &config, 10), (vec![], 2000));
+        assert_eq!(
+            payout_addrs(&state, &config, 11),
+            (vec![pox_addr(2), pox_addr(1)], 1000)
+        );
+        assert_eq!(
+            payout_addrs(&state, &config, 12),
+            (vec![pox_addr(1), pox_addr(2)], 1000)
+        );
+    }
+}
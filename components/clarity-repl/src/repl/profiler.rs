@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::errors::Error;
+use clarity::vm::functions::define::DefineFunctions;
+use clarity::vm::functions::NativeFunctions;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::{
+    contexts::{ContractContext, Environment, LocalContext},
+    types::Value,
+    ClarityVersion, EvalHook, ExecutionResult, SymbolicExpression, SymbolicExpressionType,
+};
+use serde_json::json;
+
+pub const COST_DIMENSIONS: [&str; 5] = [
+    "runtime",
+    "read_count",
+    "read_length",
+    "write_count",
+    "write_length",
+];
+
+struct Frame {
+    contract_id: QualifiedContractIdentifier,
+    expr_id: u64,
+    name: String,
+    start: ExecutionCost,
+    children: ExecutionCost,
+    /// contract-call? frames are named after their callee once it's known
+    pending_callee: Option<String>,
+}
+
+/// Costs spent in each call stack (frames separated by `;`), excluding the
+/// costs of the nested frames.
+#[derive(Debug, Clone, Default)]
+pub struct CostProfile {
+    pub name: String,
+    pub stacks: BTreeMap<String, ExecutionCost>,
+}
+
+/// `EvalHook` recording the `ExecutionCost` spent in each user function and
+/// native function call, including cross-contract calls.
+/// Costs must be tracked (`cost_track`) for the profile to be meaningful.
+pub struct CostProfiler {
+    stack: Vec<Frame>,
+    profile: CostProfile,
+}
+
+impl CostProfiler {
+    pub fn new(name: String) -> CostProfiler {
+        CostProfiler {
+            stack: vec![],
+            profile: CostProfile {
+                name,
+                stacks: BTreeMap::new(),
+            },
+        }
+    }
+
+    pub fn profile(&self) -> &CostProfile {
+        &self.profile
+    }
+
+    pub fn into_profile(self) -> CostProfile {
+        self.profile
+    }
+}
+
+impl CostProfile {
+    pub fn total(&self) -> ExecutionCost {
+        let mut total = ExecutionCost::zero();
+        for cost in self.stacks.values() {
+            add_cost(&mut total, cost);
+        }
+        total
+    }
+
+    /// Folded stacks (`frame;frame;frame value`), as consumed by flamegraph tools
+    pub fn to_folded_stacks(&self, dimension: &str) -> String {
+        let mut folded = String::new();
+        for (stack, cost) in self.stacks.iter() {
+            let value = cost_dimension(cost, dimension);
+            if value > 0 {
+                folded.push_str(&format!("{} {}\n", stack, value));
+            }
+        }
+        folded
+    }
+
+    /// Speedscope file, with one profile per cost dimension
+    pub fn to_speedscope(&self) -> serde_json::Value {
+        let mut frames: Vec<String> = vec![];
+        let mut samples = vec![];
+        for stack in self.stacks.keys() {
+            let sample = stack
+                .split(';')
+                .map(|name| match frames.iter().position(|f| f == name) {
+                    Some(i) => i,
+                    None => {
+                        frames.push(name.to_string());
+                        frames.len() - 1
+                    }
+                })
+                .collect::<Vec<_>>();
+            samples.push(sample);
+        }
+
+        let profiles = COST_DIMENSIONS
+            .iter()
+            .map(|dimension| {
+                let weights = self
+                    .stacks
+                    .values()
+                    .map(|cost| cost_dimension(cost, dimension))
+                    .collect::<Vec<_>>();
+                json!({
+                    "type": "sampled",
+                    "name": format!("{} ({})", self.name, dimension),
+                    "unit": "none",
+                    "startValue": 0,
+                    "endValue": weights.iter().sum::<u64>(),
+                    "samples": samples,
+                    "weights": weights,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": "https://www.speedscope.app/file-format-schema.json",
+            "name": self.name,
+            "exporter": "clarinet",
+            "activeProfileIndex": 0,
+            "shared": {
+                "frames": frames.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+            },
+            "profiles": profiles,
+        })
+    }
+}
+
+impl EvalHook for CostProfiler {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        let contract_id = env.contract_context.contract_identifier.clone();
+
+        // first expression evaluated in the callee of a dynamic contract-call?
+        if let Some(frame) = self.stack.last_mut() {
+            if frame.contract_id != contract_id {
+                if let Some(function) = frame.pending_callee.take() {
+                    frame.name = format!("{}::{}", contract_id.name, function);
+                }
+            }
+        }
+
+        let SymbolicExpressionType::List(ref list) = expr.expr else {
+            return;
+        };
+        let Some((name, pending_callee)) = frame_name(env.contract_context, list) else {
+            return;
+        };
+        self.stack.push(Frame {
+            contract_id,
+            expr_id: expr.id,
+            name,
+            start: env.global_context.cost_track.get_total(),
+            children: ExecutionCost::zero(),
+            pending_callee,
+        });
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+        _res: &Result<Value, Error>,
+    ) {
+        let is_top_frame = self.stack.last().map_or(false, |frame| {
+            frame.expr_id == expr.id
+                && frame.contract_id == env.contract_context.contract_identifier
+        });
+        if !is_top_frame {
+            return;
+        }
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+
+        let spent = sub_cost(&env.global_context.cost_track.get_total(), &frame.start);
+        let self_cost = sub_cost(&spent, &frame.children);
+        if let Some(parent) = self.stack.last_mut() {
+            add_cost(&mut parent.children, &spent);
+        }
+
+        let mut path = self
+            .stack
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        path.push(&frame.name);
+        let entry = self
+            .profile
+            .stacks
+            .entry(path.join(";"))
+            .or_insert(ExecutionCost::zero());
+        add_cost(entry, &self_cost);
+    }
+
+    fn did_complete(&mut self, _result: Result<&mut ExecutionResult, String>) {}
+}
+
+/// Name of the frame of a function call and, for dynamic contract calls, the
+/// called function (the callee contract is only known once it is entered)
+fn frame_name(
+    contract_context: &ContractContext,
+    list: &[SymbolicExpression],
+) -> Option<(String, Option<String>)> {
+    let (head, args) = list.split_first()?;
+    let name = head.match_atom()?;
+    if DefineFunctions::lookup_by_name(name).is_some() {
+        return None;
+    }
+    match NativeFunctions::lookup_by_name_at_version(name, &ClarityVersion::latest()) {
+        Some(NativeFunctions::ContractCall) => {
+            let function = args
+                .get(1)
+                .and_then(|f| f.match_atom())
+                .map(|f| f.to_string())
+                .unwrap_or("?".to_string());
+            let callee = match args.first().and_then(|c| c.match_literal_value()) {
+                Some(Value::Principal(PrincipalData::Contract(callee))) => {
+                    Some(format!("{}::{}", callee.name, function))
+                }
+                _ => None,
+            };
+            match callee {
+                Some(callee) => Some((callee, None)),
+                None => Some(("contract-call?".to_string(), Some(function))),
+            }
+        }
+        Some(native) => Some((native.get_name().to_string(), None)),
+        None if contract_context.lookup_function(name).is_some() => Some((
+            format!("{}::{}", contract_context.contract_identifier.name, name),
+            None,
+        )),
+        None => None,
+    }
+}
+
+pub fn cost_dimension(cost: &ExecutionCost, dimension: &str) -> u64 {
+    match dimension {
+        "read_count" => cost.read_count,
+        "read_length" => cost.read_length,
+        "write_count" => cost.write_count,
+        "write_length" => cost.write_length,
+        _ => cost.runtime,
+    }
+}
+
+fn add_cost(total: &mut ExecutionCost, cost: &ExecutionCost) {
+    total.runtime = total.runtime.saturating_add(cost.runtime);
+    total.read_count = total.read_count.saturating_add(cost.read_count);
+    total.read_length = total.read_length.saturating_add(cost.read_length);
+    total.write_count = total.write_count.saturating_add(cost.write_count);
+    total.write_length = total.write_length.saturating_add(cost.write_length);
+}
+
+fn sub_cost(a: &ExecutionCost, b: &ExecutionCost) -> ExecutionCost {
+    ExecutionCost {
+        runtime: a.runtime.saturating_sub(b.runtime),
+        read_count: a.read_count.saturating_sub(b.read_count),
+        read_length: a.read_length.saturating_sub(b.read_length),
+        write_count: a.write_count.saturating_sub(b.write_count),
+        write_length: a.write_length.saturating_sub(b.write_length),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(runtime: u64, read_count: u64) -> ExecutionCost {
+        ExecutionCost {
+            runtime,
+            read_count,
+            ..ExecutionCost::zero()
+        }
+    }
+
+    #[test]
+    fn test_folded_stacks_and_speedscope() {
+        let mut profile = CostProfile {
+            name: "transfer".to_string(),
+            stacks: BTreeMap::new(),
+        };
+        profile
+            .stacks
+            .insert("token::transfer".to_string(), cost(100, 0));
+        profile
+            .stacks
+            .insert("token::transfer;map-get?".to_string(), cost(20, 1));
+
+        assert_eq!(
+            profile.to_folded_stacks("runtime"),
+            "token::transfer 100\ntoken::transfer;map-get? 20\n"
+        );
+        assert_eq!(
+            profile.to_folded_stacks("read_count"),
+            "token::transfer;map-get? 1\n"
+        );
+        assert_eq!(profile.total().runtime, 120);
+
+        let speedscope = profile.to_speedscope();
+        assert_eq!(speedscope["shared"]["frames"].as_array().unwrap().len(), 2);
+        assert_eq!(speedscope["profiles"][0]["samples"], json!([[0], [0, 1]]));
+        assert_eq!(speedscope["profiles"][0]["weights"], json!([100, 20]));
+    }
+}
@@ -1,8 +1,10 @@
 use super::boot::{STACKS_BOOT_CODE_MAINNET, STACKS_BOOT_CODE_TESTNET};
 use super::clarity_values::uint8_to_string;
 use super::diagnostic::output_diagnostic;
+use super::interpreter::block_limit_for_epoch;
 use super::{ClarityCodeSource, ClarityContract, ClarityInterpreter, ContractDeployer};
 use crate::analysis::coverage::TestCoverageReport;
+use crate::repl::profiler::{CostProfile, CostProfiler};
 use crate::repl::Settings;
 use crate::utils;
 use clarity::codec::StacksMessageCodec;
@@ -96,6 +98,17 @@ pub struct CostsReport {
     pub cost_result: CostSynthesis,
 }
 
+/// State of a session branch that isn't checked out
+#[derive(Clone, Debug)]
+struct SessionBranch {
+    interpreter: ClarityInterpreter,
+    contracts: BTreeMap<QualifiedContractIdentifier, ParsedContract>,
+    nonces: HashMap<String, u64>,
+    nonces_history: BTreeMap<u32, HashMap<String, u64>>,
+    current_epoch: StacksEpochId,
+    epochs_history: BTreeMap<u32, StacksEpochId>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Session {
     pub settings: SessionSettings,
@@ -104,13 +117,57 @@ pub struct Session {
     api_reference: HashMap<String, String>,
     pub coverage_reports: Vec<TestCoverageReport>,
     pub costs_reports: Vec<CostsReport>,
+    /// Record a cost profile of the contract calls tracking costs
+    pub profile_costs: bool,
+    pub cost_profiles: Vec<CostProfile>,
+    /// Nonce of the next transaction of each sender
+    pub nonces: HashMap<String, u64>,
+    /// Nonces as they were before their first increment in each block, to be
+    /// restored when the chain is rewound
+    nonces_history: BTreeMap<u32, HashMap<String, u64>>,
     pub show_costs: bool,
     pub executed: Vec<String>,
     pub current_epoch: StacksEpochId,
+    /// Epoch as it was before its first update in each block
+    epochs_history: BTreeMap<u32, StacksEpochId>,
+    pub current_branch: String,
+    branches: BTreeMap<String, SessionBranch>,
     keywords_reference: HashMap<String, String>,
 }
 
 impl Session {
+    /// Console commands handled by `handle_command`
+    #[cfg(feature = "cli")]
+    pub const COMMANDS: &'static [&'static str] = &[
+        "::help",
+        "::functions",
+        "::keywords",
+        "::describe",
+        "::mint_stx",
+        "::set_tx_sender",
+        "::get_assets_maps",
+        "::get_costs",
+        "::get_contracts",
+        "::get_block_height",
+        "::advance_chain_tip",
+        "::get_burn_block_height",
+        "::advance_burn_chain_tip",
+        "::rewind",
+        "::fork",
+        "::checkout",
+        "::toggle_costs",
+        "::toggle_timings",
+        "::get_epoch",
+        "::set_epoch",
+        "::encode",
+        "::decode",
+        "::debug",
+        "::trace",
+        "::profile",
+        "::reload",
+        "::read",
+    ];
+
     pub fn new(settings: SessionSettings) -> Session {
         let tx_sender = {
             let address = match settings.initial_deployer {
@@ -127,10 +184,17 @@ impl Session {
             api_reference: build_api_reference(),
             coverage_reports: vec![],
             costs_reports: vec![],
+            profile_costs: false,
+            cost_profiles: vec![],
+            nonces: HashMap::new(),
+            nonces_history: BTreeMap::new(),
             show_costs: false,
             settings,
             executed: Vec::new(),
             current_epoch: StacksEpochId::Epoch2_05,
+            epochs_history: BTreeMap::new(),
+            current_branch: "main".to_string(),
+            branches: BTreeMap::new(),
             keywords_reference: clarity_keywords(),
         }
     }
@@ -214,6 +278,15 @@ impl Session {
             cmd if cmd.starts_with("::advance_chain_tip") => {
                 self.parse_and_advance_chain_tip(&mut output, cmd)
             }
+            cmd if cmd.starts_with("::get_burn_block_height") => {
+                self.get_burn_block_height(&mut output)
+            }
+            cmd if cmd.starts_with("::advance_burn_chain_tip") => {
+                self.parse_and_advance_burn_chain_tip(&mut output, cmd)
+            }
+            cmd if cmd.starts_with("::rewind") => self.parse_and_rewind(&mut output, cmd),
+            cmd if cmd.starts_with("::fork") => self.parse_and_fork(&mut output, cmd),
+            cmd if cmd.starts_with("::checkout") => self.parse_and_checkout(&mut output, cmd),
             cmd if cmd.starts_with("::toggle_costs") => self.toggle_costs(&mut output),
             cmd if cmd.starts_with("::toggle_timings") => self.toggle_timings(&mut output),
             cmd if cmd.starts_with("::get_epoch") => self.get_epoch(&mut output),
@@ -226,6 +299,8 @@ impl Session {
             #[cfg(feature = "cli")]
             cmd if cmd.starts_with("::trace") => self.trace(&mut output, cmd),
             #[cfg(feature = "cli")]
+            cmd if cmd.starts_with("::profile") => self.profile(&mut output, cmd),
+            #[cfg(feature = "cli")]
             cmd if cmd.starts_with("::reload") => reload = true,
             #[cfg(feature = "cli")]
             cmd if cmd.starts_with("::read") => self.read(&mut output, cmd),
@@ -435,6 +510,44 @@ impl Session {
         };
     }
 
+    #[cfg(feature = "cli")]
+    pub fn profile(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let usage = "Usage: ::profile [--output <path>] <expr>";
+        let args = match cmd.split_once(' ') {
+            Some((_, args)) => args.trim_start(),
+            _ => return output.push(red!(usage)),
+        };
+        // without --output, the speedscope profile is printed instead of written
+        let (path, snippet) = match args.strip_prefix("--output") {
+            Some(args) => match args.trim_start().split_once(' ') {
+                Some((path, snippet)) => (Some(path), snippet),
+                None => return output.push(red!(usage)),
+            },
+            None => (None, args),
+        };
+
+        let mut profiler = CostProfiler::new(snippet.to_string());
+        if let Err(diagnostics) = self.eval(snippet.to_string(), Some(vec![&mut profiler]), true) {
+            let lines = snippet.lines();
+            let formatted_lines: Vec<String> = lines.map(|l| l.to_string()).collect();
+            for d in diagnostics {
+                output.append(&mut output_diagnostic(&d, "<snippet>", &formatted_lines));
+            }
+            return;
+        }
+
+        let profile = profiler.into_profile();
+        output.push(profile.to_folded_stacks("runtime"));
+        let speedscope = profile.to_speedscope().to_string();
+        match path {
+            Some(path) => match std::fs::write(path, speedscope) {
+                Ok(_) => output.push(green!(format!("speedscope profile written to {}", path))),
+                Err(e) => output.push(red!(format!("unable to write {}: {}", path, e))),
+            },
+            None => output.push(speedscope),
+        }
+    }
+
     #[cfg(feature = "cli")]
     pub fn start(&mut self) -> Result<(String, Vec<(ContractAnalysis, String, String)>), String> {
         let mut output_err = Vec::<String>::new();
@@ -555,6 +668,7 @@ impl Session {
         args: &[String],
         sender: &str,
         test_name: String,
+        eval_hooks: Option<Vec<&mut dyn EvalHook>>,
     ) -> Result<(ExecutionResult, QualifiedContractIdentifier), Vec<Diagnostic>> {
         let initial_tx_sender = self.get_tx_sender();
         // Handle fully qualified contract_id and sugared syntax
@@ -564,7 +678,7 @@ impl Session {
             format!("{}.{}", initial_tx_sender, contract)
         };
 
-        let mut hooks: Vec<&mut dyn EvalHook> = vec![];
+        let mut hooks: Vec<&mut dyn EvalHook> = eval_hooks.unwrap_or_default();
         let mut coverage = TestCoverageReport::new(test_name.clone());
         hooks.push(&mut coverage);
 
@@ -635,6 +749,11 @@ impl Session {
         if track_coverage {
             hooks.push(&mut coverage);
         }
+        let profile_costs = track_costs && self.profile_costs;
+        let mut profiler = CostProfiler::new(format!("{} {}.{}", test_name, contract, method));
+        if profile_costs {
+            hooks.push(&mut profiler);
+        }
 
         let clarity_version = ClarityVersion::default_for_epoch(self.current_epoch);
 
@@ -665,15 +784,20 @@ impl Session {
         if track_coverage {
             self.coverage_reports.push(coverage);
         }
+        if profile_costs {
+            self.cost_profiles.push(profiler.into_profile());
+        }
 
-        if let Some(ref cost) = execution.cost {
-            self.costs_reports.push(CostsReport {
-                test_name,
-                contract_id: contract_id_str,
-                method: method.to_string(),
-                args: args.iter().map(|a| uint8_to_string(a)).collect(),
-                cost_result: cost.clone(),
-            });
+        if track_costs {
+            if let Some(ref cost) = execution.cost {
+                self.costs_reports.push(CostsReport {
+                    test_name,
+                    contract_id: contract_id_str,
+                    method: method.to_string(),
+                    args: args.iter().map(|a| uint8_to_string(a)).collect(),
+                    cost_result: cost.clone(),
+                });
+            }
         }
 
         Ok(execution)
@@ -793,6 +917,29 @@ impl Session {
             "{}",
             help_colour.paint("::advance_chain_tip <count>\t\tSimulate mining of <count> blocks")
         ));
+        output.push(format!(
+            "{}",
+            help_colour.paint("::get_burn_block_height\t\tGet current burn block height")
+        ));
+        output.push(format!(
+            "{}",
+            help_colour
+                .paint("::advance_burn_chain_tip <count>\tSimulate mining of <count> burn blocks")
+        ));
+        output.push(format!(
+            "{}",
+            help_colour.paint(
+                "::rewind <height>\t\t\tReset the chain tip to <height>, dropping later blocks"
+            )
+        ));
+        output.push(format!(
+            "{}",
+            help_colour.paint("::fork <name>\t\t\t\tCreate the branch <name> and switch to it")
+        ));
+        output.push(format!(
+            "{}",
+            help_colour.paint("::checkout <name>\t\t\tSwitch to the branch <name>")
+        ));
         output.push(format!(
             "{}",
             help_colour.paint("::set_epoch <2.0> | <2.05> | <2.1>\tUpdate the current epoch")
@@ -814,6 +961,12 @@ impl Session {
             "{}",
             help_colour.paint("::trace <expr>\t\t\t\tGenerate an execution trace for <expr>")
         ));
+        output.push(format!(
+            "{}",
+            help_colour.paint(
+                "::profile [--output <path>] <expr>\tProfile the costs of <expr> per call frame"
+            )
+        ));
         output.push(format!(
             "{}",
             help_colour.paint("::reload \t\t\t\tReload the existing contract(s) in the session")
@@ -863,8 +1016,182 @@ impl Session {
         )));
     }
 
+    #[cfg(feature = "cli")]
+    fn parse_and_advance_burn_chain_tip(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::advance_burn_chain_tip <count>"));
+            return;
+        }
+
+        let count = match args[1].parse::<u32>() {
+            Ok(count) => count,
+            _ => {
+                output.push(red!("Unable to parse count"));
+                return;
+            }
+        };
+
+        let new_burn_height = self.advance_burn_chain_tip(count);
+        let new_height = self.interpreter.get_block_height();
+        output.push(green!(format!(
+            "{} burn blocks simulated, new burn height: {}, new height: {}",
+            count, new_burn_height, new_height
+        )));
+    }
+
+    /// Mine Stacks blocks. Starting with epoch 3.0, they are mined in the current
+    /// tenure, otherwise each of them comes with a new burn block.
     pub fn advance_chain_tip(&mut self, count: u32) -> u32 {
-        self.interpreter.advance_chain_tip(count)
+        if self.current_epoch >= StacksEpochId::Epoch30 {
+            self.interpreter.advance_stacks_chain_tip(count)
+        } else {
+            self.interpreter
+                .advance_chain_tip(count, self.current_epoch)
+        }
+    }
+
+    /// Mine burn blocks, each of them starting a new tenure with one Stacks block
+    pub fn advance_burn_chain_tip(&mut self, count: u32) -> u32 {
+        self.interpreter
+            .advance_chain_tip(count, self.current_epoch);
+        self.interpreter.get_burn_block_height()
+    }
+
+    #[cfg(feature = "cli")]
+    fn parse_and_rewind(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::rewind <height>"));
+            return;
+        }
+
+        let height = match args[1].parse::<u32>() {
+            Ok(height) => height,
+            _ => {
+                output.push(red!("Unable to parse height"));
+                return;
+            }
+        };
+
+        match self.rewind(height) {
+            Ok(height) => output.push(green!(format!(
+                "Chain tip rewound, new height: {}, new burn height: {}",
+                height,
+                self.interpreter.get_burn_block_height()
+            ))),
+            Err(e) => output.push(red!(e)),
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    fn parse_and_fork(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::fork <name>"));
+            return;
+        }
+
+        match self.fork(args[1]) {
+            Ok(()) => output.push(green!(format!("Switched to new branch {}", args[1]))),
+            Err(e) => output.push(red!(e)),
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    fn parse_and_checkout(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::checkout <name>"));
+            return;
+        }
+
+        match self.checkout(args[1]) {
+            Ok(()) => output.push(green!(format!(
+                "Switched to branch {}, current height: {}",
+                args[1],
+                self.interpreter.get_block_height()
+            ))),
+            Err(e) => output.push(red!(e)),
+        }
+    }
+
+    /// Reset the chain tip to `height`. The state written in later blocks,
+    /// including the contracts deployed in them, the nonces and the epoch, is dropped.
+    pub fn rewind(&mut self, height: u32) -> Result<u32, String> {
+        self.interpreter.rewind(height)?;
+        let interpreter = &self.interpreter;
+        self.contracts
+            .retain(|contract_id, _| interpreter.has_contract(contract_id));
+
+        // undo the changes of the dropped blocks, latest first
+        let dropped_nonces = self.nonces_history.split_off(&(height + 1));
+        for nonces in dropped_nonces.into_values().rev() {
+            self.nonces.extend(nonces);
+        }
+        let dropped_epochs = self.epochs_history.split_off(&(height + 1));
+        if let Some(epoch) = dropped_epochs.into_values().next() {
+            self.current_epoch = epoch;
+        }
+        Ok(height)
+    }
+
+    /// Create a branch from the current state and switch to it, the current
+    /// branch is kept as it is
+    pub fn fork(&mut self, name: &str) -> Result<(), String> {
+        if name == self.current_branch || self.branches.contains_key(name) {
+            return Err(format!("branch {} already exists", name));
+        }
+        let branch = self.save_branch();
+        self.branches.insert(self.current_branch.clone(), branch);
+        self.current_branch = name.to_string();
+        Ok(())
+    }
+
+    /// Switch to an existing branch, the current branch is kept as it is
+    pub fn checkout(&mut self, name: &str) -> Result<(), String> {
+        if name == self.current_branch {
+            return Ok(());
+        }
+        let Some(branch) = self.branches.remove(name) else {
+            return Err(format!(
+                "unknown branch {}, available branches: {}",
+                name,
+                self.get_branches().join(", ")
+            ));
+        };
+        let current = self.save_branch();
+        self.branches.insert(self.current_branch.clone(), current);
+        self.interpreter = branch.interpreter;
+        self.contracts = branch.contracts;
+        self.nonces = branch.nonces;
+        self.nonces_history = branch.nonces_history;
+        self.current_epoch = branch.current_epoch;
+        self.epochs_history = branch.epochs_history;
+        self.current_branch = name.to_string();
+        Ok(())
+    }
+
+    pub fn get_branches(&self) -> Vec<String> {
+        let mut branches: Vec<String> = self.branches.keys().cloned().collect();
+        branches.push(self.current_branch.clone());
+        branches.sort();
+        branches
+    }
+
+    fn save_branch(&self) -> SessionBranch {
+        SessionBranch {
+            interpreter: self.interpreter.clone(),
+            contracts: self.contracts.clone(),
+            nonces: self.nonces.clone(),
+            nonces_history: self.nonces_history.clone(),
+            current_epoch: self.current_epoch,
+            epochs_history: self.epochs_history.clone(),
+        }
     }
 
     #[cfg(feature = "cli")]
@@ -888,6 +1215,56 @@ impl Session {
         output.push(green!(format!("tx-sender switched to {}", tx_sender)));
     }
 
+    /// Start a simnet block: until `end_block`, executions are cost tracked
+    /// against the block limit of the current epoch
+    pub fn begin_block(&mut self) {
+        self.interpreter.block_budget = Some(block_limit_for_epoch(self.current_epoch));
+    }
+
+    pub fn end_block(&mut self) {
+        self.interpreter.block_budget = None;
+    }
+
+    pub fn is_block_empty(&self) -> bool {
+        self.interpreter.block_budget == Some(block_limit_for_epoch(self.current_epoch))
+    }
+
+    pub fn get_nonce(&self, sender: &str) -> u64 {
+        self.nonces.get(sender).copied().unwrap_or(0)
+    }
+
+    pub fn increment_nonce(&mut self, sender: &str) {
+        let height = self.interpreter.get_block_height();
+        let nonce = self.get_nonce(sender);
+        self.nonces_history
+            .entry(height)
+            .or_default()
+            .entry(sender.to_string())
+            .or_insert(nonce);
+        self.nonces.insert(sender.to_string(), nonce + 1);
+    }
+
+    /// Debit the transaction fee from the sender's STX balance
+    pub fn pay_fee(&mut self, sender: &str, fee: u64) -> Result<(), String> {
+        if fee == 0 {
+            return Ok(());
+        }
+        let sender = PrincipalData::parse(sender).map_err(|e| e.to_string())?;
+        self.interpreter
+            .debit_stx_balance(sender, fee, self.current_epoch)?;
+        Ok(())
+    }
+
+    /// Credit back the fee of a transaction that was not included in the block
+    pub fn refund_fee(&mut self, sender: &str, fee: u64) -> Result<(), String> {
+        if fee == 0 {
+            return Ok(());
+        }
+        let sender = PrincipalData::parse(sender).map_err(|e| e.to_string())?;
+        self.interpreter.mint_stx_balance(sender, fee)?;
+        Ok(())
+    }
+
     pub fn set_tx_sender(&mut self, address: String) {
         let tx_sender =
             PrincipalData::parse_standard_principal(&address).expect("Unable to parse address");
@@ -904,6 +1281,12 @@ impl Session {
         output.push(green!(format!("Current height: {}", height)));
     }
 
+    #[cfg(feature = "cli")]
+    fn get_burn_block_height(&mut self, output: &mut Vec<String>) {
+        let height = self.interpreter.get_burn_block_height();
+        output.push(green!(format!("Current burn height: {}", height)));
+    }
+
     #[cfg(feature = "cli")]
     fn get_account_name(&self, address: &String) -> Option<&String> {
         for account in self.settings.initial_accounts.iter() {
@@ -956,6 +1339,10 @@ impl Session {
     }
 
     pub fn update_epoch(&mut self, epoch: StacksEpochId) {
+        let height = self.interpreter.get_block_height();
+        self.epochs_history
+            .entry(height)
+            .or_insert(self.current_epoch);
         self.current_epoch = epoch;
     }
 
@@ -1310,7 +1697,10 @@ fn clarity_keywords() -> HashMap<String, String> {
 #[allow(clippy::items_after_test_module)]
 #[cfg(test)]
 mod tests {
+    use crate::repl::pox::{StxUnlock, POX_4_CONTRACT};
     use crate::repl::{self, settings::Account};
+    use clarity::vm::costs::ExecutionCost;
+    use clarity::vm::types::TupleData;
 
     use super::*;
 
@@ -1330,6 +1720,398 @@ mod tests {
         assert_eq!(balance, 1000000);
     }
 
+    #[cfg(feature = "cli")]
+    #[test]
+    fn profile_command() {
+        let mut session = Session::new(SessionSettings::default());
+        let _ = session.start();
+
+        let (_, output, _) = session.handle_command("::profile (+ u1 u2)");
+        let speedscope: serde_json::Value =
+            serde_json::from_str(output.last().unwrap()).expect("speedscope profile");
+        assert_eq!(speedscope["name"], "(+ u1 u2)");
+
+        let path = std::env::temp_dir().join(format!(
+            "clarity-profile-{}.speedscope.json",
+            std::process::id()
+        ));
+        let cmd = format!("::profile --output {} (+ u1 u2)", path.display());
+        let (_, output, _) = session.handle_command(&cmd);
+        assert!(output
+            .last()
+            .unwrap()
+            .contains("speedscope profile written"));
+        let speedscope: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(speedscope["exporter"], "clarinet");
+        let _ = std::fs::remove_file(path);
+
+        let (_, output, _) = session.handle_command("::profile --output");
+        assert!(output[0].contains("Usage: ::profile"));
+    }
+
+    #[test]
+    fn block_budget_nonces_and_fees() {
+        let address = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings {
+            initial_accounts: vec![Account {
+                address: address.to_owned(),
+                balance: 1000,
+                name: "wallet_1".to_owned(),
+            }],
+            ..Default::default()
+        });
+        let _ = session.start();
+
+        session.begin_block();
+        assert!(session.is_block_empty());
+        let execution = session.eval("(+ 1 2)".to_string(), None, false).unwrap();
+        assert!(execution.cost.is_some());
+        assert!(!session.is_block_empty());
+
+        // only the executions exceeding the costs left in the block are reported as such
+        session.interpreter.block_budget = Some(ExecutionCost::zero());
+        assert!(session.eval("(+ 1 2)".to_string(), None, false).is_err());
+        assert!(session.interpreter.block_limit_reached);
+        session.begin_block();
+        assert!(session
+            .eval("(unwrap-panic none)".to_string(), None, false)
+            .is_err());
+        assert!(!session.interpreter.block_limit_reached);
+        // the failed execution is charged to the block
+        assert!(!session.is_block_empty());
+        session.end_block();
+        assert_eq!(session.interpreter.block_budget, None);
+
+        assert_eq!(session.get_nonce(address), 0);
+        session.increment_nonce(address);
+        assert_eq!(session.get_nonce(address), 1);
+
+        session.pay_fee(address, 400).unwrap();
+        let balance = session.interpreter.get_balance_for_account(address, "STX");
+        assert_eq!(balance, 600);
+        assert!(session.pay_fee(address, 1000).is_err());
+        session.refund_fee(address, 400).unwrap();
+        let balance = session.interpreter.get_balance_for_account(address, "STX");
+        assert_eq!(balance, 1000);
+    }
+
+    #[test]
+    fn tenures_since_epoch_3() {
+        let mut session = Session::new(SessionSettings::default());
+        session.advance_chain_tip(2);
+        assert_eq!(session.interpreter.get_burn_block_height(), 2);
+
+        session.update_epoch(StacksEpochId::Epoch30);
+        assert_eq!(session.advance_chain_tip(3), 5);
+        assert_eq!(session.interpreter.get_burn_block_height(), 2);
+
+        assert_eq!(session.advance_burn_chain_tip(2), 4);
+        assert_eq!(session.interpreter.get_block_height(), 7);
+    }
+
+    fn deploy_counter(session: &mut Session, name: &str) {
+        let snippet = "
+            (define-data-var x uint u0)
+
+            (define-read-only (get-x)
+                (var-get x))
+
+            (define-public (incr)
+                (begin
+                    (var-set x (+ (var-get x) u1))
+                    (ok (var-get x))))";
+
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: name.to_string(),
+            deployer: ContractDeployer::Address("ST000000000000000000002AMW42H".into()),
+            clarity_version: ClarityVersion::Clarity1,
+            epoch: repl::DEFAULT_EPOCH,
+        };
+        let _ = session.deploy_contract(&contract, None, false, None, &mut None);
+    }
+
+    #[test]
+    fn rewind_chain_tip() {
+        let mut session = Session::new(SessionSettings::default());
+        let _ = session.start();
+        deploy_counter(&mut session, "counter");
+
+        session.advance_chain_tip(1);
+        session.handle_command("(contract-call? .counter incr)");
+        session.advance_chain_tip(1);
+        session.handle_command("(contract-call? .counter incr)");
+        deploy_counter(&mut session, "late-counter");
+        session.advance_chain_tip(3);
+
+        assert_eq!(
+            session.rewind(6).unwrap_err(),
+            "height 6 is above the chain tip (5)"
+        );
+        assert_eq!(session.rewind(1), Ok(1));
+        assert_eq!(session.interpreter.get_block_height(), 1);
+        assert_eq!(session.interpreter.get_burn_block_height(), 1);
+        assert_eq!(
+            session.handle_command("(contract-call? .counter get-x)").1[0],
+            green!("u1")
+        );
+
+        // contracts deployed after the new tip can be deployed again
+        let late_counter =
+            QualifiedContractIdentifier::parse("ST000000000000000000002AMW42H.late-counter")
+                .unwrap();
+        assert!(!session.contracts.contains_key(&late_counter));
+        deploy_counter(&mut session, "late-counter");
+        assert!(session.contracts.contains_key(&late_counter));
+
+        assert_eq!(session.advance_chain_tip(2), 3);
+        assert_eq!(
+            session.handle_command("(contract-call? .counter get-x)").1[0],
+            green!("u1")
+        );
+    }
+
+    #[test]
+    fn rewind_nonces_and_epoch() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings::default());
+        let _ = session.start();
+        session.update_epoch(StacksEpochId::Epoch21);
+        session.increment_nonce(sender);
+
+        session.advance_chain_tip(1);
+        session.increment_nonce(sender);
+        session.increment_nonce(sender);
+        session.update_epoch(StacksEpochId::Epoch24);
+        session.advance_chain_tip(1);
+        session.increment_nonce(sender);
+        session.update_epoch(StacksEpochId::Epoch25);
+        session.update_epoch(StacksEpochId::Epoch30);
+        assert_eq!(session.get_nonce(sender), 4);
+
+        assert_eq!(session.rewind(1), Ok(1));
+        assert_eq!(session.get_nonce(sender), 3);
+        assert_eq!(session.current_epoch, StacksEpochId::Epoch24);
+
+        assert_eq!(session.rewind(0), Ok(0));
+        assert_eq!(session.get_nonce(sender), 1);
+        assert_eq!(session.current_epoch, StacksEpochId::Epoch21);
+
+        // the nonces keep increasing from the restored ones
+        session.advance_chain_tip(1);
+        session.increment_nonce(sender);
+        assert_eq!(session.get_nonce(sender), 2);
+        assert_eq!(session.rewind(0), Ok(0));
+        assert_eq!(session.get_nonce(sender), 1);
+    }
+
+    fn call_pox_4(session: &mut Session, method: &str, args: &[Value], sender: &str) -> Value {
+        let args = args
+            .iter()
+            .map(|arg| arg.serialize_to_vec().unwrap())
+            .collect::<Vec<_>>();
+        let execution = session
+            .call_contract_fn(
+                POX_4_CONTRACT,
+                method,
+                &args,
+                sender,
+                false,
+                false,
+                false,
+                "pox".into(),
+            )
+            .unwrap();
+        match execution.result {
+            EvaluationResult::Snippet(result) => result.result,
+            EvaluationResult::Contract(_) => unreachable!(),
+        }
+    }
+
+    fn eval_snippet(session: &mut Session, snippet: &str) -> Value {
+        match session
+            .eval(snippet.to_string(), None, false)
+            .unwrap()
+            .result
+        {
+            EvaluationResult::Snippet(result) => result.result,
+            EvaluationResult::Contract(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn pox_4_stacking_cycle() {
+        let stacker = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings {
+            include_boot_contracts: vec!["pox-4".into()],
+            initial_accounts: vec![Account {
+                address: stacker.to_owned(),
+                balance: 1_000_000_000_000,
+                name: "wallet_1".to_owned(),
+            }],
+            ..Default::default()
+        });
+        session.start().expect("session could not start");
+        session.update_epoch(StacksEpochId::Epoch25);
+
+        // reward cycles of 10 burn blocks, with prepare phases of 3 blocks
+        let args = [0, 3, 10, 0].map(Value::UInt);
+        let configured = call_pox_4(&mut session, "set-burnchain-parameters", &args, stacker);
+        assert_eq!(configured, Value::okay_true());
+
+        let pox_addr = TupleData::from_data(vec![
+            ("version".into(), Value::buff_from(vec![0x00]).unwrap()),
+            (
+                "hashbytes".into(),
+                Value::buff_from(vec![0x01; 20]).unwrap(),
+            ),
+        ])
+        .unwrap();
+        let minimum = call_pox_4(&mut session, "get-stacking-minimum", &[], stacker)
+            .expect_u128()
+            .unwrap();
+        let amount = minimum * 3;
+
+        // the signer authorizes the stacking with a transaction instead of a signature
+        let signer_key = Value::buff_from(vec![0x02; 33]).unwrap();
+        let snippet = format!(
+            "(unwrap-panic (principal-construct? 0x1a (hash160 0x{})))",
+            "02".repeat(33)
+        );
+        let signer = match eval_snippet(&mut session, &snippet).expect_principal() {
+            Ok(PrincipalData::Standard(signer)) => signer.to_address(),
+            _ => panic!("expected a standard principal"),
+        };
+        let authorization = [
+            Value::Tuple(pox_addr.clone()),
+            Value::UInt(1),
+            Value::UInt(0),
+            Value::string_ascii_from_bytes("stack-stx".into()).unwrap(),
+            signer_key.clone(),
+            Value::Bool(true),
+            Value::UInt(amount),
+            Value::UInt(1),
+        ];
+        let authorized = call_pox_4(
+            &mut session,
+            "set-signer-key-authorization",
+            &authorization,
+            &signer,
+        );
+        assert_eq!(authorized, Value::okay_true());
+
+        let burn_height = session.interpreter.get_burn_block_height();
+        let stack_stx = [
+            Value::UInt(amount),
+            Value::Tuple(pox_addr.clone()),
+            Value::UInt(burn_height.into()),
+            Value::UInt(1),
+            Value::none(),
+            signer_key,
+            Value::UInt(amount),
+            Value::UInt(1),
+        ];
+        let stacked = call_pox_4(&mut session, "stack-stx", &stack_stx, stacker)
+            .expect_result_ok()
+            .unwrap()
+            .expect_tuple()
+            .unwrap();
+        assert_eq!(stacked.get("unlock-burn-height").unwrap(), &Value::UInt(20));
+        let locked = format!("(get locked (stx-account '{}))", stacker);
+        assert_eq!(eval_snippet(&mut session, &locked), Value::UInt(amount));
+
+        // the reward set of cycle 1 is calculated when its prepare phase starts
+        assert_eq!(session.advance_burn_chain_tip(7), 7);
+        assert!(!session.interpreter.pox.reward_sets.contains_key(&1));
+        assert_eq!(session.advance_burn_chain_tip(1), 8);
+        assert_eq!(
+            session.interpreter.pox.reward_sets[&1],
+            vec![pox_addr.clone(); 3]
+        );
+
+        // the block commits of the reward phase pay the reward set, the prepare phase burns them
+        assert_eq!(session.advance_burn_chain_tip(4), 12);
+        let payouts = |session: &mut Session, burn_height: u32| {
+            let snippet = format!("(get-burn-block-info? pox-addrs u{})", burn_height);
+            let payouts = eval_snippet(session, &snippet)
+                .expect_optional()
+                .unwrap()
+                .unwrap()
+                .expect_tuple()
+                .unwrap();
+            (
+                payouts.get("addrs").unwrap().clone().expect_list().unwrap(),
+                payouts.get("payout").unwrap().clone(),
+            )
+        };
+        assert_eq!(payouts(&mut session, 10), (vec![], Value::UInt(2000)));
+        assert_eq!(
+            payouts(&mut session, 11),
+            (vec![Value::Tuple(pox_addr.clone()); 2], Value::UInt(1000))
+        );
+
+        // the STX are unlocked when the cycle following the lock period starts
+        assert_eq!(session.advance_burn_chain_tip(7), 19);
+        assert_eq!(eval_snippet(&mut session, &locked), Value::UInt(amount));
+        assert!(session.interpreter.pox.unlocks.is_empty());
+        assert_eq!(session.advance_burn_chain_tip(1), 20);
+        assert_eq!(
+            session.interpreter.pox.unlocks,
+            vec![StxUnlock {
+                stacker: stacker.to_string(),
+                amount,
+                burn_height: 20,
+            }]
+        );
+        assert_eq!(eval_snippet(&mut session, &locked), Value::UInt(0));
+    }
+
+    #[test]
+    fn commands_are_handled() {
+        let mut session = Session::new(SessionSettings::default());
+        let _ = session.start();
+        for command in Session::COMMANDS {
+            let (_, output, _) = session.handle_command(command);
+            assert!(
+                output.iter().all(|line| !line.contains("Unknown command")),
+                "{} is not handled",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn fork_and_checkout_branches() {
+        let mut session = Session::new(SessionSettings::default());
+        let _ = session.start();
+        deploy_counter(&mut session, "counter");
+
+        session.fork("feature").unwrap();
+        assert_eq!(
+            session.fork("main").unwrap_err(),
+            "branch main already exists"
+        );
+        session.handle_command("(contract-call? .counter incr)");
+        session.advance_chain_tip(4);
+        assert_eq!(session.get_branches(), vec!["feature", "main"]);
+
+        session.checkout("main").unwrap();
+        assert_eq!(session.interpreter.get_block_height(), 0);
+        assert_eq!(
+            session.handle_command("(contract-call? .counter get-x)").1[0],
+            green!("u0")
+        );
+
+        session.checkout("feature").unwrap();
+        assert_eq!(session.interpreter.get_block_height(), 4);
+        assert_eq!(
+            session.handle_command("(contract-call? .counter get-x)").1[0],
+            green!("u1")
+        );
+        assert!(session.checkout("unknown").is_err());
+    }
+
     #[test]
     fn encode_simple() {
         let mut session = Session::new(SessionSettings::default());
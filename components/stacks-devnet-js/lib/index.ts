@@ -11,6 +11,7 @@ const {
   stacksDevnetGetStacksApiUrl,
   stacksDevnetGetStacksExplorerUrl,
   stacksDevnetGetBitcoinExplorerUrl,
+  stacksDevnetInjectFault,
 } = require("../native/index.node");
 import {
   BitcoinChainUpdate,
@@ -108,6 +109,22 @@ export interface PoxStackingOrder {
   btc_address: string;
 }
 
+/**
+ * Fault to inject in a running devnet, for testing how clients behave under degraded chain conditions
+ * - `bitcoin_reorg`: invalidate the last `depth` bitcoin blocks and mine a longer fork
+ * - `pause_service`: freeze a service container (eg. `stacks-node`) for `duration_ms`
+ * - `partition`: split the listed services from the rest of the devnet for `duration_ms`, they still reach each other
+ * - `delay_events`: hold the chain events received by the observer for `delay_ms`, during `duration_ms`
+ *   (requires `enable_events_delay` unless a `delay_events` fault is scheduled in Devnet.toml)
+ * @export
+ * @type DevnetFault
+ */
+export type DevnetFault =
+  | { kind: "bitcoin_reorg"; depth: number }
+  | { kind: "pause_service"; service: string; duration_ms: number }
+  | { kind: "partition"; services: string[]; duration_ms: number }
+  | { kind: "delay_events"; delay_ms: number; duration_ms: number };
+
 /**
  * Set of values that can be used for overriding values coming from the default project settings
  * @export
@@ -210,6 +227,18 @@ export interface DevnetConfig {
    * @memberof DevnetConfig
    */
   bitcoin_controller_automining_disabled?: boolean;
+  /**
+   * Mine the next Bitcoin block as soon as the stacks-node processed the previous one, with deterministic timestamps
+   * @type {boolean}
+   * @memberof DevnetConfig
+   */
+  bitcoin_controller_accelerated_mining?: boolean;
+  /**
+   * Relay the chain events through the events proxy, for `delay_events` faults injected at runtime
+   * @type {boolean}
+   * @memberof DevnetConfig
+   */
+  enable_events_delay?: boolean;
   /**
    * The username to use for authenticating bitcoind RPC calls
    * @type {number}
@@ -505,6 +534,15 @@ export class DevnetNetworkOrchestrator {
     return stacksDevnetStart.call(this.handle, timeout, emptyBuffer);
   }
 
+  /**
+   * @summary Inject a fault (reorg, paused service, network partition, delayed events) in the running devnet
+   * @param {DevnetFault} fault
+   * @memberof DevnetNetworkOrchestrator
+   */
+  injectFault(fault: DevnetFault) {
+    return stacksDevnetInjectFault.call(this.handle, fault);
+  }
+
   /**
    * @summary Returns the URL of the stacks-node container
    * @memberof DevnetNetworkOrchestrator
@@ -7,8 +7,8 @@ mod serde;
 use clarinet_deployments::{get_default_deployment_path, load_deployment};
 use clarinet_files::bip39::{Language, Mnemonic};
 use clarinet_files::{
-    compute_addresses, AccountConfig, DevnetConfigFile, FileLocation, PoxStackingOrder,
-    ProjectManifest, DEFAULT_DERIVATION_PATH,
+    compute_addresses, AccountConfig, DevnetConfigFile, DevnetFault, FileLocation,
+    PoxStackingOrder, ProjectManifest, DEFAULT_DERIVATION_PATH,
 };
 use hiro_system_kit::{o, slog, slog_async, slog_term, Drain};
 use neon::context::Context as NeonContext;
@@ -645,6 +645,20 @@ impl StacksDevnet {
             overrides.bitcoin_controller_automining_disabled = Some(res.value(&mut cx));
         }
 
+        if let Ok(res) = devnet_settings
+            .get(&mut cx, "bitcoin_controller_accelerated_mining")?
+            .downcast::<JsBoolean, _>(&mut cx)
+        {
+            overrides.bitcoin_controller_accelerated_mining = Some(res.value(&mut cx));
+        }
+
+        if let Ok(res) = devnet_settings
+            .get(&mut cx, "enable_events_delay")?
+            .downcast::<JsBoolean, _>(&mut cx)
+        {
+            overrides.enable_events_delay = Some(res.value(&mut cx));
+        }
+
         if let Ok(res) = devnet_settings
             .get(&mut cx, "bind_containers_volumes")?
             .downcast::<JsBoolean, _>(&mut cx)
@@ -819,6 +833,7 @@ impl StacksDevnet {
                     slots,
                     btc_address,
                     auto_extend: Some(false),
+                    signer: None,
                 });
             }
             overrides.pox_stacking_orders = Some(stacking_orders);
@@ -909,6 +924,71 @@ impl StacksDevnet {
         Ok(js_block)
     }
 
+    fn js_inject_fault(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let devnet = cx
+            .this()
+            .downcast_or_throw::<JsBox<StacksDevnet>, _>(&mut cx)?;
+        let fault_settings = cx.argument::<JsObject>(0)?;
+
+        let kind = fault_settings
+            .get(&mut cx, "kind")?
+            .downcast_or_throw::<JsString, _>(&mut cx)?
+            .value(&mut cx);
+
+        fn get_number(
+            cx: &mut FunctionContext,
+            settings: Handle<JsObject>,
+            key: &str,
+        ) -> NeonResult<u64> {
+            Ok(settings
+                .get(cx, key)?
+                .downcast_or_throw::<JsNumber, _>(cx)?
+                .value(cx) as u64)
+        }
+
+        let fault = match kind.as_str() {
+            "bitcoin_reorg" => DevnetFault::BitcoinReorg {
+                depth: get_number(&mut cx, fault_settings, "depth")? as u32,
+            },
+            "pause_service" => DevnetFault::PauseService {
+                service: fault_settings
+                    .get(&mut cx, "service")?
+                    .downcast_or_throw::<JsString, _>(&mut cx)?
+                    .value(&mut cx),
+                duration_ms: get_number(&mut cx, fault_settings, "duration_ms")?,
+            },
+            "partition" => {
+                let raw_services = fault_settings
+                    .get(&mut cx, "services")?
+                    .downcast_or_throw::<JsArray, _>(&mut cx)?
+                    .to_vec(&mut cx)?;
+                let mut services = vec![];
+                for raw_service in raw_services.iter() {
+                    services.push(
+                        raw_service
+                            .downcast_or_throw::<JsString, _>(&mut cx)?
+                            .value(&mut cx),
+                    );
+                }
+                DevnetFault::Partition {
+                    services,
+                    duration_ms: get_number(&mut cx, fault_settings, "duration_ms")?,
+                }
+            }
+            "delay_events" => DevnetFault::DelayEvents {
+                delay_ms: get_number(&mut cx, fault_settings, "delay_ms")?,
+                duration_ms: get_number(&mut cx, fault_settings, "duration_ms")?,
+            },
+            _ => return cx.throw_error(format!("unknown fault kind: {}", kind)),
+        };
+
+        let _ = devnet
+            .mining_tx
+            .send(BitcoinMiningCommand::InjectFault(fault));
+
+        Ok(cx.undefined())
+    }
+
     fn js_get_bitcoin_node_url(mut cx: FunctionContext) -> JsResult<JsString> {
         let devnet = cx
             .this()
@@ -969,6 +1049,7 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         "stacksDevnetWaitForBitcoinBlock",
         StacksDevnet::js_on_bitcoin_block,
     )?;
+    cx.export_function("stacksDevnetInjectFault", StacksDevnet::js_inject_fault)?;
     cx.export_function(
         "stacksDevnetGetBitcoinNodeUrl",
         StacksDevnet::js_get_bitcoin_node_url,
@@ -3,7 +3,11 @@ use super::ChainsCoordinatorCommand;
 use crate::event::send_status_update;
 use crate::event::DevnetEvent;
 use crate::event::Status;
+use crate::events_proxy::start_events_proxy;
+use crate::events_recording::EventsRecorder;
+use crate::faults::{inject_fault, take_due_faults};
 use crate::orchestrator::ServicesMapHosts;
+use crate::scripts::ScriptsRunner;
 
 use base58::FromBase58;
 use chainhook_sdk::chainhooks::types::ChainhookConfig;
@@ -23,6 +27,7 @@ use clarinet_deployments::onchain::{
     apply_on_chain_deployment, DeploymentCommand, DeploymentEvent,
 };
 use clarinet_deployments::types::DeploymentSpecification;
+use clarinet_files::DevnetFault;
 use clarinet_files::PoxStackingOrder;
 use clarinet_files::DEFAULT_FIRST_BURN_HEADER_HEIGHT;
 use clarinet_files::{self, AccountConfig, DevnetConfig, NetworkManifest, ProjectManifest};
@@ -35,8 +40,9 @@ use clarity_repl::clarity::PublicKey;
 use hiro_system_kit;
 use hiro_system_kit::slog;
 use hiro_system_kit::yellow;
+use serde_json::Value as JsonValue;
 use stacks_codec::codec;
-use stacks_rpc_client::rpc_client::PoxInfo;
+use stacks_rpc_client::rpc_client::{NodeInfo, PoxInfo};
 use stacks_rpc_client::StacksRpc;
 use stackslib::chainstate::stacks::address::PoxAddress;
 use stackslib::core::CHAIN_ID_TESTNET;
@@ -44,9 +50,10 @@ use stackslib::types::chainstate::StacksPrivateKey;
 use stackslib::types::chainstate::StacksPublicKey;
 use stackslib::util_lib::signed_structured_data::pox4::make_pox_4_signer_key_signature;
 use stackslib::util_lib::signed_structured_data::pox4::Pox4SignatureTopic;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::str;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
 use std::time::Duration;
@@ -92,12 +99,35 @@ pub struct ContractReadonlyCall {
     pub result: String,
 }
 
+/// With accelerated mining, bitcoin block N is timestamped
+/// `ACCELERATED_MINING_GENESIS_TIMESTAMP + N * ACCELERATED_MINING_BLOCK_SPACING_SECS`
+pub const ACCELERATED_MINING_GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+pub const ACCELERATED_MINING_BLOCK_SPACING_SECS: u64 = 600;
+
+/// Timestamp of the bitcoin block mined at `height` with accelerated mining
+pub fn accelerated_mining_timestamp(height: u64) -> u64 {
+    ACCELERATED_MINING_GENESIS_TIMESTAMP + height * ACCELERATED_MINING_BLOCK_SPACING_SECS
+}
+
+/// Timestamps of the `count` blocks of a fork starting at `fork_point`. The blocks
+/// replacing invalidated ones are timestamped one second after them: a block identical
+/// to an invalidated one would be rejected by bitcoind.
+fn reorg_timestamps(fork_point: u64, replaced_timestamps: &[u64], count: u32) -> Vec<u64> {
+    (0..u64::from(count))
+        .map(|i| match replaced_timestamps.get(i as usize) {
+            Some(timestamp) => timestamp + 1,
+            None => accelerated_mining_timestamp(fork_point + i),
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub enum BitcoinMiningCommand {
     Start,
     Pause,
     Mine,
     InvalidateChainTip,
+    InjectFault(DevnetFault),
 }
 
 impl DevnetEventObserverConfig {
@@ -190,6 +220,23 @@ pub async fn start_chains_coordinator(
         Some(config.consolidated_stacks_rpc_url()),
     );
 
+    // Scripts listed in `execute_script` run once the deployment is completed,
+    // or when reaching their `at_block` height.
+    let scripts_runner = if config.devnet_config.execute_script.is_empty() {
+        None
+    } else {
+        match ScriptsRunner::new(&config) {
+            Ok(scripts_runner) => Some(scripts_runner),
+            Err(e) => {
+                let _ = devnet_event_tx.send(DevnetEvent::error(format!(
+                    "unable to prepare scripts execution: {}",
+                    e
+                )));
+                None
+            }
+        }
+    };
+
     // Set-up the background task in charge of monitoring contracts deployments.
     // This thread will be waiting and relaying events emitted by the thread above.
     relay_devnet_protocol_deployment(
@@ -197,6 +244,7 @@ pub async fn start_chains_coordinator(
         &devnet_event_tx,
         Some(mining_command_tx.clone()),
         &boot_completed,
+        scripts_runner.clone(),
     );
 
     if let Some(ref hooks) = config.event_observer_config.chainhook_config {
@@ -228,12 +276,55 @@ pub async fn start_chains_coordinator(
         );
     });
 
-    // Spawn bitcoin miner controller
+    // Spawn the proxy relaying events to the configured observers when a `delay_events` fault
+    // is scheduled, and recording the payloads posted by the node
+    let events_delay_ms = Arc::new(AtomicU64::new(0));
+    let events_recorder = match config.devnet_config.record_events_path {
+        Some(ref path) => match EventsRecorder::new(path) {
+            Ok(recorder) => {
+                let _ = devnet_event_tx.send(DevnetEvent::info(format!(
+                    "Recording chain events in {}",
+                    path
+                )));
+                Some(recorder)
+            }
+            Err(e) => {
+                let _ = devnet_event_tx.send(DevnetEvent::error(e));
+                None
+            }
+        },
+        None => None,
+    };
+    if config.devnet_config.uses_events_proxy() {
+        let relayed_observers = if config.devnet_config.relays_events_through_proxy() {
+            config.devnet_config.stacks_node_events_observers.clone()
+        } else {
+            vec![]
+        };
+        if let Err(e) = start_events_proxy(
+            config.devnet_config.stacks_node_events_proxy_port,
+            relayed_observers,
+            events_delay_ms.clone(),
+            events_recorder,
+            devnet_event_tx.clone(),
+        ) {
+            let _ = devnet_event_tx.send(DevnetEvent::error(e));
+        }
+    }
+
+    // Spawn bitcoin miner controller, also in charge of injecting faults
+    let (tenure_processed_tx, tenure_processed_rx) = crossbeam_channel::unbounded();
     let devnet_event_tx_moved = devnet_event_tx.clone();
     let devnet_config = config.clone();
+    let events_delay_ms_moved = events_delay_ms.clone();
     let _ = hiro_system_kit::thread_named("Bitcoin mining").spawn(move || {
-        let future =
-            handle_bitcoin_mining(mining_command_rx, &devnet_config, &devnet_event_tx_moved);
+        let future = handle_bitcoin_mining(
+            mining_command_rx,
+            &devnet_config,
+            &events_delay_ms_moved,
+            tenure_processed_rx,
+            &devnet_event_tx_moved,
+        );
         hiro_system_kit::nestable_block_on(future);
     });
 
@@ -241,15 +332,15 @@ pub async fn start_chains_coordinator(
     // and orchestrate the 2 chains + protocol.
     let mut deployment_commands_tx = Some(deployment_commands_tx);
     let mut subnet_initialized = false;
+    let mut triggered_faults = HashSet::new();
+    let mut triggered_scripts = HashSet::new();
+    let mut bitcoin_tip_height = 0;
+    let mut last_processed_tenure = 0;
 
     let mut sel = crossbeam_channel::Select::new();
     let chains_coordinator_commands_oper = sel.recv(&chains_coordinator_commands_rx);
     let observer_event_oper = sel.recv(&observer_event_rx);
 
-    let DevnetConfig {
-        enable_subnet_node, ..
-    } = config.devnet_config;
-
     loop {
         let oper = sel.select();
         let command = match oper.index() {
@@ -299,6 +390,7 @@ pub async fn start_chains_coordinator(
                     BitcoinChainEvent::ChainUpdatedWithBlocks(event) => {
                         let tip = event.new_blocks.last().unwrap();
                         let bitcoin_block_height = tip.block_identifier.index;
+                        bitcoin_tip_height = bitcoin_block_height;
                         let log = format!("Bitcoin block #{} received", bitcoin_block_height);
                         let comment =
                             format!("mining blocks (chaintip = #{})", bitcoin_block_height);
@@ -322,10 +414,35 @@ pub async fn start_chains_coordinator(
                             }
                         }
 
+                        for fault in take_due_faults(
+                            &config.devnet_config.faults,
+                            bitcoin_block_height,
+                            &mut triggered_faults,
+                        ) {
+                            let _ =
+                                mining_command_tx.send(BitcoinMiningCommand::InjectFault(fault));
+                        }
+
+                        if let Some(ref scripts_runner) = scripts_runner {
+                            scripts_runner.run_due_scripts(
+                                bitcoin_block_height,
+                                &mut triggered_scripts,
+                                &devnet_event_tx,
+                            );
+                        }
+
+                        update_extra_stacks_nodes_status(
+                            &config.devnet_config,
+                            &config.services_map_hosts,
+                            &devnet_event_tx,
+                        )
+                        .await;
+
                         (log, comment)
                     }
                     BitcoinChainEvent::ChainUpdatedWithReorg(events) => {
                         let tip = events.blocks_to_apply.last().unwrap();
+                        bitcoin_tip_height = tip.block_identifier.index;
                         let log = format!(
                             "Bitcoin reorg received (new height: {})",
                             tip.block_identifier.index
@@ -338,13 +455,7 @@ pub async fn start_chains_coordinator(
 
                 let _ = devnet_event_tx.send(DevnetEvent::debug(log));
 
-                send_status_update(
-                    &devnet_event_tx,
-                    enable_subnet_node,
-                    "bitcoin-node",
-                    Status::Green,
-                    &comment,
-                );
+                send_status_update(&devnet_event_tx, "bitcoin-node", Status::Green, &comment);
                 let _ = devnet_event_tx.send(DevnetEvent::BitcoinChainEvent(chain_update.clone()));
             }
             ObserverEvent::StacksChainEvent((chain_event, _)) => {
@@ -376,8 +487,25 @@ pub async fn start_chains_coordinator(
                     StacksChainEvent::ChainUpdatedWithMicroblocksReorg(_) => {
                         unreachable!() // TODO(lgalabru): good enough for now - code path unreachable in the context of Devnet
                     }
-                    StacksChainEvent::ChainUpdatedWithReorg(_) => {
-                        unreachable!() // TODO(lgalabru): good enough for now - code path unreachable in the context of Devnet
+                    StacksChainEvent::ChainUpdatedWithReorg(data) => {
+                        // With several miners, competing stacks forks are expected
+                        match data.blocks_to_apply.last() {
+                            Some(known_tip) => {
+                                let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+                                    "Stacks reorg received ({} blocks rolled back, new tip: #{})",
+                                    data.blocks_to_rollback.len(),
+                                    known_tip.block.block_identifier.index
+                                )));
+                                known_tip.clone()
+                            }
+                            None => {
+                                let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+                                    "Stacks reorg received without blocks to apply ({} blocks rolled back)",
+                                    data.blocks_to_rollback.len()
+                                )));
+                                continue;
+                            }
+                        }
                     }
                 };
 
@@ -387,7 +515,6 @@ pub async fn start_chains_coordinator(
                 // would requires either cloning the block, or passing ownership.
                 send_status_update(
                     &devnet_event_tx,
-                    enable_subnet_node,
                     "stacks-node",
                     Status::Green,
                     &format!(
@@ -398,6 +525,21 @@ pub async fn start_chains_coordinator(
 
                 // devnet_event_tx.send(DevnetEvent::send_status_update(status_update_data));
 
+                // With accelerated mining, the next bitcoin block is mined as soon as
+                // the stacks-node has processed the tenure of the current one
+                if config.devnet_config.bitcoin_controller_accelerated_mining {
+                    let anchor_height = known_tip
+                        .block
+                        .metadata
+                        .bitcoin_anchor_block_identifier
+                        .index;
+                    if anchor_height >= bitcoin_tip_height && anchor_height > last_processed_tenure
+                    {
+                        last_processed_tenure = anchor_height;
+                        let _ = tenure_processed_tx.send(anchor_height);
+                    }
+                }
+
                 let message = if known_tip.block.block_identifier.index == 0 {
                     format!(
                         "Genesis Stacks block anchored in Bitcoin block #{} includes {} transactions",
@@ -425,13 +567,7 @@ pub async fn start_chains_coordinator(
             ObserverEvent::NotifyBitcoinTransactionProxied => {
                 if !boot_completed.load(Ordering::SeqCst) {
                     std::thread::sleep(std::time::Duration::from_secs(1));
-                    let res = mine_bitcoin_block(
-                        &config.services_map_hosts.bitcoin_node_host,
-                        config.devnet_config.bitcoin_node_username.as_str(),
-                        config.devnet_config.bitcoin_node_password.as_str(),
-                        config.devnet_config.miner_btc_address.as_str(),
-                    )
-                    .await;
+                    let res = mine_next_bitcoin_block(config).await;
                     if let Err(e) = res {
                         let _ = devnet_event_tx.send(DevnetEvent::error(e));
                     }
@@ -459,7 +595,6 @@ pub async fn start_chains_coordinator(
                             if tx.tx_description.contains("::commit-block") {
                                 send_status_update(
                                     &devnet_event_tx,
-                                    enable_subnet_node,
                                     "subnet-node",
                                     Status::Green,
                                     "⚡️",
@@ -511,6 +646,7 @@ pub fn relay_devnet_protocol_deployment(
     devnet_event_tx: &Sender<DevnetEvent>,
     bitcoin_mining_tx: Option<Sender<BitcoinMiningCommand>>,
     boot_completed: &Arc<AtomicBool>,
+    scripts_runner: Option<ScriptsRunner>,
 ) {
     let devnet_event_tx = devnet_event_tx.clone();
     let boot_completed = boot_completed.clone();
@@ -536,6 +672,9 @@ pub fn relay_devnet_protocol_deployment(
                     if let Some(bitcoin_mining_tx) = bitcoin_mining_tx {
                         let _ = devnet_event_tx.send(DevnetEvent::BootCompleted(bitcoin_mining_tx));
                     }
+                    if let Some(scripts_runner) = scripts_runner {
+                        scripts_runner.run_post_deployment_scripts(&devnet_event_tx);
+                    }
                     break;
                 }
             }
@@ -580,6 +719,7 @@ mod tests {
             slots: 1,
             btc_address: "address_1".to_string(),
             auto_extend: Some(true),
+            signer: None,
         }
     }
 
@@ -622,6 +762,43 @@ mod tests {
         assert!(!should_publish_stacking_orders(&100, &pox_stacking_order));
         assert!(should_publish_stacking_orders(&101, &pox_stacking_order));
     }
+
+    #[test]
+    fn test_accelerated_mining_timestamps() {
+        let timestamps = (0..=3)
+            .map(accelerated_mining_timestamp)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            timestamps,
+            vec![1_700_000_000, 1_700_000_600, 1_700_001_200, 1_700_001_800]
+        );
+
+        // a 2 blocks deep reorg of a 5 blocks chain replaces the blocks 4 and 5,
+        // and mines the block 6 on top of them
+        let chain = (0..=5)
+            .map(accelerated_mining_timestamp)
+            .collect::<Vec<_>>();
+        let fork = reorg_timestamps(4, &chain[4..], 3);
+        assert_eq!(
+            fork,
+            vec![chain[4] + 1, chain[5] + 1, accelerated_mining_timestamp(6)]
+        );
+
+        // reorging the fork again gives blocks distinct from both chains
+        let fork_of_fork = reorg_timestamps(5, &fork[1..], 3);
+        assert_eq!(
+            fork_of_fork,
+            vec![
+                chain[5] + 2,
+                accelerated_mining_timestamp(6) + 1,
+                accelerated_mining_timestamp(7)
+            ]
+        );
+
+        // the timestamps keep increasing along the chain
+        let reorged_chain = [&chain[..5], &fork[..1], &fork_of_fork[..]].concat();
+        assert!(reorged_chain.windows(2).all(|pair| pair[0] < pair[1]));
+    }
 }
 
 pub async fn publish_stacking_orders(
@@ -672,16 +849,15 @@ pub async fn publish_stacking_orders(
         .and_then(|version| version.parse().ok())
         .unwrap_or(1); // pox 1 contract is `pox.clar`
 
-    let default_signing_keys = [
-        StacksPrivateKey::from_hex(
-            "7287ba251d44a4d3fd9276c88ce34c5c52a038955511cccaf77e61068649c17801",
-        )
-        .unwrap(),
-        StacksPrivateKey::from_hex(
-            "530d9f61984c888536871c6573073bdfc0058896dc1adfe9a6a10dfacadc209101",
-        )
-        .unwrap(),
-    ];
+    let signing_keys = devnet_config
+        .signers
+        .iter()
+        .filter_map(|signer| {
+            // keys are validated when Devnet.toml is parsed
+            let signing_key = StacksPrivateKey::from_hex(&signer.secret_key_hex).ok()?;
+            Some((signer.wallet.clone(), signing_key))
+        })
+        .collect::<Vec<_>>();
 
     let mut transactions = 0;
     for (i, pox_stacking_order) in devnet_config.pox_stacking_orders.iter().enumerate() {
@@ -704,6 +880,16 @@ pub async fn publish_stacking_orders(
             _ => continue,
         };
 
+        // stacking orders are assigned to the signer set in a round-robin fashion,
+        // unless a specific signer is requested
+        let signing_key = match &pox_stacking_order.signer {
+            Some(wallet) => signing_keys.iter().find(|(label, _)| label == wallet),
+            None => signing_keys.get(i % signing_keys.len().max(1)),
+        };
+        let Some((_, signing_key)) = signing_key.cloned() else {
+            continue;
+        };
+
         transactions += 1;
 
         let stx_amount = pox_info.next_cycle.min_threshold_ustx * pox_stacking_order.slots;
@@ -729,7 +915,7 @@ pub async fn publish_stacking_orders(
                     pox_version,
                     bitcoin_block_height,
                     current_cycle.into(),
-                    &default_signing_keys[i % 2],
+                    &signing_key,
                     extend_stacking,
                     &btc_address_moved,
                     stx_amount,
@@ -778,12 +964,181 @@ pub async fn publish_stacking_orders(
     }
 }
 
-pub fn invalidate_bitcoin_chain_tip(
-    _bitcoin_node_host: &str,
-    _bitcoin_node_username: &str,
-    _bitcoin_node_password: &str,
+async fn update_extra_stacks_nodes_status(
+    devnet_config: &DevnetConfig,
+    services_map_hosts: &ServicesMapHosts,
+    devnet_event_tx: &Sender<DevnetEvent>,
 ) {
-    unimplemented!()
+    for (node, host) in devnet_config
+        .extra_stacks_nodes
+        .iter()
+        .zip(services_map_hosts.extra_stacks_node_hosts.iter())
+    {
+        let node_info = match reqwest::get(format!("http://{}/v2/info", host)).await {
+            Ok(response) => response.json::<NodeInfo>().await.ok(),
+            Err(_) => None,
+        };
+        let (status, comment) = match node_info {
+            Some(info) if node.is_miner => (
+                Status::Green,
+                format!("mining blocks (chaintip = #{})", info.stacks_tip_height),
+            ),
+            Some(info) => (
+                Status::Green,
+                format!("following (chaintip = #{})", info.stacks_tip_height),
+            ),
+            None => (Status::Yellow, "waiting for rpc".to_string()),
+        };
+        send_status_update(devnet_event_tx, &node.name, status, &comment);
+    }
+}
+
+async fn call_bitcoin_rpc(
+    bitcoin_node_host: &str,
+    bitcoin_node_username: &str,
+    bitcoin_node_password: &str,
+    method: &str,
+    params: Vec<JsonValue>,
+) -> Result<JsonValue, String> {
+    use reqwest::Client as HttpClient;
+
+    let response = HttpClient::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("Unable to build http client")
+        .post(format!("http://{}", bitcoin_node_host))
+        .basic_auth(bitcoin_node_username, Some(bitcoin_node_password))
+        .header("Content-Type", "application/json")
+        .header("Host", bitcoin_node_host)
+        .json(&serde_json::json!({
+            "jsonrpc": "1.0",
+            "id": "stacks-network",
+            "method": method,
+            "params": params
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("unable to send request ({})", e))?
+        .json::<JsonValue>()
+        .await
+        .map_err(|e| format!("unable to parse {} response: ({})", method, e))?;
+    match response.get("error") {
+        Some(error) if !error.is_null() => Err(format!("{} failed: {}", method, error)),
+        _ => Ok(response.get("result").cloned().unwrap_or(JsonValue::Null)),
+    }
+}
+
+/// Invalidate the last `depth` blocks of the bitcoin chain, and mine a longer fork
+/// on top of the new tip, so that observers witness a reorg. With accelerated mining,
+/// the blocks of the fork are mined one by one, with deterministic timestamps.
+pub async fn reorg_bitcoin_chain(
+    bitcoin_node_host: &str,
+    bitcoin_node_username: &str,
+    bitcoin_node_password: &str,
+    miner_btc_address: &str,
+    accelerated_mining: bool,
+    depth: u32,
+) -> Result<(), String> {
+    let rpc = |method: &'static str, params: Vec<JsonValue>| {
+        call_bitcoin_rpc(
+            bitcoin_node_host,
+            bitcoin_node_username,
+            bitcoin_node_password,
+            method,
+            params,
+        )
+    };
+
+    let height = rpc("getblockcount", vec![])
+        .await?
+        .as_u64()
+        .ok_or("unable to retrieve bitcoin chain height")?;
+    if depth == 0 || u64::from(depth) >= height {
+        return Err(format!(
+            "unable to reorg {} blocks of a {} blocks chain",
+            depth, height
+        ));
+    }
+    let fork_point = height - u64::from(depth) + 1;
+
+    let mut replaced_timestamps = vec![];
+    if accelerated_mining {
+        for replaced_height in fork_point..=height {
+            let block_hash = rpc("getblockhash", vec![replaced_height.into()]).await?;
+            let header = rpc("getblockheader", vec![block_hash]).await?;
+            let timestamp = header
+                .get("time")
+                .and_then(|time| time.as_u64())
+                .ok_or("unable to retrieve bitcoin block time")?;
+            replaced_timestamps.push(timestamp);
+        }
+    }
+
+    let block_hash = rpc("getblockhash", vec![fork_point.into()]).await?;
+    rpc("invalidateblock", vec![block_hash]).await?;
+    if accelerated_mining {
+        for timestamp in reorg_timestamps(fork_point, &replaced_timestamps, depth + 1) {
+            rpc("setmocktime", vec![timestamp.into()]).await?;
+            rpc(
+                "generatetoaddress",
+                vec![1.into(), miner_btc_address.into()],
+            )
+            .await?;
+        }
+    } else {
+        rpc(
+            "generatetoaddress",
+            vec![(depth + 1).into(), miner_btc_address.into()],
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Pin bitcoind's clock so that the next block gets a deterministic timestamp.
+pub async fn set_bitcoin_mocktime_for_next_block(
+    bitcoin_node_host: &str,
+    bitcoin_node_username: &str,
+    bitcoin_node_password: &str,
+) -> Result<(), String> {
+    let height = call_bitcoin_rpc(
+        bitcoin_node_host,
+        bitcoin_node_username,
+        bitcoin_node_password,
+        "getblockcount",
+        vec![],
+    )
+    .await?
+    .as_u64()
+    .ok_or("unable to retrieve bitcoin chain height")?;
+    let timestamp = accelerated_mining_timestamp(height + 1);
+    call_bitcoin_rpc(
+        bitcoin_node_host,
+        bitcoin_node_username,
+        bitcoin_node_password,
+        "setmocktime",
+        vec![timestamp.into()],
+    )
+    .await?;
+    Ok(())
+}
+
+async fn mine_next_bitcoin_block(config: &DevnetEventObserverConfig) -> Result<(), String> {
+    if config.devnet_config.bitcoin_controller_accelerated_mining {
+        set_bitcoin_mocktime_for_next_block(
+            &config.services_map_hosts.bitcoin_node_host,
+            &config.devnet_config.bitcoin_node_username,
+            &config.devnet_config.bitcoin_node_password,
+        )
+        .await?;
+    }
+    mine_bitcoin_block(
+        &config.services_map_hosts.bitcoin_node_host,
+        &config.devnet_config.bitcoin_node_username,
+        &config.devnet_config.bitcoin_node_password,
+        &config.devnet_config.miner_btc_address,
+    )
+    .await
 }
 
 pub async fn mine_bitcoin_block(
@@ -824,6 +1179,8 @@ pub async fn mine_bitcoin_block(
 async fn handle_bitcoin_mining(
     mining_command_rx: Receiver<BitcoinMiningCommand>,
     config: &DevnetEventObserverConfig,
+    events_delay_ms: &Arc<AtomicU64>,
+    tenure_processed_rx: crossbeam_channel::Receiver<u64>,
     devnet_event_tx: &Sender<DevnetEvent>,
 ) {
     let stop_miner = Arc::new(AtomicBool::new(false));
@@ -841,20 +1198,27 @@ async fn handle_bitcoin_mining(
                 let stop_miner_reader = stop_miner.clone();
                 let devnet_event_tx_moved = devnet_event_tx.clone();
                 let config_moved = config.clone();
+                let tenure_processed_rx = tenure_processed_rx.clone();
+                // discard the tenures processed while the miner was paused
+                while tenure_processed_rx.try_recv().is_ok() {}
                 let _ =
                     hiro_system_kit::thread_named("Bitcoin mining runloop").spawn(move || loop {
-                        std::thread::sleep(std::time::Duration::from_millis(
+                        let block_time = Duration::from_millis(
                             config_moved
                                 .devnet_config
                                 .bitcoin_controller_block_time
                                 .into(),
-                        ));
-                        let future = mine_bitcoin_block(
-                            &config_moved.services_map_hosts.bitcoin_node_host,
-                            &config_moved.devnet_config.bitcoin_node_username,
-                            &config_moved.devnet_config.bitcoin_node_password,
-                            &config_moved.devnet_config.miner_btc_address,
                         );
+                        if config_moved
+                            .devnet_config
+                            .bitcoin_controller_accelerated_mining
+                        {
+                            // the block time acts as a timeout, for the blocks without tenure
+                            let _ = tenure_processed_rx.recv_timeout(block_time);
+                        } else {
+                            std::thread::sleep(block_time);
+                        }
+                        let future = mine_next_bitcoin_block(&config_moved);
                         let res = hiro_system_kit::nestable_block_on(future);
                         if stop_miner_reader.load(Ordering::SeqCst) {
                             break;
@@ -868,23 +1232,31 @@ async fn handle_bitcoin_mining(
                 stop_miner.store(true, Ordering::SeqCst);
             }
             BitcoinMiningCommand::Mine => {
-                let res = mine_bitcoin_block(
+                let res = mine_next_bitcoin_block(config).await;
+                if let Err(e) = res {
+                    let _ = devnet_event_tx.send(DevnetEvent::error(e));
+                }
+            }
+            BitcoinMiningCommand::InvalidateChainTip => {
+                let res = reorg_bitcoin_chain(
                     &config.services_map_hosts.bitcoin_node_host,
                     config.devnet_config.bitcoin_node_username.as_str(),
                     config.devnet_config.bitcoin_node_password.as_str(),
                     config.devnet_config.miner_btc_address.as_str(),
+                    config.devnet_config.bitcoin_controller_accelerated_mining,
+                    1,
                 )
                 .await;
                 if let Err(e) = res {
                     let _ = devnet_event_tx.send(DevnetEvent::error(e));
                 }
             }
-            BitcoinMiningCommand::InvalidateChainTip => {
-                invalidate_bitcoin_chain_tip(
-                    &config.services_map_hosts.bitcoin_node_host,
-                    config.devnet_config.bitcoin_node_username.as_str(),
-                    config.devnet_config.bitcoin_node_password.as_str(),
-                );
+            BitcoinMiningCommand::InjectFault(fault) => {
+                let res = inject_fault(&fault, config, events_delay_ms, devnet_event_tx).await;
+                if let Err(e) = res {
+                    let _ = devnet_event_tx
+                        .send(DevnetEvent::error(format!("unable to inject fault: {}", e)));
+                }
             }
         }
     }
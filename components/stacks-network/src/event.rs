@@ -72,26 +72,29 @@ impl DevnetEvent {
 
 pub fn send_status_update(
     event_tx: &Sender<DevnetEvent>,
-    with_subnets: bool,
     name: &str,
     status: Status,
     comment: &str,
 ) {
-    // leaving it a variable in case we want to make it dynamic in the future
-    let signers_services = 2;
-    let subnet_services = if with_subnets { 2 } else { 0 };
-
+    // `order` is a sort key: numbered services (extra stacks nodes, signers) are
+    // listed right after their parent service, whatever their count
+    let numbered = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .and_then(|number| number.parse::<usize>().ok())
+    };
     let order = match name {
         "bitcoin-node" => 0,
-        "stacks-node" => 1,
-        "stacks-signer-1" => 2,
-        "stacks-signer-2" => 3,
-        "stacks-api" => signers_services + 2,
-        "subnet-node" => signers_services + 3,
-        "subnet-api" => signers_services + 4,
-        "stacks-explorer" => signers_services + subnet_services + 3,
-        "bitcoin-explorer" => signers_services + subnet_services + 4,
-        _ => return,
+        "stacks-node" => 1_000,
+        "stacks-api" => 3_000,
+        "subnet-node" => 4_000,
+        "subnet-api" => 5_000,
+        "stacks-explorer" => 6_000,
+        "bitcoin-explorer" => 7_000,
+        name => match (numbered("stacks-node-"), numbered("stacks-signer-")) {
+            (Some(node_number), _) => 1_000 + node_number,
+            (_, Some(signer_id)) => 2_000 + signer_id,
+            _ => return,
+        },
     };
 
     let _ = event_tx.send(DevnetEvent::ServiceStatus(ServiceStatusData {
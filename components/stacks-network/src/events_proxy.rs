@@ -0,0 +1,428 @@
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hyper::body::Bytes;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::Instant;
+
+use crate::event::DevnetEvent;
+use crate::events_recording::EventsRecorder;
+
+/// Attempts to deliver an event to an observer, as the `retry_count` of the nodes
+const RELAY_RETRY_COUNT: u32 = 255;
+const RELAY_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Payload posted by a stacks-node, e.g. on `/new_block`
+#[derive(Clone)]
+struct RelayedEvent {
+    path: String,
+    body: Bytes,
+    deliver_at: Instant,
+}
+
+/// Relay the events posted by the stacks nodes to the event observers listed in
+/// `stacks_node_events_observers`. Events are acknowledged right away, and delivered
+/// in order once `events_delay_ms` (set by the `delay_events` fault) has elapsed.
+/// Failed deliveries are retried, as the nodes do. When a recorder is given, the
+/// payloads are recorded as they are received. Returns the address the proxy is listening on.
+pub fn start_events_proxy(
+    port: u16,
+    observers: Vec<String>,
+    events_delay_ms: Arc<AtomicU64>,
+    recorder: Option<EventsRecorder>,
+    devnet_event_tx: Sender<DevnetEvent>,
+) -> Result<SocketAddr, String> {
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .map_err(|e| format!("unable to start events proxy on port {}: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("unable to start events proxy: {}", e))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| format!("unable to start events proxy: {}", e))?;
+
+    let _ = hiro_system_kit::thread_named("Events proxy").spawn(move || {
+        let recorder = recorder.map(|recorder| Arc::new(Mutex::new(recorder)));
+        let future = serve_events_proxy(
+            listener,
+            observers,
+            events_delay_ms,
+            recorder,
+            devnet_event_tx,
+        );
+        hiro_system_kit::nestable_block_on(future);
+    });
+    Ok(local_addr)
+}
+
+async fn serve_events_proxy(
+    listener: TcpListener,
+    observers: Vec<String>,
+    events_delay_ms: Arc<AtomicU64>,
+    recorder: Option<Arc<Mutex<EventsRecorder>>>,
+    devnet_event_tx: Sender<DevnetEvent>,
+) {
+    // each observer gets its own queue, so that an unreachable observer doesn't hold the others
+    let mut observers_tx = vec![];
+    for observer in observers.iter() {
+        let (observer_tx, observer_rx) = unbounded_channel();
+        tokio::spawn(relay_events(
+            observer_rx,
+            host_observer_address(observer),
+            devnet_event_tx.clone(),
+        ));
+        observers_tx.push(observer_tx);
+    }
+    let observers_tx = Arc::new(observers_tx);
+
+    let server = match Server::from_tcp(listener) {
+        Ok(server) => server,
+        Err(e) => {
+            let _ = devnet_event_tx.send(DevnetEvent::error(format!(
+                "unable to start events proxy: {}",
+                e
+            )));
+            return;
+        }
+    };
+    let devnet_event_tx_moved = devnet_event_tx.clone();
+    let make_service = make_service_fn(move |_| {
+        let observers_tx = observers_tx.clone();
+        let events_delay_ms = events_delay_ms.clone();
+        let recorder = recorder.clone();
+        let devnet_event_tx = devnet_event_tx_moved.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                receive_event(
+                    request,
+                    observers_tx.clone(),
+                    events_delay_ms.clone(),
+                    recorder.clone(),
+                    devnet_event_tx.clone(),
+                )
+            }))
+        }
+    });
+    if let Err(e) = server.serve(make_service).await {
+        let _ = devnet_event_tx.send(DevnetEvent::error(format!("events proxy stopped: {}", e)));
+    }
+}
+
+async fn receive_event(
+    request: Request<Body>,
+    observers_tx: Arc<Vec<UnboundedSender<RelayedEvent>>>,
+    events_delay_ms: Arc<AtomicU64>,
+    recorder: Option<Arc<Mutex<EventsRecorder>>>,
+    devnet_event_tx: Sender<DevnetEvent>,
+) -> Result<Response<Body>, Infallible> {
+    let path = request.uri().path().to_string();
+    // the delay in effect when the event is received applies
+    let delay = Duration::from_millis(events_delay_ms.load(Ordering::SeqCst));
+    if let Ok(body) = hyper::body::to_bytes(request.into_body()).await {
+        if let Some(recorder) = recorder {
+            if let Err(e) = recorder.lock().unwrap().record(&path, &body) {
+                let _ = devnet_event_tx.send(DevnetEvent::error(e));
+            }
+        }
+        let event = RelayedEvent {
+            path,
+            body,
+            deliver_at: Instant::now() + delay,
+        };
+        for observer_tx in observers_tx.iter() {
+            let _ = observer_tx.send(event.clone());
+        }
+    }
+    Ok(Response::new(Body::empty()))
+}
+
+/// The observers are configured with addresses reachable from the containers, while the
+/// proxy runs on the host: `host.docker.internal` is the host itself.
+fn host_observer_address(observer: &str) -> String {
+    match observer.strip_prefix("host.docker.internal") {
+        Some(port) => format!("localhost{}", port),
+        None => observer.to_string(),
+    }
+}
+
+async fn relay_events(
+    mut events_rx: UnboundedReceiver<RelayedEvent>,
+    observer: String,
+    devnet_event_tx: Sender<DevnetEvent>,
+) {
+    let client = reqwest::Client::new();
+    while let Some(event) = events_rx.recv().await {
+        tokio::time::sleep_until(event.deliver_at).await;
+        // like the nodes, retry until the observer accepts the event, to keep them in order
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let res = client
+                .post(format!("http://{}{}", observer, event.path))
+                .header("Content-Type", "application/json")
+                .body(event.body.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            let error = match res {
+                Ok(_) => break,
+                Err(e) => e,
+            };
+            if attempts == 1 {
+                let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+                    "unable to relay {} to {} ({}), retrying",
+                    event.path, observer, error
+                )));
+            }
+            if attempts >= RELAY_RETRY_COUNT {
+                let _ = devnet_event_tx.send(DevnetEvent::error(format!(
+                    "unable to relay {} to {} after {} attempts: {}",
+                    event.path, observer, attempts, error
+                )));
+                break;
+            }
+            tokio::time::sleep(RELAY_RETRY_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events_recording::{load_recorded_events, replay_recorded_events};
+    use crate::log::{LogData, LogLevel};
+    use std::sync::mpsc::channel;
+
+    /// Start an event observer recording the paths it receives
+    fn start_observer() -> (SocketAddr, Arc<Mutex<Vec<(String, String)>>>) {
+        start_failing_observer(0)
+    }
+
+    /// Start an event observer rejecting the first `failures` requests
+    fn start_failing_observer(failures: usize) -> (SocketAddr, Arc<Mutex<Vec<(String, String)>>>) {
+        let received = Arc::new(Mutex::new(vec![]));
+        let failures = Arc::new(AtomicU64::new(failures as u64));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let received_moved = received.clone();
+        std::thread::spawn(move || {
+            hiro_system_kit::nestable_block_on(async move {
+                let make_service = make_service_fn(move |_| {
+                    let received = received_moved.clone();
+                    let failures = failures.clone();
+                    async move {
+                        Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                            let received = received.clone();
+                            let failures = failures.clone();
+                            async move {
+                                if failures.load(Ordering::SeqCst) > 0 {
+                                    failures.fetch_sub(1, Ordering::SeqCst);
+                                    let mut response = Response::new(Body::empty());
+                                    *response.status_mut() =
+                                        hyper::StatusCode::INTERNAL_SERVER_ERROR;
+                                    return Ok::<_, Infallible>(response);
+                                }
+                                let path = request.uri().path().to_string();
+                                let body =
+                                    hyper::body::to_bytes(request.into_body()).await.unwrap();
+                                let body = String::from_utf8(body.to_vec()).unwrap();
+                                received.lock().unwrap().push((path, body));
+                                Ok::<_, Infallible>(Response::new(Body::empty()))
+                            }
+                        }))
+                    }
+                });
+                Server::from_tcp(listener)
+                    .unwrap()
+                    .serve(make_service)
+                    .await
+                    .unwrap();
+            })
+        });
+        (addr, received)
+    }
+
+    fn post(proxy_addr: &SocketAddr, path: &str, body: &str) {
+        let response = reqwest::blocking::Client::new()
+            .post(format!("http://127.0.0.1:{}{}", proxy_addr.port(), path))
+            .body(body.to_string())
+            .send()
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    fn wait_for(received: &Arc<Mutex<Vec<(String, String)>>>, count: usize) {
+        for _ in 0..100 {
+            if received.lock().unwrap().len() >= count {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        panic!("events not relayed");
+    }
+
+    #[test]
+    fn test_relay_events_in_order() {
+        let (observer_addr, received) = start_observer();
+        let (devnet_event_tx, _devnet_event_rx) = channel();
+        let events_delay_ms = Arc::new(AtomicU64::new(0));
+        let proxy_addr = start_events_proxy(
+            0,
+            vec![observer_addr.to_string()],
+            events_delay_ms,
+            None,
+            devnet_event_tx,
+        )
+        .unwrap();
+
+        post(&proxy_addr, "/new_burn_block", r#"{"burn_block_height":1}"#);
+        post(&proxy_addr, "/new_block", r#"{"block_height":1}"#);
+        wait_for(&received, 2);
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                (
+                    "/new_burn_block".to_string(),
+                    r#"{"burn_block_height":1}"#.to_string()
+                ),
+                (
+                    "/new_block".to_string(),
+                    r#"{"block_height":1}"#.to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retry_failed_relays() {
+        let (observer_addr, received) = start_failing_observer(1);
+        let (devnet_event_tx, devnet_event_rx) = channel();
+        let proxy_addr = start_events_proxy(
+            0,
+            vec![observer_addr.to_string()],
+            Arc::new(AtomicU64::new(0)),
+            None,
+            devnet_event_tx,
+        )
+        .unwrap();
+
+        post(&proxy_addr, "/new_block", r#"{"block_height":1}"#);
+        post(&proxy_addr, "/new_block", r#"{"block_height":2}"#);
+        // the rejected event is delivered again, before the next one
+        for _ in 0..150 {
+            if received.lock().unwrap().len() >= 2 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                (
+                    "/new_block".to_string(),
+                    r#"{"block_height":1}"#.to_string()
+                ),
+                (
+                    "/new_block".to_string(),
+                    r#"{"block_height":2}"#.to_string()
+                ),
+            ]
+        );
+        assert!(matches!(
+            devnet_event_rx.recv().unwrap(),
+            DevnetEvent::Log(LogData {
+                level: LogLevel::Warning,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_host_observer_address() {
+        assert_eq!(
+            host_observer_address("host.docker.internal:8002"),
+            "localhost:8002"
+        );
+        assert_eq!(host_observer_address("127.0.0.1:8002"), "127.0.0.1:8002");
+    }
+
+    #[test]
+    fn test_delay_events() {
+        let (observer_addr, received) = start_observer();
+        let (devnet_event_tx, _devnet_event_rx) = channel();
+        let events_delay_ms = Arc::new(AtomicU64::new(500));
+        let proxy_addr = start_events_proxy(
+            0,
+            vec![observer_addr.to_string()],
+            events_delay_ms.clone(),
+            None,
+            devnet_event_tx,
+        )
+        .unwrap();
+
+        // the node gets its response without waiting for the delay
+        let sent_at = std::time::Instant::now();
+        post(&proxy_addr, "/new_block", "{}");
+        assert!(sent_at.elapsed() < Duration::from_millis(500));
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(received.lock().unwrap().is_empty());
+        wait_for(&received, 1);
+        assert!(sent_at.elapsed() >= Duration::from_millis(500));
+
+        // once healed, events are delivered right away
+        events_delay_ms.store(0, Ordering::SeqCst);
+        let sent_at = std::time::Instant::now();
+        post(&proxy_addr, "/new_block", "{}");
+        wait_for(&received, 2);
+        assert!(sent_at.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_record_and_replay_events() {
+        let mut recording_path = std::env::temp_dir();
+        recording_path.push(format!(
+            "clarinet-events-proxy-{}-{}.ndjson",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let recording_path = recording_path.to_str().unwrap().to_string();
+
+        let (observer_addr, received) = start_observer();
+        let (devnet_event_tx, _devnet_event_rx) = channel();
+        let proxy_addr = start_events_proxy(
+            0,
+            vec![observer_addr.to_string()],
+            Arc::new(AtomicU64::new(0)),
+            Some(EventsRecorder::new(&recording_path).unwrap()),
+            devnet_event_tx,
+        )
+        .unwrap();
+        post(&proxy_addr, "/new_burn_block", r#"{"burn_block_height":1}"#);
+        post(&proxy_addr, "/new_mempool_tx", r#"["0x00"]"#);
+        post(&proxy_addr, "/new_block", r#"{"block_height":1}"#);
+        wait_for(&received, 3);
+
+        // the recording is replayed on the paths the node posted to
+        let events = load_recorded_events(&recording_path).unwrap();
+        let _ = std::fs::remove_file(&recording_path);
+        let (replay_observer_addr, replayed) = start_observer();
+        hiro_system_kit::nestable_block_on(replay_recorded_events(
+            &events,
+            &format!("http://{}", replay_observer_addr),
+            0.0,
+            |_, _| {},
+        ))
+        .unwrap();
+        assert_eq!(*replayed.lock().unwrap(), *received.lock().unwrap());
+    }
+}
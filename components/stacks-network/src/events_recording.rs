@@ -0,0 +1,241 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::Client as HttpClient;
+use serde_json::value::RawValue;
+
+/// One line of a recording (ndjson): a payload posted by a stacks-node to its
+/// event observers, e.g. on `/new_block` or `/new_burn_block`. The payload is
+/// kept as posted, large integers included.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedEvent {
+    pub timestamp_ms: u64,
+    pub path: String,
+    pub payload: Box<RawValue>,
+}
+
+/// Hashes identifying the blocks posted on `/new_block` and `/new_burn_block`
+#[derive(Deserialize)]
+struct PostedBlock {
+    index_block_hash: Option<String>,
+    burn_block_hash: Option<String>,
+}
+
+pub struct EventsRecorder {
+    writer: BufWriter<File>,
+    recorded_events: HashSet<u64>,
+}
+
+impl EventsRecorder {
+    pub fn new(path: &str) -> Result<EventsRecorder, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("unable to create recording file {} ({})", path, e))?;
+        Ok(EventsRecorder {
+            writer: BufWriter::new(file),
+            recorded_events: HashSet::new(),
+        })
+    }
+
+    /// Record the body posted by the node on `path`. Every stacks node posts the
+    /// same events: blocks are recorded once per block hash, other payloads once.
+    pub fn record(&mut self, path: &str, body: &[u8]) -> Result<(), String> {
+        let payload = std::str::from_utf8(body)
+            .map_err(|e| e.to_string())
+            .and_then(|body| {
+                RawValue::from_string(body.trim().to_string()).map_err(|e| e.to_string())
+            })
+            .map_err(|e| format!("unable to parse event posted on {} ({})", path, e))?;
+        if !self.recorded_events.insert(event_key(path, payload.get())) {
+            return Ok(());
+        }
+        let event = RecordedEvent {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            path: path.to_string(),
+            payload,
+        };
+        let line = serde_json::to_string(&event)
+            .map_err(|e| format!("unable to serialize event ({})", e))?;
+        writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| format!("unable to record event ({})", e))
+    }
+}
+
+fn event_key(path: &str, payload: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    let block_hash =
+        serde_json::from_str::<PostedBlock>(payload)
+            .ok()
+            .and_then(|block| match path {
+                "/new_block" => block.index_block_hash,
+                "/new_burn_block" => block.burn_block_hash,
+                _ => None,
+            });
+    match block_hash {
+        Some(block_hash) => block_hash.hash(&mut hasher),
+        None => payload.hash(&mut hasher),
+    }
+    hasher.finish()
+}
+
+pub fn load_recorded_events(path: &str) -> Result<Vec<RecordedEvent>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("unable to open recording {} ({})", path, e))?;
+    let mut events = vec![];
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("unable to read recording {} ({})", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str::<RecordedEvent>(&line)
+            .map_err(|e| format!("invalid event at line {} of {} ({})", i + 1, path, e))?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Post the recorded events to `observer_url`, on the path the node posted them to,
+/// keeping the original intervals between events divided by `speed` (`speed = 0`
+/// disables the waiting).
+pub async fn replay_recorded_events<F>(
+    events: &[RecordedEvent],
+    observer_url: &str,
+    speed: f64,
+    on_event_replayed: F,
+) -> Result<(), String>
+where
+    F: Fn(usize, &RecordedEvent),
+{
+    let client = HttpClient::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("unable to build http client ({})", e))?;
+    let observer_url = observer_url.trim_end_matches('/');
+
+    let mut previous_timestamp_ms = None;
+    for (i, event) in events.iter().enumerate() {
+        if let Some(previous_timestamp_ms) = previous_timestamp_ms {
+            let interval_ms = event.timestamp_ms.saturating_sub(previous_timestamp_ms);
+            if speed > 0.0 && interval_ms > 0 {
+                let wait = Duration::from_millis((interval_ms as f64 / speed) as u64);
+                tokio::time::sleep(wait).await;
+            }
+        }
+        previous_timestamp_ms = Some(event.timestamp_ms);
+
+        let url = format!("{}{}", observer_url, event.path);
+        let response = client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .body(event.payload.get().to_string())
+            .send()
+            .await
+            .map_err(|e| format!("unable to post event {} to {} ({})", i + 1, url, e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "observer rejected event {} ({})",
+                i + 1,
+                response.status()
+            ));
+        }
+        on_event_replayed(i, event);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_events_round_trip() {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "clarinet-events-recording-{}-{}.ndjson",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let path = path.to_str().unwrap().to_string();
+
+        let mut recorder = EventsRecorder::new(&path).unwrap();
+        recorder
+            .record("/new_burn_block", br#"{"burn_block_height":101}"#)
+            .unwrap();
+        recorder
+            .record(
+                "/new_block",
+                br#"{"block_height":1,"burn_block_height":101}"#,
+            )
+            .unwrap();
+        assert!(recorder.record("/new_block", b"not json").is_err());
+
+        let events = load_recorded_events(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].path, "/new_burn_block");
+        assert_eq!(events[1].path, "/new_block");
+        assert_eq!(
+            events[1].payload.get(),
+            r#"{"block_height":1,"burn_block_height":101}"#
+        );
+        assert!(events[0].timestamp_ms <= events[1].timestamp_ms);
+    }
+
+    #[test]
+    fn test_record_events_once() {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "clarinet-events-recording-once-{}-{}.ndjson",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let path = path.to_str().unwrap().to_string();
+
+        let mut recorder = EventsRecorder::new(&path).unwrap();
+        // the same block, posted by two nodes
+        recorder
+            .record(
+                "/new_block",
+                br#"{"index_block_hash":"0x01","block_height":1,"anchored_cost":{"runtime":1}}"#,
+            )
+            .unwrap();
+        recorder
+            .record(
+                "/new_block",
+                br#"{"index_block_hash":"0x01","block_height":1,"anchored_cost":{"runtime":2}}"#,
+            )
+            .unwrap();
+        recorder.record("/new_mempool_tx", br#"["0x00"]"#).unwrap();
+        recorder.record("/new_mempool_tx", br#"["0x00"]"#).unwrap();
+        // integers are kept as posted
+        recorder
+            .record(
+                "/new_block",
+                br#"{"index_block_hash":"0x02","amount":340282366920938463463374607431768211455}"#,
+            )
+            .unwrap();
+
+        let events = load_recorded_events(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let paths = events.iter().map(|e| e.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, ["/new_block", "/new_mempool_tx", "/new_block"]);
+        assert_eq!(
+            events[2].payload.get(),
+            r#"{"index_block_hash":"0x02","amount":340282366920938463463374607431768211455}"#
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bollard::models::EndpointSettings;
+use bollard::network::{ConnectNetworkOptions, CreateNetworkOptions, DisconnectNetworkOptions};
+use bollard::Docker;
+use clarinet_files::{DevnetFault, ScheduledFault};
+
+use crate::chains_coordinator::{reorg_bitcoin_chain, DevnetEventObserverConfig};
+use crate::event::DevnetEvent;
+
+pub async fn inject_fault(
+    fault: &DevnetFault,
+    config: &DevnetEventObserverConfig,
+    events_delay_ms: &Arc<AtomicU64>,
+    devnet_event_tx: &Sender<DevnetEvent>,
+) -> Result<(), String> {
+    match fault {
+        DevnetFault::BitcoinReorg { depth } => {
+            reorg_bitcoin_chain(
+                &config.services_map_hosts.bitcoin_node_host,
+                &config.devnet_config.bitcoin_node_username,
+                &config.devnet_config.bitcoin_node_password,
+                &config.devnet_config.miner_btc_address,
+                config.devnet_config.bitcoin_controller_accelerated_mining,
+                *depth,
+            )
+            .await?;
+            let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+                "Fault injected: bitcoin reorg ({} blocks deep)",
+                depth
+            )));
+        }
+        DevnetFault::PauseService {
+            service,
+            duration_ms,
+        } => {
+            let (docker, network_name) = connect_docker(config)?;
+            let container = format!("{}.{}", service, network_name);
+            docker
+                .pause_container(&container)
+                .await
+                .map_err(|e| format!("unable to pause {}: {}", service, e))?;
+            let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+                "Fault injected: {} paused for {}ms",
+                service, duration_ms
+            )));
+
+            let service = service.clone();
+            heal_after(*duration_ms, devnet_event_tx, async move {
+                docker
+                    .unpause_container(&container)
+                    .await
+                    .map_err(|e| format!("unable to resume {}: {}", service, e))?;
+                Ok(format!("{} resumed", service))
+            });
+        }
+        DevnetFault::Partition {
+            services,
+            duration_ms,
+        } => {
+            // the services are moved to a network of their own: they still reach
+            // each other, but not the rest of the devnet
+            let (docker, network_name) = connect_docker(config)?;
+            let partition_name = format!("{}.partition", network_name);
+            let mut labels = HashMap::new();
+            labels.insert("project", network_name.as_str());
+            docker
+                .create_network::<&str>(CreateNetworkOptions {
+                    name: &partition_name,
+                    driver: "bridge",
+                    labels,
+                    ..Default::default()
+                })
+                .await
+                .map_err(|e| format!("unable to create the partition network: {}", e))?;
+            for service in services.iter() {
+                let container = format!("{}.{}", service, network_name);
+                move_container(&docker, &container, &network_name, &partition_name)
+                    .await
+                    .map_err(|e| format!("unable to partition {}: {}", service, e))?;
+            }
+            let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+                "Fault injected: {} partitioned from the rest of the devnet for {}ms",
+                services.join(", "),
+                duration_ms
+            )));
+
+            let services = services.clone();
+            heal_after(*duration_ms, devnet_event_tx, async move {
+                for service in services.iter() {
+                    let container = format!("{}.{}", service, network_name);
+                    move_container(&docker, &container, &partition_name, &network_name)
+                        .await
+                        .map_err(|e| format!("unable to reconnect {}: {}", service, e))?;
+                }
+                let _ = docker.remove_network(&partition_name).await;
+                Ok(format!(
+                    "{} reconnected to the rest of the devnet",
+                    services.join(", ")
+                ))
+            });
+        }
+        DevnetFault::DelayEvents {
+            delay_ms,
+            duration_ms,
+        } => {
+            // unless delays are enabled, the nodes post to the observers directly
+            if !config.devnet_config.relays_events_through_proxy() {
+                return Err(
+                    "delay_events requires enable_events_delay = true in Devnet.toml (or clarinet devnet start --enable-events-delay)".into(),
+                );
+            }
+            delay_events(*delay_ms, *duration_ms, events_delay_ms, devnet_event_tx)
+        }
+    }
+    Ok(())
+}
+
+/// Faults scheduled up to `bitcoin_block_height` that were not triggered yet
+pub fn take_due_faults(
+    faults: &[ScheduledFault],
+    bitcoin_block_height: u64,
+    triggered_faults: &mut HashSet<usize>,
+) -> Vec<DevnetFault> {
+    faults
+        .iter()
+        .enumerate()
+        .filter(|(i, scheduled_fault)| {
+            scheduled_fault.at_block <= bitcoin_block_height && triggered_faults.insert(*i)
+        })
+        .map(|(_, scheduled_fault)| scheduled_fault.fault.clone())
+        .collect()
+}
+
+/// The events proxy holds the events relayed to the observers while `events_delay_ms` is set
+fn delay_events(
+    delay_ms: u64,
+    duration_ms: u64,
+    events_delay_ms: &Arc<AtomicU64>,
+    devnet_event_tx: &Sender<DevnetEvent>,
+) {
+    events_delay_ms.store(delay_ms, Ordering::SeqCst);
+    let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+        "Fault injected: chain events delayed by {}ms for {}ms",
+        delay_ms, duration_ms
+    )));
+
+    let events_delay_ms = events_delay_ms.clone();
+    heal_after(duration_ms, devnet_event_tx, async move {
+        events_delay_ms.store(0, Ordering::SeqCst);
+        Ok("Chain events delivered without delay".to_string())
+    });
+}
+
+/// Connect a container to the network `to` before disconnecting it from `from`
+async fn move_container(
+    docker: &Docker,
+    container: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), bollard::errors::Error> {
+    docker
+        .connect_network(
+            to,
+            ConnectNetworkOptions {
+                container,
+                endpoint_config: EndpointSettings::default(),
+            },
+        )
+        .await?;
+    docker
+        .disconnect_network(
+            from,
+            DisconnectNetworkOptions {
+                container,
+                force: true,
+            },
+        )
+        .await
+}
+
+fn connect_docker(config: &DevnetEventObserverConfig) -> Result<(Docker, String), String> {
+    let network_name = match config.services_map_hosts.docker_network_name {
+        Some(ref network_name) => network_name.clone(),
+        None => return Err("this fault can only be injected in a docker based devnet".into()),
+    };
+    let docker = Docker::connect_with_socket(
+        &config.devnet_config.docker_host,
+        120,
+        bollard::API_DEFAULT_VERSION,
+    )
+    .or_else(|_| Docker::connect_with_socket_defaults())
+    .map_err(|e| format!("unable to connect to docker: {:?}", e))?;
+    Ok((docker, network_name))
+}
+
+/// Revert a fault once `duration_ms` has elapsed, without blocking the caller.
+fn heal_after<F>(duration_ms: u64, devnet_event_tx: &Sender<DevnetEvent>, heal: F)
+where
+    F: Future<Output = Result<String, String>> + Send + 'static,
+{
+    let devnet_event_tx = devnet_event_tx.clone();
+    let _ = hiro_system_kit::thread_named("Fault healing").spawn(move || {
+        std::thread::sleep(Duration::from_millis(duration_ms));
+        match hiro_system_kit::nestable_block_on(heal) {
+            Ok(message) => {
+                let _ = devnet_event_tx.send(DevnetEvent::success(message));
+            }
+            Err(message) => {
+                let _ = devnet_event_tx.send(DevnetEvent::error(message));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::{LogData, LogLevel};
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_take_due_faults() {
+        let faults = vec![
+            ScheduledFault {
+                at_block: 110,
+                fault: DevnetFault::BitcoinReorg { depth: 2 },
+            },
+            ScheduledFault {
+                at_block: 105,
+                fault: DevnetFault::PauseService {
+                    service: "stacks-node".into(),
+                    duration_ms: 1000,
+                },
+            },
+        ];
+        let mut triggered_faults = HashSet::new();
+
+        assert!(take_due_faults(&faults, 100, &mut triggered_faults).is_empty());
+        assert_eq!(
+            take_due_faults(&faults, 105, &mut triggered_faults),
+            vec![faults[1].fault.clone()]
+        );
+        // a fault is only triggered once, even when blocks are skipped or reorged
+        assert_eq!(
+            take_due_faults(&faults, 120, &mut triggered_faults),
+            vec![faults[0].fault.clone()]
+        );
+        assert!(take_due_faults(&faults, 108, &mut triggered_faults).is_empty());
+        assert!(take_due_faults(&faults, 130, &mut triggered_faults).is_empty());
+    }
+
+    #[test]
+    fn test_delay_events_heals() {
+        let events_delay_ms = Arc::new(AtomicU64::new(0));
+        let (devnet_event_tx, devnet_event_rx) = channel();
+
+        delay_events(2000, 200, &events_delay_ms, &devnet_event_tx);
+        assert_eq!(events_delay_ms.load(Ordering::SeqCst), 2000);
+        assert!(matches!(
+            devnet_event_rx.recv().unwrap(),
+            DevnetEvent::Log(LogData {
+                level: LogLevel::Warning,
+                ..
+            })
+        ));
+
+        // healed once the duration has elapsed
+        let healed = devnet_event_rx
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert!(matches!(
+            healed,
+            DevnetEvent::Log(LogData {
+                level: LogLevel::Success,
+                ..
+            })
+        ));
+        assert_eq!(events_delay_ms.load(Ordering::SeqCst), 0);
+    }
+}
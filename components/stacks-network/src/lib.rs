@@ -6,8 +6,12 @@ extern crate serde_derive;
 mod chainhooks;
 pub mod chains_coordinator;
 mod event;
+mod events_proxy;
+pub mod events_recording;
+mod faults;
 mod log;
 mod orchestrator;
+mod scripts;
 mod ui;
 
 pub use chainhook_sdk::observer::MempoolAdmissionData;
@@ -11,11 +11,12 @@ use bollard::service::Ipam;
 use bollard::Docker;
 use chainhook_sdk::utils::Context;
 use clarinet_files::chainhook_types::StacksNetwork;
-use clarinet_files::{DevnetConfigFile, NetworkManifest, ProjectManifest};
+use clarinet_files::{DevnetConfig, DevnetConfigFile, NetworkManifest, ProjectManifest};
 use futures::stream::TryStreamExt;
 use hiro_system_kit::slog;
 use reqwest::RequestBuilder;
 use serde_json::Value as JsonValue;
+use stackslib::types::chainstate::{StacksPrivateKey, StacksPublicKey};
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::Write;
@@ -23,6 +24,7 @@ use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender};
 use std::time::Duration;
 
+use crate::chains_coordinator::accelerated_mining_timestamp;
 use crate::event::{send_status_update, DevnetEvent, Status};
 
 #[derive(Debug)]
@@ -34,8 +36,8 @@ pub struct DevnetOrchestrator {
     pub termination_success_tx: Option<Sender<bool>>,
     pub can_exit: bool,
     stacks_node_container_id: Option<String>,
-    stacks_signer_1_container_id: Option<String>,
-    stacks_signer_2_container_id: Option<String>,
+    extra_stacks_node_container_ids: Vec<String>,
+    stacks_signers_container_ids: HashMap<u32, String>,
     stacks_api_container_id: Option<String>,
     stacks_explorer_container_id: Option<String>,
     bitcoin_node_container_id: Option<String>,
@@ -61,12 +63,14 @@ pub struct DevnetOrchestrator {
 pub struct ServicesMapHosts {
     pub bitcoin_node_host: String,
     pub stacks_node_host: String,
+    pub extra_stacks_node_hosts: Vec<String>,
     pub stacks_api_host: String,
     pub postgres_host: String,
     pub stacks_explorer_host: String,
     pub bitcoin_explorer_host: String,
     pub subnet_node_host: String,
     pub subnet_api_host: String,
+    pub docker_network_name: Option<String>,
 }
 
 impl DevnetOrchestrator {
@@ -154,8 +158,8 @@ impl DevnetOrchestrator {
             can_exit: true,
             termination_success_tx: None,
             stacks_node_container_id: None,
-            stacks_signer_1_container_id: None,
-            stacks_signer_2_container_id: None,
+            extra_stacks_node_container_ids: vec![],
+            stacks_signers_container_ids: HashMap::new(),
             stacks_api_container_id: None,
             stacks_explorer_container_id: None,
             bitcoin_node_container_id: None,
@@ -176,12 +180,14 @@ impl DevnetOrchestrator {
                 "bitcoind-chain-coordinator.{namespace}.svc.cluster.local:18443"
             ),
             stacks_node_host: format!("stacks-blockchain.{namespace}.svc.cluster.local:20443"),
+            extra_stacks_node_hosts: vec![],
             postgres_host: format!("stacks-blockchain-api.{namespace}.svc.cluster.local:5432"),
             stacks_api_host: format!("stacks-blockchain-api.{namespace}.svc.cluster.local:3999"),
             stacks_explorer_host: "localhost".into(), // todo (micaiah)
             bitcoin_explorer_host: "localhost".into(), // todo (micaiah)
             subnet_node_host: "localhost".into(),     // todo (micaiah)
             subnet_api_host: "localhost".into(),      // todo (micaiah)
+            docker_network_name: None,
         };
 
         self.services_map_hosts = Some(services_map_hosts.clone());
@@ -250,6 +256,11 @@ impl DevnetOrchestrator {
             ServicesMapHosts {
                 bitcoin_node_host: format!("{}:{}", gateway, devnet_config.bitcoin_node_rpc_port),
                 stacks_node_host: format!("{}:{}", gateway, devnet_config.stacks_node_rpc_port),
+                extra_stacks_node_hosts: devnet_config
+                    .extra_stacks_nodes
+                    .iter()
+                    .map(|node| format!("{}:{}", gateway, node.rpc_port))
+                    .collect(),
                 postgres_host: format!("{}:{}", gateway, devnet_config.postgres_port),
                 stacks_api_host: format!("{}:{}", gateway, devnet_config.stacks_api_port),
                 stacks_explorer_host: format!("{}:{}", gateway, devnet_config.stacks_explorer_port),
@@ -259,17 +270,24 @@ impl DevnetOrchestrator {
                 ),
                 subnet_node_host: format!("{}:{}", gateway, devnet_config.subnet_node_rpc_port),
                 subnet_api_host: format!("{}:{}", gateway, devnet_config.subnet_api_port),
+                docker_network_name: Some(self.network_name.clone()),
             }
         } else {
             ServicesMapHosts {
                 bitcoin_node_host: format!("localhost:{}", devnet_config.bitcoin_node_rpc_port),
                 stacks_node_host: format!("localhost:{}", devnet_config.stacks_node_rpc_port),
+                extra_stacks_node_hosts: devnet_config
+                    .extra_stacks_nodes
+                    .iter()
+                    .map(|node| format!("localhost:{}", node.rpc_port))
+                    .collect(),
                 postgres_host: format!("localhost:{}", devnet_config.postgres_port),
                 stacks_api_host: format!("localhost:{}", devnet_config.stacks_api_port),
                 stacks_explorer_host: format!("localhost:{}", devnet_config.stacks_explorer_port),
                 bitcoin_explorer_host: format!("localhost:{}", devnet_config.bitcoin_explorer_port),
                 subnet_node_host: format!("localhost:{}", devnet_config.subnet_node_rpc_port),
                 subnet_api_host: format!("localhost:{}", devnet_config.subnet_api_port),
+                docker_network_name: Some(self.network_name.clone()),
             }
         };
 
@@ -306,6 +324,16 @@ impl DevnetOrchestrator {
         let disable_bitcoin_explorer = devnet_config.disable_bitcoin_explorer;
         let enable_subnet_node = devnet_config.enable_subnet_node;
         let disable_subnet_api = devnet_config.disable_subnet_api;
+        let signers = devnet_config
+            .signers
+            .iter()
+            .map(|signer| (signer.wallet.clone(), signer.start))
+            .collect::<Vec<_>>();
+        let extra_stacks_nodes_names = devnet_config
+            .extra_stacks_nodes
+            .iter()
+            .map(|node| node.name.clone())
+            .collect::<Vec<_>>();
 
         let _ = fs::create_dir(&devnet_config.working_dir);
         let _ = fs::create_dir(format!("{}/conf", devnet_config.working_dir));
@@ -316,76 +344,32 @@ impl DevnetOrchestrator {
         let stacks_api_port = devnet_config.stacks_api_port;
         let subnet_api_port = devnet_config.subnet_api_port;
 
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "bitcoin-node",
-            Status::Red,
-            "initializing",
-        );
-
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-node",
-            Status::Red,
-            "initializing",
-        );
-
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-1",
-            Status::Red,
-            "initializing",
-        );
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-2",
-            Status::Red,
-            "initializing",
-        );
+        send_status_update(&event_tx, "bitcoin-node", Status::Red, "initializing");
 
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-api",
-            Status::Red,
-            "initializing",
-        );
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-explorer",
-            Status::Red,
-            "initializing",
-        );
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "bitcoin-explorer",
-            Status::Red,
-            "initializing",
-        );
+        send_status_update(&event_tx, "stacks-node", Status::Red, "initializing");
 
-        if enable_subnet_node {
-            send_status_update(
-                &event_tx,
-                enable_subnet_node,
-                "subnet-node",
-                Status::Red,
-                "initializing",
-            );
+        for signer_id in 1..=signers.len() {
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
-                "subnet-api",
+                &format!("stacks-signer-{signer_id}"),
                 Status::Red,
                 "initializing",
             );
         }
 
+        send_status_update(&event_tx, "stacks-api", Status::Red, "initializing");
+        send_status_update(&event_tx, "stacks-explorer", Status::Red, "initializing");
+        send_status_update(&event_tx, "bitcoin-explorer", Status::Red, "initializing");
+
+        if enable_subnet_node {
+            send_status_update(&event_tx, "subnet-node", Status::Red, "initializing");
+            send_status_update(&event_tx, "subnet-api", Status::Red, "initializing");
+        }
+
+        for name in extra_stacks_nodes_names.iter() {
+            send_status_update(&event_tx, name, Status::Red, "initializing");
+        }
+
         let _ = event_tx.send(DevnetEvent::info(format!(
             "Creating network {}",
             self.network_name
@@ -395,7 +379,6 @@ impl DevnetOrchestrator {
         let _ = event_tx.send(DevnetEvent::info("Starting bitcoin-node".to_string()));
         send_status_update(
             &event_tx,
-            enable_subnet_node,
             "bitcoin-node",
             Status::Yellow,
             "preparing container",
@@ -408,13 +391,7 @@ impl DevnetOrchestrator {
                 return Err(message);
             }
         };
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "bitcoin-node",
-            Status::Yellow,
-            "booting",
-        );
+        send_status_update(&event_tx, "bitcoin-node", Status::Yellow, "booting");
         match self.boot_bitcoin_node_container().await {
             Ok(_) => {
                 self.initialize_bitcoin_node(&event_tx).await?;
@@ -431,7 +408,6 @@ impl DevnetOrchestrator {
             // Start postgres
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
                 "stacks-api",
                 Status::Yellow,
                 "preparing postgres container",
@@ -455,7 +431,6 @@ impl DevnetOrchestrator {
             };
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
                 "stacks-api",
                 Status::Yellow,
                 "preparing container",
@@ -472,7 +447,6 @@ impl DevnetOrchestrator {
             };
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
                 "stacks-api",
                 Status::Green,
                 &format!("http://localhost:{}/doc", stacks_api_port),
@@ -499,13 +473,7 @@ impl DevnetOrchestrator {
                     return Err(message);
                 }
             };
-            send_status_update(
-                &event_tx,
-                enable_subnet_node,
-                "subnet-node",
-                Status::Yellow,
-                "booting",
-            );
+            send_status_update(&event_tx, "subnet-node", Status::Yellow, "booting");
             match self.boot_subnet_node_container().await {
                 Ok(_) => {}
                 Err(message) => {
@@ -527,7 +495,6 @@ impl DevnetOrchestrator {
                 };
                 send_status_update(
                     &event_tx,
-                    enable_subnet_node,
                     "subnet-api",
                     Status::Green,
                     &format!("http://localhost:{}/doc", subnet_api_port),
@@ -545,13 +512,7 @@ impl DevnetOrchestrator {
 
         // Start stacks-node
         let _ = event_tx.send(DevnetEvent::info("Starting stacks-node".to_string()));
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-node",
-            Status::Yellow,
-            "updating image",
-        );
+        send_status_update(&event_tx, "stacks-node", Status::Yellow, "updating image");
         match self.prepare_stacks_node_container(boot_index, ctx).await {
             Ok(_) => {}
             Err(message) => {
@@ -560,13 +521,7 @@ impl DevnetOrchestrator {
                 return Err(message);
             }
         };
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-node",
-            Status::Yellow,
-            "booting",
-        );
+        send_status_update(&event_tx, "stacks-node", Status::Yellow, "booting");
         match self.boot_stacks_node_container().await {
             Ok(_) => {}
             Err(message) => {
@@ -576,97 +531,87 @@ impl DevnetOrchestrator {
             }
         };
 
-        // Start stacks-signer-1
-        let _ = event_tx.send(DevnetEvent::info("Starting stacks-signer-1".to_string()));
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-1",
-            Status::Yellow,
-            "updating image",
-        );
-        match self
-            .prepare_stacks_signer_container(boot_index, ctx, 1)
-            .await
-        {
-            Ok(_) => {}
-            Err(message) => {
-                let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
-                self.kill(ctx, Some(&message)).await;
-                return Err(message);
-            }
-        };
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-1",
-            Status::Yellow,
-            "booting",
-        );
-        match self.boot_stacks_signer_container(1).await {
-            Ok(_) => {}
-            Err(message) => {
-                let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
-                self.kill(ctx, Some(&message)).await;
-                return Err(message);
+        // Start the signers marked as started in the signer set
+        let mut started_signers = 0;
+        for (i, (wallet, start)) in signers.iter().enumerate() {
+            let signer_id = i as u32 + 1;
+            let service_name = format!("stacks-signer-{signer_id}");
+            if !start {
+                send_status_update(
+                    &event_tx,
+                    &service_name,
+                    Status::Red,
+                    &format!("{wallet} (not started)"),
+                );
+                continue;
             }
-        };
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-1",
-            Status::Green,
-            "running",
-        );
+            let _ = event_tx.send(DevnetEvent::info(format!("Starting {service_name}")));
+            send_status_update(&event_tx, &service_name, Status::Yellow, "updating image");
+            match self
+                .prepare_stacks_signer_container(boot_index, ctx, signer_id)
+                .await
+            {
+                Ok(_) => {}
+                Err(message) => {
+                    let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
+                    self.kill(ctx, Some(&message)).await;
+                    return Err(message);
+                }
+            };
+            send_status_update(&event_tx, &service_name, Status::Yellow, "booting");
+            match self.boot_stacks_signer_container(signer_id).await {
+                Ok(_) => {}
+                Err(message) => {
+                    let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
+                    self.kill(ctx, Some(&message)).await;
+                    return Err(message);
+                }
+            };
+            send_status_update(
+                &event_tx,
+                &service_name,
+                Status::Green,
+                &format!("{wallet} (running)"),
+            );
+            started_signers += 1;
+        }
+        if started_signers < signers.len() {
+            let _ = event_tx.send(DevnetEvent::warning(format!(
+                "Only {started_signers} of {} signers started: Nakamoto blocks need signatures from 70% of the stacked weight",
+                signers.len()
+            )));
+        }
 
-        // Start stacks-signer-2
-        let _ = event_tx.send(DevnetEvent::info("Starting stacks-signer-2".to_string()));
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-2",
-            Status::Yellow,
-            "updating image",
-        );
-        match self
-            .prepare_stacks_signer_container(boot_index, ctx, 2)
-            .await
-        {
-            Ok(_) => {}
-            Err(message) => {
-                let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
-                self.kill(ctx, Some(&message)).await;
-                return Err(message);
-            }
-        };
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-2",
-            Status::Yellow,
-            "booting",
-        );
-        match self.boot_stacks_signer_container(2).await {
-            Ok(_) => {}
-            Err(message) => {
-                let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
-                self.kill(ctx, Some(&message)).await;
-                return Err(message);
-            }
-        };
-        send_status_update(
-            &event_tx,
-            enable_subnet_node,
-            "stacks-signer-2",
-            Status::Green,
-            "running",
-        );
+        // Start extra stacks-nodes (miners, then followers)
+        for (node_index, name) in extra_stacks_nodes_names.iter().enumerate() {
+            let _ = event_tx.send(DevnetEvent::info(format!("Starting {}", name)));
+            send_status_update(&event_tx, name, Status::Yellow, "preparing container");
+            match self
+                .prepare_extra_stacks_node_container(boot_index, node_index, ctx)
+                .await
+            {
+                Ok(_) => {}
+                Err(message) => {
+                    let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
+                    self.kill(ctx, Some(&message)).await;
+                    return Err(message);
+                }
+            };
+            send_status_update(&event_tx, name, Status::Yellow, "booting");
+            match self.boot_extra_stacks_node_container(node_index).await {
+                Ok(_) => {}
+                Err(message) => {
+                    let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
+                    self.kill(ctx, Some(&message)).await;
+                    return Err(message);
+                }
+            };
+        }
 
         // Start stacks-explorer
         if !disable_stacks_explorer {
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
                 "stacks-explorer",
                 Status::Yellow,
                 "preparing container",
@@ -690,7 +635,6 @@ impl DevnetOrchestrator {
             };
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
                 "stacks-explorer",
                 Status::Green,
                 &format!("http://localhost:{}", stacks_explorer_port),
@@ -701,7 +645,6 @@ impl DevnetOrchestrator {
         if !disable_bitcoin_explorer {
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
                 "bitcoin-explorer",
                 Status::Yellow,
                 "preparing container",
@@ -725,7 +668,6 @@ impl DevnetOrchestrator {
             };
             send_status_update(
                 &event_tx,
-                enable_subnet_node,
                 "bitcoin-explorer",
                 Status::Green,
                 &format!("http://localhost:{}", bitcoin_explorer_port),
@@ -740,32 +682,25 @@ impl DevnetOrchestrator {
                     break;
                 }
                 Ok(false) => {
-                    send_status_update(
-                        &event_tx,
-                        enable_subnet_node,
-                        "bitcoin-node",
-                        Status::Yellow,
-                        "restarting",
-                    );
-
-                    send_status_update(
-                        &event_tx,
-                        enable_subnet_node,
-                        "stacks-node",
-                        Status::Yellow,
-                        "restarting",
-                    );
+                    send_status_update(&event_tx, "bitcoin-node", Status::Yellow, "restarting");
+
+                    send_status_update(&event_tx, "stacks-node", Status::Yellow, "restarting");
+
+                    for name in extra_stacks_nodes_names.iter() {
+                        send_status_update(&event_tx, name, Status::Yellow, "restarting");
+                    }
 
                     let _ = event_tx.send(DevnetEvent::debug("Killing containers".into()));
                     let _ = self.stop_containers().await;
 
                     let _ = event_tx.send(DevnetEvent::debug("Restarting containers".into()));
-                    let (bitcoin_node_c_id, stacks_node_c_id) = self
+                    let (bitcoin_node_c_id, stacks_node_c_id, extra_stacks_node_c_ids) = self
                         .start_containers(boot_index)
                         .await
                         .map_err(|e| format!("unable to reboot: {:?}", e))?;
                     self.bitcoin_node_container_id = Some(bitcoin_node_c_id);
                     self.stacks_node_container_id = Some(stacks_node_c_id);
+                    self.extra_stacks_node_container_ids = extra_stacks_node_c_ids;
                 }
                 Err(_) => {
                     break;
@@ -1067,9 +1002,9 @@ microblock_frequency = 1000
 # inv_sync_interval = 10
 # download_interval = 10
 # walk_interval = 10
-disable_block_download = true
-disable_inbound_handshakes = true
-disable_inbound_walks = true
+disable_block_download = {isolated}
+disable_inbound_handshakes = {isolated}
+disable_inbound_walks = {isolated}
 public_ip_address = "1.1.1.1:1234"
 block_proposal_token = "12345"
 
@@ -1089,41 +1024,28 @@ mining_key = "19ec1c3e31d139c989a23a27eac60d1abfad5277d3ae9604242514c738258efa01
             first_attempt_time_ms = devnet_config.stacks_node_first_attempt_time_ms,
             subsequent_attempt_time_ms = devnet_config.stacks_node_subsequent_attempt_time_ms,
             miner_coinbase_recipient = devnet_config.miner_coinbase_recipient,
+            // peers can only sync from this node when it accepts inbound connections
+            isolated = devnet_config.extra_stacks_nodes.is_empty(),
         );
 
-        for (_, account) in network_config.accounts.iter() {
+        // add the started signers as event observers
+        for (i, signer) in devnet_config.signers.iter().enumerate() {
+            if !signer.start {
+                continue;
+            }
+            let signer_id = i as u32 + 1;
             stacks_conf.push_str(&format!(
                 r#"
-[[ustx_balance]]
-address = "{}"
-amount = {}
-"#,
-                account.stx_address, account.balance
-            ));
-        }
-
-        // add the 2 signers event observers
-        stacks_conf.push_str(&format!(
-            r#"
-[[events_observer]]
-endpoint = "stacks-signer-1.{}:30001"
-retry_count = 255
-include_data_events = false
-events_keys = ["stackerdb", "block_proposal", "burn_blocks"]
-"#,
-            self.network_name
-        ));
-
-        stacks_conf.push_str(&format!(
-            r#"
 [[events_observer]]
-endpoint = "stacks-signer-2.{}:30002"
+endpoint = "stacks-signer-{signer_id}.{network_name}:{signer_port}"
 retry_count = 255
 include_data_events = false
 events_keys = ["stackerdb", "block_proposal", "burn_blocks"]
 "#,
-            self.network_name
-        ));
+                network_name = self.network_name,
+                signer_port = signer_event_port(signer_id),
+            ));
+        }
 
         stacks_conf.push_str(&format!(
             r#"
@@ -1160,121 +1082,413 @@ endpoint = "subnet-node.{}:{}"
 retry_count = 255
 events_keys = ["*"]
 "#,
-                self.network_name, devnet_config.subnet_events_ingestion_port
-            ));
-        }
+                self.network_name, devnet_config.subnet_events_ingestion_port
+            ));
+        }
+
+        stacks_conf.push_str(&self.stacks_node_events_observers_config(devnet_config, true));
+
+        stacks_conf.push_str(&self.stacks_node_chain_config(network_config, devnet_config));
+
+        let mut stacks_conf_path = PathBuf::from(&devnet_config.working_dir);
+        stacks_conf_path.push("conf/Stacks.toml");
+        let mut file = File::create(stacks_conf_path)
+            .map_err(|e| format!("unable to create Stacks.toml: {:?}", e))?;
+        file.write_all(stacks_conf.as_bytes())
+            .map_err(|e| format!("unable to write Stacks.toml: {:?}", e))?;
+
+        let mut stacks_node_data_path = PathBuf::from(&devnet_config.working_dir);
+        stacks_node_data_path.push("data");
+        stacks_node_data_path.push(format!("{}", boot_index));
+        stacks_node_data_path.push("stacks");
+        fs::create_dir_all(stacks_node_data_path)
+            .map_err(|e| format!("unable to create stacks directory: {:?}", e))?;
+
+        let mut exposed_ports = HashMap::new();
+        exposed_ports.insert(
+            format!("{}/tcp", devnet_config.stacks_node_rpc_port),
+            HashMap::new(),
+        );
+        exposed_ports.insert(
+            format!("{}/tcp", devnet_config.stacks_node_p2p_port),
+            HashMap::new(),
+        );
+
+        let mut labels = HashMap::new();
+        labels.insert("project".to_string(), self.network_name.to_string());
+        labels.insert("reset".to_string(), "true".to_string());
+
+        let mut binds = vec![format!(
+            "{}/conf:/src/stacks-node/",
+            devnet_config.working_dir
+        )];
+
+        if devnet_config.bind_containers_volumes {
+            binds.push(format!(
+                "{}/data/{}/stacks:/devnet/",
+                devnet_config.working_dir, boot_index
+            ))
+        }
+
+        let mut env = vec![
+            "STACKS_LOG_PP=1".to_string(),
+            "BLOCKSTACK_USE_TEST_GENESIS_CHAINSTATE=1".to_string(),
+        ];
+        env.append(&mut devnet_config.stacks_node_env_vars.clone());
+
+        let config = Config {
+            labels: Some(labels),
+            image: Some(devnet_config.stacks_node_image_url.clone()),
+            // domainname: Some(self.network_name.to_string()),
+            tty: None,
+            exposed_ports: Some(exposed_ports),
+            entrypoint: Some(vec![
+                "stacks-node".into(),
+                "start".into(),
+                "--config".into(),
+                "/src/stacks-node/Stacks.toml".into(),
+            ]),
+            env: Some(env),
+            host_config: Some(HostConfig {
+                auto_remove: Some(true),
+                binds: Some(binds),
+                network_mode: Some(self.network_name.clone()),
+                port_bindings: Some(port_bindings),
+                extra_hosts: Some(vec!["host.docker.internal:host-gateway".into()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        Ok(config)
+    }
+
+    fn stacks_node_chain_config(
+        &self,
+        network_config: &NetworkManifest,
+        devnet_config: &DevnetConfig,
+    ) -> String {
+        // genesis balances and burnchain settings must be identical across all the stacks-nodes
+        let mut stacks_conf = String::new();
+        for (_, account) in network_config.accounts.iter() {
+            stacks_conf.push_str(&format!(
+                r#"
+[[ustx_balance]]
+address = "{}"
+amount = {}
+"#,
+                account.stx_address, account.balance
+            ));
+        }
+
+        stacks_conf.push_str(&format!(
+            r#"
+[burnchain]
+chain = "bitcoin"
+mode = "{burnchain_mode}"
+magic_bytes = "T3"
+first_burn_block_height = 100
+pox_prepare_length = 5
+pox_reward_length = 20
+burn_fee_cap = 20_000
+poll_time_secs = 1
+timeout = 30
+peer_host = "host.docker.internal"
+rpc_ssl = false
+wallet_name = "{miner_wallet_name}"
+username = "{bitcoin_node_username}"
+password = "{bitcoin_node_password}"
+rpc_port = {orchestrator_ingestion_port}
+peer_port = {bitcoin_node_p2p_port}
+"#,
+            burnchain_mode = "nakamoto-neon",
+            bitcoin_node_username = devnet_config.bitcoin_node_username,
+            bitcoin_node_password = devnet_config.bitcoin_node_password,
+            bitcoin_node_p2p_port = devnet_config.bitcoin_node_p2p_port,
+            orchestrator_ingestion_port = devnet_config.orchestrator_ingestion_port,
+            miner_wallet_name = devnet_config.miner_wallet_name,
+        ));
+
+        stacks_conf.push_str(&format!(
+            r#"
+[[burnchain.epochs]]
+epoch_name = "1.0"
+start_height = 0
+
+[[burnchain.epochs]]
+epoch_name = "2.0"
+start_height = {epoch_2_0}
+
+[[burnchain.epochs]]
+epoch_name = "2.05"
+start_height = {epoch_2_05}
+
+[[burnchain.epochs]]
+epoch_name = "2.1"
+start_height = {epoch_2_1}
+
+[[burnchain.epochs]]
+epoch_name = "2.2"
+start_height = {epoch_2_2}
+
+[[burnchain.epochs]]
+epoch_name = "2.3"
+start_height = {epoch_2_3}
+
+[[burnchain.epochs]]
+epoch_name = "2.4"
+start_height = {epoch_2_4}
+
+[[burnchain.epochs]]
+epoch_name = "2.5"
+start_height = {epoch_2_5}
+
+[[burnchain.epochs]]
+epoch_name = "3.0"
+start_height = {epoch_3_0}
+"#,
+            epoch_2_0 = devnet_config.epoch_2_0,
+            epoch_2_05 = devnet_config.epoch_2_05,
+            epoch_2_1 = devnet_config.epoch_2_1,
+            epoch_2_2 = devnet_config.epoch_2_2,
+            epoch_2_3 = devnet_config.epoch_2_3,
+            epoch_2_4 = devnet_config.epoch_2_4,
+            epoch_2_5 = devnet_config.epoch_2_5,
+            epoch_3_0 = devnet_config.epoch_3_0,
+        ));
+
+        stacks_conf
+    }
+
+    pub async fn prepare_stacks_node_container(
+        &mut self,
+        boot_index: u32,
+        ctx: &Context,
+    ) -> Result<(), String> {
+        let (docker, devnet_config) = match (&self.docker_client, &self.network_config) {
+            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (docker, devnet_config),
+                _ => return Err("unable to get devnet configuration".into()),
+            },
+            _ => return Err("unable to get Docker client".into()),
+        };
+
+        let _info = docker
+            .create_image(
+                Some(CreateImageOptions {
+                    from_image: devnet_config.stacks_node_image_url.clone(),
+                    platform: devnet_config.docker_platform.clone(),
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| format!("unable to create image: {}", e))?;
+
+        let config = self.prepare_stacks_node_config(boot_index)?;
+
+        let options = CreateContainerOptions {
+            name: format!("stacks-node.{}", self.network_name),
+            platform: Some(devnet_config.docker_platform.to_string()),
+        };
+
+        let container = docker
+            .create_container::<String, String>(Some(options), config)
+            .await
+            .map_err(|e| format!("unable to create container: {}", e))?
+            .id;
+
+        ctx.try_log(|logger| slog::info!(logger, "Created container stacks-node: {}", container));
+        self.stacks_node_container_id = Some(container.clone());
+
+        Ok(())
+    }
+
+    pub async fn boot_stacks_node_container(&mut self) -> Result<(), String> {
+        let container = match &self.stacks_node_container_id {
+            Some(container) => container.clone(),
+            _ => return Err("unable to boot container".to_string()),
+        };
+
+        let docker = match &self.docker_client {
+            Some(ref docker) => docker,
+            _ => return Err("unable to get Docker client".into()),
+        };
+
+        docker
+            .start_container::<String>(&container, None)
+            .await
+            .map_err(|e| formatted_docker_error("unable to start stacks-node container", e))?;
+
+        Ok(())
+    }
+
+    /// The stacks nodes post their events to `stacks_node_events_observers`, unless
+    /// a `delay_events` fault is scheduled: the events proxy then relays them.
+    /// When it only records them, the proxy receives the events of the main node.
+    fn stacks_node_events_observers_config(
+        &self,
+        devnet_config: &DevnetConfig,
+        is_main_node: bool,
+    ) -> String {
+        let mut config = String::new();
+        if !devnet_config.relays_events_through_proxy() {
+            for events_observer in devnet_config.stacks_node_events_observers.iter() {
+                config.push_str(&format!(
+                    r#"
+[[events_observer]]
+endpoint = "{}"
+retry_count = 255
+events_keys = ["*"]
+"#,
+                    events_observer,
+                ));
+            }
+        }
+        let posts_to_proxy = devnet_config.relays_events_through_proxy()
+            || (is_main_node && devnet_config.record_events_path.is_some());
+        if posts_to_proxy {
+            config.push_str(&format!(
+                r#"
+# Add the events proxy (docker-host), relaying events to stacks_node_events_observers and recording them
+[[events_observer]]
+endpoint = "host.docker.internal:{}"
+retry_count = 255
+events_keys = ["*"]
+"#,
+                devnet_config.stacks_node_events_proxy_port
+            ));
+        }
+        config
+    }
+
+    pub fn prepare_extra_stacks_node_config(
+        &self,
+        boot_index: u32,
+        node_index: usize,
+    ) -> Result<Config<String>, String> {
+        let (network_config, devnet_config) = match &self.network_config {
+            Some(ref network_config) => match network_config.devnet {
+                Some(ref devnet_config) => (network_config, devnet_config),
+                _ => return Err("unable to get devnet configuration".into()),
+            },
+            _ => return Err("unable to get Docker client".into()),
+        };
+        let node = devnet_config
+            .extra_stacks_nodes
+            .get(node_index)
+            .ok_or(format!("unknown extra stacks-node ({})", node_index))?;
+
+        let mut port_bindings = HashMap::new();
+        port_bindings.insert(
+            format!("{}/tcp", node.p2p_port),
+            Some(vec![PortBinding {
+                host_ip: Some(String::from("0.0.0.0")),
+                host_port: Some(format!("{}/tcp", node.p2p_port)),
+            }]),
+        );
+        port_bindings.insert(
+            format!("{}/tcp", node.rpc_port),
+            Some(vec![PortBinding {
+                host_ip: Some(String::from("0.0.0.0")),
+                host_port: Some(format!("{}/tcp", node.rpc_port)),
+            }]),
+        );
+
+        // the main stacks-node uses the miner key as its peer seed
+        let bootstrap_node_public_key =
+            StacksPrivateKey::from_hex(&devnet_config.miner_secret_key_hex)
+                .map(|private_key| StacksPublicKey::from_private(&private_key).to_hex())
+                .map_err(|e| format!("unable to compute stacks-node public key: {}", e))?;
+
+        let mut stacks_conf = format!(
+            r#"
+[node]
+working_dir = "/devnet"
+rpc_bind = "0.0.0.0:{rpc_port}"
+p2p_bind = "0.0.0.0:{p2p_port}"
+data_url = "http://{name}.{network_name}:{rpc_port}"
+p2p_address = "{name}.{network_name}:{p2p_port}"
+bootstrap_node = "{bootstrap_node_public_key}@stacks-node.{network_name}:{stacks_node_p2p_port}"
+miner = {is_miner}
+seed = "{secret_key_hex}"
+local_peer_seed = "{secret_key_hex}"
+pox_sync_sample_secs = 0
+wait_time_for_blocks = 0
+wait_time_for_microblocks = 0
+next_initiative_delay = 4000
+mine_microblocks = false
+microblock_frequency = 1000
+
+[connection_options]
+public_ip_address = "1.1.1.1:1234"
+block_proposal_token = "12345"
+"#,
+            name = node.name,
+            network_name = self.network_name,
+            rpc_port = node.rpc_port,
+            p2p_port = node.p2p_port,
+            stacks_node_p2p_port = devnet_config.stacks_node_p2p_port,
+            is_miner = node.is_miner,
+            secret_key_hex = node.secret_key_hex,
+        );
 
-        for chains_coordinator in devnet_config.stacks_node_events_observers.iter() {
+        if node.is_miner {
             stacks_conf.push_str(&format!(
                 r#"
-[[events_observer]]
-endpoint = "{}"
-retry_count = 255
-events_keys = ["*"]
+[miner]
+min_tx_fee = 1
+first_attempt_time_ms = {first_attempt_time_ms}
+second_attempt_time_ms = {subsequent_attempt_time_ms}
+block_reward_recipient = "{stx_address}"
+wait_for_block_download = false
+microblock_attempt_time_ms = 10
+mining_key = "{secret_key_hex}"
 "#,
-                chains_coordinator,
+                first_attempt_time_ms = devnet_config.stacks_node_first_attempt_time_ms,
+                subsequent_attempt_time_ms = devnet_config.stacks_node_subsequent_attempt_time_ms,
+                stx_address = node.stx_address,
+                secret_key_hex = node.secret_key_hex,
             ));
         }
 
+        // the orchestrator ignores the blocks it already received from the main node,
+        // and picks up the forks mined by the extra miners
         stacks_conf.push_str(&format!(
             r#"
-[burnchain]
-chain = "bitcoin"
-mode = "{burnchain_mode}"
-magic_bytes = "T3"
-first_burn_block_height = 100
-pox_prepare_length = 5
-pox_reward_length = 20
-burn_fee_cap = 20_000
-poll_time_secs = 1
-timeout = 30
-peer_host = "host.docker.internal"
-rpc_ssl = false
-wallet_name = "{miner_wallet_name}"
-username = "{bitcoin_node_username}"
-password = "{bitcoin_node_password}"
-rpc_port = {orchestrator_ingestion_port}
-peer_port = {bitcoin_node_p2p_port}
+# Add orchestrator (docker-host) as an event observer
+[[events_observer]]
+endpoint = "host.docker.internal:{orchestrator_ingestion_port}"
+retry_count = 255
+include_data_events = true
+events_keys = ["*"]
 "#,
-            burnchain_mode = "nakamoto-neon",
-            bitcoin_node_username = devnet_config.bitcoin_node_username,
-            bitcoin_node_password = devnet_config.bitcoin_node_password,
-            bitcoin_node_p2p_port = devnet_config.bitcoin_node_p2p_port,
             orchestrator_ingestion_port = devnet_config.orchestrator_ingestion_port,
-            miner_wallet_name = devnet_config.miner_wallet_name,
         ));
 
-        stacks_conf.push_str(&format!(
-            r#"
-[[burnchain.epochs]]
-epoch_name = "1.0"
-start_height = 0
-
-[[burnchain.epochs]]
-epoch_name = "2.0"
-start_height = {epoch_2_0}
-
-[[burnchain.epochs]]
-epoch_name = "2.05"
-start_height = {epoch_2_05}
-
-[[burnchain.epochs]]
-epoch_name = "2.1"
-start_height = {epoch_2_1}
-
-[[burnchain.epochs]]
-epoch_name = "2.2"
-start_height = {epoch_2_2}
-
-[[burnchain.epochs]]
-epoch_name = "2.3"
-start_height = {epoch_2_3}
-
-[[burnchain.epochs]]
-epoch_name = "2.4"
-start_height = {epoch_2_4}
-
-[[burnchain.epochs]]
-epoch_name = "2.5"
-start_height = {epoch_2_5}
+        stacks_conf.push_str(&self.stacks_node_events_observers_config(devnet_config, false));
 
-[[burnchain.epochs]]
-epoch_name = "3.0"
-start_height = {epoch_3_0}
-"#,
-            epoch_2_0 = devnet_config.epoch_2_0,
-            epoch_2_05 = devnet_config.epoch_2_05,
-            epoch_2_1 = devnet_config.epoch_2_1,
-            epoch_2_2 = devnet_config.epoch_2_2,
-            epoch_2_3 = devnet_config.epoch_2_3,
-            epoch_2_4 = devnet_config.epoch_2_4,
-            epoch_2_5 = devnet_config.epoch_2_5,
-            epoch_3_0 = devnet_config.epoch_3_0,
-        ));
+        stacks_conf.push_str(&self.stacks_node_chain_config(network_config, devnet_config));
 
         let mut stacks_conf_path = PathBuf::from(&devnet_config.working_dir);
-        stacks_conf_path.push("conf/Stacks.toml");
+        stacks_conf_path.push(format!("conf/{}.toml", node.name));
         let mut file = File::create(stacks_conf_path)
-            .map_err(|e| format!("unable to create Stacks.toml: {:?}", e))?;
+            .map_err(|e| format!("unable to create {}.toml: {:?}", node.name, e))?;
         file.write_all(stacks_conf.as_bytes())
-            .map_err(|e| format!("unable to write Stacks.toml: {:?}", e))?;
+            .map_err(|e| format!("unable to write {}.toml: {:?}", node.name, e))?;
 
         let mut stacks_node_data_path = PathBuf::from(&devnet_config.working_dir);
         stacks_node_data_path.push("data");
         stacks_node_data_path.push(format!("{}", boot_index));
-        stacks_node_data_path.push("stacks");
+        stacks_node_data_path.push(&node.name);
         fs::create_dir_all(stacks_node_data_path)
-            .map_err(|e| format!("unable to create stacks directory: {:?}", e))?;
+            .map_err(|e| format!("unable to create {} directory: {:?}", node.name, e))?;
 
         let mut exposed_ports = HashMap::new();
-        exposed_ports.insert(
-            format!("{}/tcp", devnet_config.stacks_node_rpc_port),
-            HashMap::new(),
-        );
-        exposed_ports.insert(
-            format!("{}/tcp", devnet_config.stacks_node_p2p_port),
-            HashMap::new(),
-        );
+        exposed_ports.insert(format!("{}/tcp", node.rpc_port), HashMap::new());
+        exposed_ports.insert(format!("{}/tcp", node.p2p_port), HashMap::new());
 
         let mut labels = HashMap::new();
         labels.insert("project".to_string(), self.network_name.to_string());
@@ -1287,8 +1501,8 @@ start_height = {epoch_3_0}
 
         if devnet_config.bind_containers_volumes {
             binds.push(format!(
-                "{}/data/{}/stacks:/devnet/",
-                devnet_config.working_dir, boot_index
+                "{}/data/{}/{}:/devnet/",
+                devnet_config.working_dir, boot_index, node.name
             ))
         }
 
@@ -1301,14 +1515,13 @@ start_height = {epoch_3_0}
         let config = Config {
             labels: Some(labels),
             image: Some(devnet_config.stacks_node_image_url.clone()),
-            // domainname: Some(self.network_name.to_string()),
             tty: None,
             exposed_ports: Some(exposed_ports),
             entrypoint: Some(vec![
                 "stacks-node".into(),
                 "start".into(),
                 "--config".into(),
-                "/src/stacks-node/Stacks.toml".into(),
+                format!("/src/stacks-node/{}.toml", node.name),
             ]),
             env: Some(env),
             host_config: Some(HostConfig {
@@ -1325,9 +1538,10 @@ start_height = {epoch_3_0}
         Ok(config)
     }
 
-    pub async fn prepare_stacks_node_container(
+    pub async fn prepare_extra_stacks_node_container(
         &mut self,
         boot_index: u32,
+        node_index: usize,
         ctx: &Context,
     ) -> Result<(), String> {
         let (docker, devnet_config) = match (&self.docker_client, &self.network_config) {
@@ -1337,25 +1551,16 @@ start_height = {epoch_3_0}
             },
             _ => return Err("unable to get Docker client".into()),
         };
+        let name = match devnet_config.extra_stacks_nodes.get(node_index) {
+            Some(node) => node.name.clone(),
+            None => return Err(format!("unknown extra stacks-node ({})", node_index)),
+        };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.stacks_node_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
-            )
-            .try_collect::<Vec<_>>()
-            .await
-            .map_err(|e| format!("unable to create image: {}", e))?;
-
-        let config = self.prepare_stacks_node_config(boot_index)?;
+        // the image was already pulled when preparing the main stacks-node
+        let config = self.prepare_extra_stacks_node_config(boot_index, node_index)?;
 
         let options = CreateContainerOptions {
-            name: format!("stacks-node.{}", self.network_name),
+            name: format!("{}.{}", name, self.network_name),
             platform: Some(devnet_config.docker_platform.to_string()),
         };
 
@@ -1365,14 +1570,17 @@ start_height = {epoch_3_0}
             .map_err(|e| format!("unable to create container: {}", e))?
             .id;
 
-        ctx.try_log(|logger| slog::info!(logger, "Created container stacks-node: {}", container));
-        self.stacks_node_container_id = Some(container.clone());
+        ctx.try_log(|logger| slog::info!(logger, "Created container {}: {}", name, container));
+        self.extra_stacks_node_container_ids.push(container);
 
         Ok(())
     }
 
-    pub async fn boot_stacks_node_container(&mut self) -> Result<(), String> {
-        let container = match &self.stacks_node_container_id {
+    pub async fn boot_extra_stacks_node_container(
+        &mut self,
+        node_index: usize,
+    ) -> Result<(), String> {
+        let container = match self.extra_stacks_node_container_ids.get(node_index) {
             Some(container) => container.clone(),
             _ => return Err("unable to boot container".to_string()),
         };
@@ -1403,24 +1611,23 @@ start_height = {epoch_3_0}
             _ => return Err("unable to initialize bitcoin node".to_string()),
         };
 
-        // the default wallet_1 and wallet_2 are the default signers
-        let default_signing_keys = [
-            "7287ba251d44a4d3fd9276c88ce34c5c52a038955511cccaf77e61068649c17801",
-            "530d9f61984c888536871c6573073bdfc0058896dc1adfe9a6a10dfacadc209101",
-        ];
+        let signer = devnet_config
+            .signers
+            .get((signer_id as usize).wrapping_sub(1))
+            .ok_or(format!("invalid signer_id ({})", signer_id))?;
 
         let signer_conf = format!(
             r#"
 stacks_private_key = "{signer_private_key}"
 node_host = "stacks-node.{network_name}:{stacks_node_rpc_port}" # eg "127.0.0.1:20443"
 # must be added as event_observer in node config:
-endpoint = "0.0.0.0:3000{signer_id}"
+endpoint = "0.0.0.0:{signer_port}"
 network = "testnet"
 auth_password = "12345"
 db_path = "stacks-signer-{signer_id}.sqlite"
 "#,
-            signer_private_key = default_signing_keys[(signer_id - 1) as usize],
-            // signer_private_key = devnet_config.signer_private_key,
+            signer_private_key = signer.secret_key_hex,
+            signer_port = signer_event_port(signer_id),
             network_name = self.network_name,
             stacks_node_rpc_port = devnet_config.stacks_node_rpc_port
         );
@@ -1529,27 +1736,16 @@ db_path = "stacks-signer-{signer_id}.sqlite"
                 container
             )
         });
-        if signer_id == 1 {
-            self.stacks_signer_1_container_id = Some(container.clone());
-        } else {
-            self.stacks_signer_2_container_id = Some(container.clone());
-        }
-        // self.stacks_signer_container_id = Some(container.clone());
+        self.stacks_signers_container_ids
+            .insert(signer_id, container.clone());
 
         Ok(())
     }
 
     pub async fn boot_stacks_signer_container(&mut self, signer_id: u32) -> Result<(), String> {
-        let container = match signer_id {
-            1 => match &self.stacks_signer_1_container_id {
-                Some(container) => container.clone(),
-                _ => return Err("unable to boot container".to_string()),
-            },
-            2 => match &self.stacks_signer_2_container_id {
-                Some(container) => container.clone(),
-                _ => return Err("unable to boot container".to_string()),
-            },
-            _ => return Err("invalid signer_id".to_string()),
+        let container = match self.stacks_signers_container_ids.get(&signer_id) {
+            Some(container) => container.clone(),
+            _ => return Err("unable to boot container".to_string()),
         };
 
         let docker = match &self.docker_client {
@@ -2439,23 +2635,19 @@ events_keys = ["*"]
     pub async fn stop_containers(&self) -> Result<(), String> {
         let containers_ids = match (
             &self.stacks_node_container_id,
-            &self.stacks_signer_1_container_id,
-            &self.stacks_signer_2_container_id,
             &self.stacks_api_container_id,
             &self.stacks_explorer_container_id,
             &self.bitcoin_node_container_id,
             &self.bitcoin_explorer_container_id,
             &self.postgres_container_id,
         ) {
-            (Some(c1), Some(c2), Some(c3), Some(c4), Some(c5), Some(c6), Some(c7), Some(c8)) => {
-                (c1, c2, c3, c4, c5, c6, c7, c8)
+            (Some(c1), Some(c2), Some(c3), Some(c4), Some(c5), Some(c6)) => {
+                (c1, c2, c3, c4, c5, c6)
             }
             _ => return Err("unable to boot container".to_string()),
         };
         let (
             stacks_node_c_id,
-            stacks_signer_1_c_id,
-            stacks_signer_2_c_id,
             stacks_api_c_id,
             stacks_explorer_c_id,
             bitcoin_node_c_id,
@@ -2474,13 +2666,17 @@ events_keys = ["*"]
             .kill_container(stacks_node_c_id, Some(options.clone()))
             .await;
 
-        let _ = docker
-            .kill_container(stacks_signer_1_c_id, Some(options.clone()))
-            .await;
+        for extra_stacks_node_c_id in self.extra_stacks_node_container_ids.iter() {
+            let _ = docker
+                .kill_container(extra_stacks_node_c_id, Some(options.clone()))
+                .await;
+        }
 
-        let _ = docker
-            .kill_container(stacks_signer_2_c_id, Some(options.clone()))
-            .await;
+        for stacks_signer_c_id in self.stacks_signers_container_ids.values() {
+            let _ = docker
+                .kill_container(stacks_signer_c_id, Some(options.clone()))
+                .await;
+        }
 
         let _ = docker
             .kill_container(stacks_api_c_id, Some(options.clone()))
@@ -2510,7 +2706,10 @@ events_keys = ["*"]
         Ok(())
     }
 
-    pub async fn start_containers(&self, boot_index: u32) -> Result<(String, String), String> {
+    pub async fn start_containers(
+        &self,
+        boot_index: u32,
+    ) -> Result<(String, String, Vec<String>), String> {
         let containers_ids = match (
             &self.stacks_api_container_id,
             &self.stacks_explorer_container_id,
@@ -2567,7 +2766,7 @@ events_keys = ["*"]
 
         let options = CreateContainerOptions {
             name: format!("stacks-node.{}", self.network_name),
-            platform,
+            platform: platform.clone(),
         };
         let stacks_node_c_id = docker
             .create_container::<String, String>(Some(options), stacks_node_config)
@@ -2575,6 +2774,33 @@ events_keys = ["*"]
             .map_err(|e| format!("unable to create container: {}", e))?
             .id;
 
+        let extra_stacks_nodes_names = self
+            .network_config
+            .as_ref()
+            .and_then(|c| c.devnet.as_ref())
+            .map(|c| {
+                c.extra_stacks_nodes
+                    .iter()
+                    .map(|node| node.name.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let mut extra_stacks_node_c_ids = vec![];
+        for (node_index, name) in extra_stacks_nodes_names.iter().enumerate() {
+            let extra_stacks_node_config =
+                self.prepare_extra_stacks_node_config(boot_index, node_index)?;
+            let options = CreateContainerOptions {
+                name: format!("{}.{}", name, self.network_name),
+                platform: platform.clone(),
+            };
+            let extra_stacks_node_c_id = docker
+                .create_container::<String, String>(Some(options), extra_stacks_node_config)
+                .await
+                .map_err(|e| format!("unable to create container: {}", e))?
+                .id;
+            extra_stacks_node_c_ids.push(extra_stacks_node_c_id);
+        }
+
         // Start all the containers
         let _ = docker
             .start_container::<String>(&bitcoin_node_c_id, None)
@@ -2598,7 +2824,13 @@ events_keys = ["*"]
             .start_container::<String>(&stacks_node_c_id, None)
             .await;
 
-        Ok((bitcoin_node_c_id, stacks_node_c_id))
+        for extra_stacks_node_c_id in extra_stacks_node_c_ids.iter() {
+            let _ = docker
+                .start_container::<String>(extra_stacks_node_c_id, None)
+                .await;
+        }
+
+        Ok((bitcoin_node_c_id, stacks_node_c_id, extra_stacks_node_c_ids))
     }
 
     pub async fn kill(&self, ctx: &Context, fatal_message: Option<&str>) {
@@ -2612,18 +2844,28 @@ events_keys = ["*"]
         let options = Some(KillContainerOptions { signal: "SIGKILL" });
 
         // Terminate containers
-        let container_ids = vec![
+        let mut container_ids = vec![
             self.bitcoin_explorer_container_id.clone(),
             self.stacks_explorer_container_id.clone(),
             self.bitcoin_node_container_id.clone(),
             self.stacks_api_container_id.clone(),
             self.postgres_container_id.clone(),
             self.stacks_node_container_id.clone(),
-            self.stacks_signer_1_container_id.clone(),
-            self.stacks_signer_2_container_id.clone(),
             self.subnet_node_container_id.clone(),
             self.subnet_api_container_id.clone(),
         ];
+        container_ids.extend(
+            self.extra_stacks_node_container_ids
+                .iter()
+                .cloned()
+                .map(Some),
+        );
+        container_ids.extend(
+            self.stacks_signers_container_ids
+                .values()
+                .cloned()
+                .map(Some),
+        );
 
         for container_id in container_ids.into_iter().flatten() {
             let _ = docker.kill_container(&container_id, options.clone()).await;
@@ -2701,6 +2943,17 @@ events_keys = ["*"]
         let faucet_address = Address::from_str(&devnet_config.faucet_btc_address)
             .map_err(|e| format!("unable to create faucet address: {:?}", e))?;
 
+        let mut extra_miners_addresses = vec![];
+        for node in devnet_config
+            .extra_stacks_nodes
+            .iter()
+            .filter(|n| n.is_miner)
+        {
+            let address = Address::from_str(&node.btc_address)
+                .map_err(|e| format!("unable to create {} address: {:?}", node.name, e))?;
+            extra_miners_addresses.push(address);
+        }
+
         let bitcoin_node_url = format!(
             "http://{}/",
             self.services_map_hosts.as_ref().unwrap().bitcoin_node_host
@@ -2755,100 +3008,81 @@ events_keys = ["*"]
             let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
         }
 
-        let mut error_count = 0;
-        loop {
-            let rpc_call = base_builder(
-                &bitcoin_node_url,
-                &devnet_config.bitcoin_node_username,
-                &devnet_config.bitcoin_node_password,
-            )
-            .json(&json!({
-                "jsonrpc": "1.0",
-                "id": "stacks-network",
-                "method": "generatetoaddress",
-                "params": [json!(3), json!(miner_address)]
-            }))
-            .send()
-            .await
-            .map_err(|e| format!("unable to send 'generatetoaddress' request ({})", e));
-
-            match rpc_call {
-                Ok(_r) => break,
-                Err(e) => {
-                    error_count += 1;
-                    if error_count > max_errors {
-                        return Err(e);
-                    } else if error_count > 1 {
-                        let _ = devnet_event_tx.send(DevnetEvent::error(e));
-                    }
-                }
-            }
-            std::thread::sleep(std::time::Duration::from_secs(1));
-            let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
+        // The first blocks go to the miner. Each extra miner gets one coinbase, taken from
+        // the blocks usually sent to the faucet, so that the chain height at the end of the
+        // initialization remains unchanged.
+        let mut initial_blocks = vec![(3, miner_address.to_string())];
+        for address in extra_miners_addresses.iter() {
+            initial_blocks.push((1, address.to_string()));
         }
-
-        let mut error_count = 0;
-        loop {
-            let rpc_call = base_builder(
-                &bitcoin_node_url,
-                &devnet_config.bitcoin_node_username,
-                &devnet_config.bitcoin_node_password,
-            )
-            .json(&json!({
-                "jsonrpc": "1.0",
-                "id": "stacks-network",
-                "method": "generatetoaddress",
-                "params": [json!(97), json!(faucet_address)]
-            }))
-            .send()
-            .await
-            .map_err(|e| format!("unable to send 'generatetoaddress' request ({})", e));
-
-            match rpc_call {
-                Ok(_r) => break,
-                Err(e) => {
-                    error_count += 1;
-                    if error_count > max_errors {
-                        return Err(e);
-                    } else if error_count > 1 {
-                        let _ = devnet_event_tx.send(DevnetEvent::error(e));
-                    }
+        initial_blocks.push((
+            97usize.saturating_sub(extra_miners_addresses.len()),
+            faucet_address.to_string(),
+        ));
+        initial_blocks.push((1, miner_address.to_string()));
+
+        let accelerated_mining = devnet_config.bitcoin_controller_accelerated_mining;
+        let mut height = 0;
+        for (count, address) in initial_blocks {
+            // With accelerated mining, the blocks are mined one by one, bitcoind's clock
+            // being mocked before each of them to get deterministic timestamps
+            let batches = if accelerated_mining {
+                vec![1; count]
+            } else {
+                vec![count]
+            };
+            for batch in batches {
+                height += batch as u64;
+                if accelerated_mining {
+                    let _ = base_builder(
+                        &bitcoin_node_url,
+                        &devnet_config.bitcoin_node_username,
+                        &devnet_config.bitcoin_node_password,
+                    )
+                    .json(&json!({
+                        "jsonrpc": "1.0",
+                        "id": "stacks-network",
+                        "method": "setmocktime",
+                        "params": [json!(accelerated_mining_timestamp(height))]
+                    }))
+                    .send()
+                    .await
+                    .map_err(|e| format!("unable to send 'setmocktime' request ({})", e))?;
                 }
-            }
-            std::thread::sleep(std::time::Duration::from_secs(1));
-            let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
-        }
-
-        let mut error_count = 0;
-        loop {
-            let rpc_call = base_builder(
-                &bitcoin_node_url,
-                &devnet_config.bitcoin_node_username,
-                &devnet_config.bitcoin_node_password,
-            )
-            .json(&json!({
-            "jsonrpc": "1.0",
-            "id": "stacks-network",
-            "method": "generatetoaddress",
-            "params": [json!(1), json!(miner_address)]
-            }))
-            .send()
-            .await
-            .map_err(|e| format!("unable to send 'generatetoaddress' request ({})", e));
 
-            match rpc_call {
-                Ok(_r) => break,
-                Err(e) => {
-                    error_count += 1;
-                    if error_count > max_errors {
-                        return Err(e);
-                    } else if error_count > 1 {
-                        let _ = devnet_event_tx.send(DevnetEvent::error(e));
+                let mut error_count = 0;
+                loop {
+                    let rpc_call = base_builder(
+                        &bitcoin_node_url,
+                        &devnet_config.bitcoin_node_username,
+                        &devnet_config.bitcoin_node_password,
+                    )
+                    .json(&json!({
+                        "jsonrpc": "1.0",
+                        "id": "stacks-network",
+                        "method": "generatetoaddress",
+                        "params": [json!(batch), json!(address)]
+                    }))
+                    .send()
+                    .await
+                    .map_err(|e| format!("unable to send 'generatetoaddress' request ({})", e));
+
+                    match rpc_call {
+                        Ok(_r) => break,
+                        Err(e) => {
+                            error_count += 1;
+                            if error_count > max_errors {
+                                return Err(e);
+                            } else if error_count > 1 {
+                                let _ = devnet_event_tx.send(DevnetEvent::error(e));
+                            }
+                        }
                     }
+                    std::thread::sleep(std::time::Duration::from_secs(1));
+                    let _ = devnet_event_tx
+                        .send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
                 }
             }
-            std::thread::sleep(std::time::Duration::from_secs(1));
-            let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
         }
 
         let mut error_count = 0;
@@ -3044,11 +3278,16 @@ events_keys = ["*"]
             std::thread::sleep(std::time::Duration::from_secs(1));
             let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
         }
-        // Index devnet's wallets by default
+        // Index devnet's wallets and extra miners by default
+        let mut indexed_addresses = vec![];
         for (_, account) in accounts.iter() {
             let address = Address::from_str(&account.btc_address)
                 .map_err(|e| format!("unable to create address: {:?}", e))?;
+            indexed_addresses.push(address);
+        }
+        indexed_addresses.extend(extra_miners_addresses);
 
+        for address in indexed_addresses.iter() {
             let mut error_count = 0;
             loop {
                 let descriptor = format!("addr({})", address);
@@ -3131,6 +3370,10 @@ events_keys = ["*"]
     }
 }
 
+fn signer_event_port(signer_id: u32) -> u32 {
+    30000 + signer_id
+}
+
 fn formatted_docker_error(message: &str, error: DockerError) -> String {
     let error = match &error {
         DockerError::DockerResponseServerError {
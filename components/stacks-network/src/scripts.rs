@@ -0,0 +1,322 @@
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+
+use clarinet_files::ExecuteScript;
+
+use crate::chains_coordinator::DevnetEventObserverConfig;
+use crate::event::DevnetEvent;
+
+/// Runs the `execute_script` hooks of Devnet.toml, with the devnet urls
+/// (and, when allowed, the wallets mnemonics) exposed in their environment.
+#[derive(Clone, Debug)]
+pub struct ScriptsRunner {
+    scripts: Vec<ExecuteScript>,
+    working_dir: String,
+    env: Vec<(String, String)>,
+    wallets_env: Vec<(String, String)>,
+}
+
+impl ScriptsRunner {
+    pub fn new(config: &DevnetEventObserverConfig) -> Result<ScriptsRunner, String> {
+        let working_dir = config
+            .manifest
+            .location
+            .get_project_root_location()?
+            .to_string();
+
+        let hosts = &config.services_map_hosts;
+        let mut env = vec![
+            (
+                "STACKS_NODE_URL".to_string(),
+                config.consolidated_stacks_rpc_url(),
+            ),
+            (
+                "BITCOIN_NODE_URL".to_string(),
+                config.consolidated_bitcoin_rpc_url(),
+            ),
+            (
+                "BITCOIN_NODE_USERNAME".to_string(),
+                config.devnet_config.bitcoin_node_username.clone(),
+            ),
+            (
+                "BITCOIN_NODE_PASSWORD".to_string(),
+                config.devnet_config.bitcoin_node_password.clone(),
+            ),
+            (
+                "STACKS_API_URL".to_string(),
+                format!("http://{}", hosts.stacks_api_host),
+            ),
+            (
+                "STACKS_EXPLORER_URL".to_string(),
+                format!("http://{}", hosts.stacks_explorer_host),
+            ),
+            (
+                "BITCOIN_EXPLORER_URL".to_string(),
+                format!("http://{}", hosts.bitcoin_explorer_host),
+            ),
+        ];
+        let mut wallets_env = vec![];
+        for account in config.accounts.iter() {
+            let prefix = format!("DEVNET_{}", account.label.to_uppercase().replace('-', "_"));
+            env.push((
+                format!("{}_STX_ADDRESS", prefix),
+                account.stx_address.clone(),
+            ));
+            env.push((
+                format!("{}_BTC_ADDRESS", prefix),
+                account.btc_address.clone(),
+            ));
+            wallets_env.push((format!("{}_MNEMONIC", prefix), account.mnemonic.clone()));
+            wallets_env.push((
+                format!("{}_DERIVATION_PATH", prefix),
+                account.derivation.clone(),
+            ));
+        }
+
+        Ok(ScriptsRunner {
+            scripts: config.devnet_config.execute_script.clone(),
+            working_dir,
+            env,
+            wallets_env,
+        })
+    }
+
+    /// Run the scripts scheduled up to `bitcoin_block_height` that were not triggered yet
+    pub fn run_due_scripts(
+        &self,
+        bitcoin_block_height: u64,
+        triggered_scripts: &mut HashSet<usize>,
+        devnet_event_tx: &Sender<DevnetEvent>,
+    ) {
+        for script in take_due_scripts(&self.scripts, bitcoin_block_height, triggered_scripts) {
+            self.run_script(&script, devnet_event_tx);
+        }
+    }
+
+    /// Run the scripts without `at_block`, once the deployment plan is applied
+    pub fn run_post_deployment_scripts(&self, devnet_event_tx: &Sender<DevnetEvent>) {
+        for script in self.scripts.iter().filter(|s| s.at_block.is_none()) {
+            self.run_script(script, devnet_event_tx);
+        }
+    }
+
+    /// Run a script in a background thread, streaming its output to the devnet logs
+    pub fn run_script(&self, script: &ExecuteScript, devnet_event_tx: &Sender<DevnetEvent>) {
+        let script = script.clone();
+        let runner = self.clone();
+        let devnet_event_tx = devnet_event_tx.clone();
+        let _ = hiro_system_kit::thread_named("Execute script").spawn(move || {
+            let critical = script.critical.unwrap_or(false);
+            if let Err(message) = runner.execute(&script, &devnet_event_tx) {
+                let message = format!("script `{}` failed: {}", script.script, message);
+                if critical {
+                    let _ = devnet_event_tx.send(DevnetEvent::FatalError(message));
+                } else {
+                    let _ = devnet_event_tx.send(DevnetEvent::error(message));
+                }
+            }
+        });
+    }
+
+    fn execute(
+        &self,
+        script: &ExecuteScript,
+        devnet_event_tx: &Sender<DevnetEvent>,
+    ) -> Result<(), String> {
+        let _ = devnet_event_tx.send(DevnetEvent::info(format!(
+            "Executing script `{}`",
+            script.script
+        )));
+
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        command
+            .arg(&script.script)
+            .current_dir(&self.working_dir)
+            .envs(self.env.iter().cloned())
+            // scripts are not sandboxed: allow_write is advisory, scripts are expected to honor it
+            .env("CLARINET_ALLOW_WRITE", script.allow_write.to_string())
+            .env("CLARINET_ALLOW_WALLETS", script.allow_wallets.to_string())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if script.allow_wallets {
+            command.envs(self.wallets_env.iter().cloned());
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("unable to spawn process ({})", e))?;
+
+        let stderr_relay = child.stderr.take().map(|stderr| {
+            let devnet_event_tx = devnet_event_tx.clone();
+            std::thread::spawn(move || relay_output(stderr, DevnetEvent::warning, &devnet_event_tx))
+        });
+        if let Some(stdout) = child.stdout.take() {
+            relay_output(stdout, DevnetEvent::info, devnet_event_tx);
+        }
+        if let Some(handle) = stderr_relay {
+            let _ = handle.join();
+        }
+
+        let status = child
+            .wait()
+            .map_err(|e| format!("unable to wait for process ({})", e))?;
+        if !status.success() {
+            return Err(format!("exited with {}", status));
+        }
+        let _ = devnet_event_tx.send(DevnetEvent::success(format!(
+            "Script `{}` executed",
+            script.script
+        )));
+        Ok(())
+    }
+}
+
+/// Scripts scheduled with `at_block` up to `bitcoin_block_height` that were not triggered yet
+pub fn take_due_scripts(
+    scripts: &[ExecuteScript],
+    bitcoin_block_height: u64,
+    triggered_scripts: &mut HashSet<usize>,
+) -> Vec<ExecuteScript> {
+    scripts
+        .iter()
+        .enumerate()
+        .filter(|(i, script)| match script.at_block {
+            Some(at_block) => at_block <= bitcoin_block_height && triggered_scripts.insert(*i),
+            None => false,
+        })
+        .map(|(_, script)| script.clone())
+        .collect()
+}
+
+fn relay_output<R: Read>(
+    output: R,
+    to_event: fn(String) -> DevnetEvent,
+    devnet_event_tx: &Sender<DevnetEvent>,
+) {
+    for line in BufReader::new(output).lines().map_while(Result::ok) {
+        let _ = devnet_event_tx.send(to_event(line));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::{LogData, LogLevel};
+    use std::sync::mpsc::{channel, Receiver};
+    use std::time::Duration;
+
+    fn script(script: &str, at_block: Option<u64>, critical: Option<bool>) -> ExecuteScript {
+        ExecuteScript {
+            script: script.to_string(),
+            allow_wallets: false,
+            allow_write: false,
+            at_block,
+            critical,
+        }
+    }
+
+    fn runner(scripts: Vec<ExecuteScript>) -> ScriptsRunner {
+        ScriptsRunner {
+            scripts,
+            working_dir: std::env::temp_dir().to_string_lossy().to_string(),
+            env: vec![("STACKS_NODE_URL".into(), "http://localhost:20443".into())],
+            wallets_env: vec![("DEVNET_DEPLOYER_MNEMONIC".into(), "twice kind".into())],
+        }
+    }
+
+    /// The last event sent by a script, once its thread is done
+    fn last_event(devnet_event_rx: &Receiver<DevnetEvent>) -> DevnetEvent {
+        let mut last_event = devnet_event_rx
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap();
+        while let Ok(event) = devnet_event_rx.recv_timeout(Duration::from_millis(500)) {
+            last_event = event;
+        }
+        last_event
+    }
+
+    #[test]
+    fn test_take_due_scripts() {
+        let scripts = vec![
+            script("echo post-deployment", None, None),
+            script("echo at 110", Some(110), None),
+            script("echo at 105", Some(105), None),
+        ];
+        let mut triggered_scripts = HashSet::new();
+
+        assert!(take_due_scripts(&scripts, 100, &mut triggered_scripts).is_empty());
+        let due = take_due_scripts(&scripts, 105, &mut triggered_scripts);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].script, "echo at 105");
+        // a script is only triggered once, even when blocks are skipped or reorged
+        let due = take_due_scripts(&scripts, 120, &mut triggered_scripts);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].script, "echo at 110");
+        assert!(take_due_scripts(&scripts, 108, &mut triggered_scripts).is_empty());
+        assert!(take_due_scripts(&scripts, 130, &mut triggered_scripts).is_empty());
+    }
+
+    #[test]
+    fn test_failing_critical_script_is_fatal() {
+        let (devnet_event_tx, devnet_event_rx) = channel();
+        let runner = runner(vec![]);
+
+        runner.run_script(&script("exit 3", None, Some(true)), &devnet_event_tx);
+        assert!(matches!(
+            last_event(&devnet_event_rx),
+            DevnetEvent::FatalError(_)
+        ));
+    }
+
+    #[test]
+    fn test_failing_script_is_an_error() {
+        let (devnet_event_tx, devnet_event_rx) = channel();
+        let runner = runner(vec![]);
+
+        runner.run_script(&script("exit 3", None, None), &devnet_event_tx);
+        assert!(matches!(
+            last_event(&devnet_event_rx),
+            DevnetEvent::Log(LogData {
+                level: LogLevel::Error,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_run_due_scripts() {
+        let (devnet_event_tx, devnet_event_rx) = channel();
+        let runner = runner(vec![
+            script("echo post-deployment", None, None),
+            script("echo scheduled", Some(105), Some(true)),
+        ]);
+        let mut triggered_scripts = HashSet::new();
+
+        runner.run_due_scripts(100, &mut triggered_scripts, &devnet_event_tx);
+        assert!(devnet_event_rx
+            .recv_timeout(Duration::from_millis(500))
+            .is_err());
+
+        runner.run_due_scripts(105, &mut triggered_scripts, &devnet_event_tx);
+        let mut messages = vec![];
+        while let Ok(DevnetEvent::Log(log)) = devnet_event_rx.recv_timeout(Duration::from_secs(5)) {
+            let done = matches!(log.level, LogLevel::Success);
+            messages.push(log.message);
+            if done {
+                break;
+            }
+        }
+        assert!(messages.contains(&"scheduled".to_string()));
+        assert!(!messages.contains(&"post-deployment".to_string()));
+    }
+}
@@ -76,12 +76,19 @@ impl<'a> App<'a> {
     }
 
     pub fn display_service_status_update(&mut self, service_update: ServiceStatusData) {
-        let insertion_index = service_update.order;
-        if insertion_index == self.services.items.len() {
-            self.services.items.push(service_update)
-        } else {
-            self.services.items.remove(insertion_index);
-            self.services.items.insert(insertion_index, service_update)
+        let services = &mut self.services.items;
+        match services
+            .iter()
+            .position(|service| service.name == service_update.name)
+        {
+            Some(index) => services[index] = service_update,
+            None => {
+                let insertion_index = services
+                    .iter()
+                    .position(|service| service.order > service_update.order)
+                    .unwrap_or(services.len());
+                services.insert(insertion_index, service_update)
+            }
         }
     }
 
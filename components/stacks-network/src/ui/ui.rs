@@ -24,11 +24,8 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         .constraints([Constraint::Min(0), Constraint::Length(78)].as_ref())
         .split(page_components[1]);
 
-    let nb_of_services = 5;
-    let nb_of_signers = 2;
-    let nb_of_subnet_services = if app.subnet_enabled { 2 } else { 0 };
-
-    let service_len = nb_of_services + nb_of_signers + nb_of_subnet_services + 2;
+    // the services list is as long as the number of services reported, plus borders
+    let service_len = app.services.items.len() as u16 + 2;
 
     let top_right_components = Layout::default()
         .direction(Direction::Vertical)